@@ -0,0 +1,66 @@
+#version 450
+
+layout(location = 0) in vec2 v_Uv;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 1, binding = 0) uniform ColorMaterial_color {
+    vec4 Color;
+};
+
+# ifdef COLORMATERIAL_TEXTURE
+layout(set = 1, binding = 1) uniform texture2D ColorMaterial_texture;
+layout(set = 1, binding = 2) uniform sampler ColorMaterial_texture_sampler;
+# endif
+
+layout(set = 2, binding = 2) uniform BlockStyle_top_color {
+    vec4 top_color;
+};
+layout(set = 2, binding = 3) uniform BlockStyle_bottom_color {
+    vec4 bottom_color;
+};
+layout(set = 2, binding = 4) uniform BlockStyle_glow {
+    float glow;
+};
+layout(set = 2, binding = 5) uniform BlockStyle_dissolve {
+    float dissolve;
+};
+
+// How wide the burning edge of a dissolve is
+const float DISSOLVE_EDGE = 0.08;
+const vec3 DISSOLVE_EDGE_COLOR = vec3(1.0, 0.6, 0.2);
+// How far in from the sides the glow reaches
+const float GLOW_WIDTH = 0.25;
+
+// Cheap noise, blocky on purpose so the dissolve eats the block in chunks
+float noise(vec2 uv) {
+    vec2 cell = floor(uv * 12.0);
+    return fract(sin(dot(cell, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main() {
+    vec4 color = Color;
+# ifdef COLORMATERIAL_TEXTURE
+    color *= texture(
+        sampler2D(ColorMaterial_texture, ColorMaterial_texture_sampler),
+        v_Uv);
+# endif
+
+    // The uv starts at the top of the sprite
+    color *= mix(top_color, bottom_color, v_Uv.y);
+
+    // Brightest at the edges, fading towards the middle
+    vec2 from_edge = min(v_Uv, 1.0 - v_Uv);
+    float edge = 1.0 - clamp(min(from_edge.x, from_edge.y) / GLOW_WIDTH, 0.0, 1.0);
+    color.rgb += glow * edge * color.rgb;
+
+    float burn = noise(v_Uv);
+    if (burn < dissolve) {
+        discard;
+    }
+    if (dissolve > 0.0 && burn < dissolve + DISSOLVE_EDGE) {
+        color.rgb = DISSOLVE_EDGE_COLOR;
+    }
+
+    o_Target = color;
+}
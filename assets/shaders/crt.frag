@@ -0,0 +1,54 @@
+#version 450
+
+// Draws the screen texture with the glow bleeding out of the bright parts
+// and the corners darkened like an old tube
+
+layout(location = 0) in vec2 v_Uv;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 1, binding = 0) uniform ColorMaterial_color {
+    vec4 Color;
+};
+
+layout(set = 1, binding = 1) uniform texture2D ColorMaterial_texture;
+layout(set = 1, binding = 2) uniform sampler ColorMaterial_texture_sampler;
+
+// Only brighter than this glows
+const float BLOOM_THRESHOLD = 0.7;
+const float BLOOM_STRENGTH = 0.8;
+// How far the glow spreads, in texels
+const float BLOOM_RADIUS = 3.0;
+// How dark the corners get, and where the darkening starts from the middle
+const float VIGNETTE_STRENGTH = 0.5;
+const float VIGNETTE_START = 0.4;
+
+vec3 bright(vec2 uv) {
+    vec3 color = texture(
+        sampler2D(ColorMaterial_texture, ColorMaterial_texture_sampler),
+        uv).rgb;
+    return max(color - BLOOM_THRESHOLD, 0.0);
+}
+
+void main() {
+    vec4 color = Color * texture(
+        sampler2D(ColorMaterial_texture, ColorMaterial_texture_sampler),
+        v_Uv);
+
+    // A small box blur of the bright parts, added back on top
+    vec2 texel = BLOOM_RADIUS / vec2(textureSize(
+        sampler2D(ColorMaterial_texture, ColorMaterial_texture_sampler), 0));
+    vec3 bloom = vec3(0.0);
+    for (int x = -2; x <= 2; x++) {
+        for (int y = -2; y <= 2; y++) {
+            bloom += bright(v_Uv + vec2(x, y) * texel);
+        }
+    }
+    color.rgb += bloom / 25.0 * BLOOM_STRENGTH;
+
+    float from_middle = length(v_Uv - 0.5);
+    float vignette = smoothstep(VIGNETTE_START, 0.75, from_middle) * VIGNETTE_STRENGTH;
+    color.rgb *= 1.0 - vignette;
+
+    o_Target = color;
+}
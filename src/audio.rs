@@ -0,0 +1,169 @@
+// AUDIO CODE
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy_kira_audio::{Audio, AudioChannel, AudioSource};
+
+use crate::logic::beat::Beat;
+use crate::logic::blocks::SpawnBlockEvent;
+use crate::logic::factions::CollisionEvent;
+use crate::logic::game_time::GameTime;
+use crate::logic::player::{PlayerHitEvent, TeleportEvent};
+use crate::logic::powerups::PowerUpPickedEvent;
+use crate::logic::projectiles::BulletDeflectedEvent;
+use crate::logic::stats::NearMissEvent;
+use crate::AppState;
+
+// A music track and its tempo, the tempo is used to sync gameplay to the beat
+pub struct MusicTrack {
+    pub path: &'static str,
+    pub bpm: f32,
+}
+
+const BACKGROUND_MUSIC: MusicTrack = MusicTrack {
+    path: "sounds/bg_music.mp3",
+    bpm: 120.0,
+};
+
+const TELEPORT_SOUND: &str = "sounds/teleport.wav";
+const BLOCK_SPAWN_SOUND: &str = "sounds/block_spawn.wav";
+const POWERUP_SOUND: &str = "sounds/powerup.wav";
+const PLAYER_DEATH_SOUND: &str = "sounds/player_death.wav";
+const CLANK_SOUND: &str = "sounds/clank.wav";
+const GRAZE_SOUND: &str = "sounds/graze.wav";
+const IMPACT_SOUND: &str = "sounds/impact.wav";
+
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Volume>()
+            .insert_resource(AudioChannels {
+                music: AudioChannel::new("music".to_string()),
+                sfx: AudioChannel::new("sfx".to_string()),
+            })
+            .init_resource::<Soundtrack>()
+            .add_system_set(SystemSet::on_enter(AppState::Loading).with_system(load_music.system()))
+            .add_system(start_music_when_loaded.system())
+            .add_system(apply_volume.system())
+            .add_system(play_sound_effects.system());
+    }
+}
+
+// Zero is silent and one is full volume. Anything can change these
+// and the channels pick the change up on the next frame.
+pub struct Volume {
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Volume {
+            music: 1.0,
+            sfx: 0.6,
+        }
+    }
+}
+
+// Music and sound effects are on their own channels so
+// their volumes can be set apart from each other
+struct AudioChannels {
+    music: AudioChannel,
+    sfx: AudioChannel,
+}
+
+// The music being loaded, and whether it has started yet
+#[derive(Default)]
+struct Soundtrack {
+    music: Option<Handle<AudioSource>>,
+    playing: bool,
+}
+
+// The asset server reads and decodes on its own task pool, so nothing waits
+// for the music. The loading screen only waits on the small assets and the
+// music comes in whenever it is ready.
+//
+// NOTE
+// The music is still decoded whole, bevy_kira_audio 0.6 can't stream.
+// It is kept as an mp3 so there is far less to read in the first place.
+fn load_music(asset_server: Res<AssetServer>, mut soundtrack: ResMut<Soundtrack>) {
+    soundtrack.music = Some(asset_server.load(BACKGROUND_MUSIC.path));
+}
+
+// The beat starts counting with the music so spawns stay in time with it
+fn start_music_when_loaded(
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    channels: Res<AudioChannels>,
+    time: Res<GameTime>,
+    mut soundtrack: ResMut<Soundtrack>,
+    mut beat: ResMut<Beat>,
+) {
+    if soundtrack.playing {
+        return;
+    }
+
+    let music = match &soundtrack.music {
+        Some(music) => music.clone(),
+        None => return,
+    };
+
+    match asset_server.get_load_state(&music) {
+        LoadState::Loaded => {
+            beat.bpm = BACKGROUND_MUSIC.bpm;
+            beat.started_at = time.seconds_since_startup();
+            audio.play_looped_in_channel(music, &channels.music);
+            soundtrack.playing = true;
+        }
+        LoadState::Failed => {
+            println!("Could not load the music: {}", BACKGROUND_MUSIC.path);
+            soundtrack.music = None;
+        }
+        _ => {}
+    }
+}
+
+fn apply_volume(volume: Res<Volume>, audio: Res<Audio>, channels: Res<AudioChannels>) {
+    if !volume.is_changed() {
+        return;
+    }
+
+    audio.set_volume_in_channel(volume.music, &channels.music);
+    audio.set_volume_in_channel(volume.sfx, &channels.sfx);
+}
+
+// Each sound plays at most once a frame, so a whole wave
+// spawning together doesn't play six spawn sounds on top of each other
+#[allow(clippy::too_many_arguments)]
+fn play_sound_effects(
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    channels: Res<AudioChannels>,
+    mut teleport_event: EventReader<TeleportEvent>,
+    mut spawn_event: EventReader<SpawnBlockEvent>,
+    mut powerup_event: EventReader<PowerUpPickedEvent>,
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    mut bullet_deflected_event: EventReader<BulletDeflectedEvent>,
+    mut near_miss_event: EventReader<NearMissEvent>,
+    mut collision_event: EventReader<CollisionEvent>,
+) {
+    let sounds = [
+        (teleport_event.iter().count() > 0, TELEPORT_SOUND),
+        (spawn_event.iter().count() > 0, BLOCK_SPAWN_SOUND),
+        (powerup_event.iter().count() > 0, POWERUP_SOUND),
+        (player_hit_event.iter().count() > 0, PLAYER_DEATH_SOUND),
+        (bullet_deflected_event.iter().count() > 0, CLANK_SOUND),
+        (near_miss_event.iter().count() > 0, GRAZE_SOUND),
+        (collision_event.iter().count() > 0, IMPACT_SOUND),
+    ];
+
+    // Some sounds stand in for others, they still only play once
+    let mut played: Vec<&str> = Vec::new();
+    for (happened, path) in sounds.iter() {
+        if *happened && !played.contains(path) {
+            audio.play_in_channel(asset_server.load(*path), &channels.sfx);
+            played.push(path);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// BLOCK STYLE CODE
+
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::pipeline::{CullMode, PipelineDescriptor, RenderPipeline, RenderPipelines};
+use bevy::render::render_graph::{base::node::MAIN_PASS, RenderGraph, RenderResourcesNode};
+use bevy::render::renderer::RenderResources;
+use bevy::render::shader::{Shader, ShaderStage, ShaderStages};
+
+use super::pixel_art::PIXEL_ART_PASS;
+use super::sprites::block_texture;
+use crate::logic::beat::Beat;
+use crate::logic::block_kinds::BlockKind;
+use crate::logic::blocks::{Block, BlockDestroyedEvent};
+use crate::logic::game_time::GameTime;
+use crate::logic::reset_game::RunScoped;
+use crate::{BLOCKSIZEX, BLOCKSIZEY};
+
+// How much brighter the edges of a block get right on the beat
+const GLOW_PEAK: f32 = 0.6;
+// How long a destroyed block takes to burn away
+const DISSOLVE_SECONDS: f32 = 0.4;
+
+pub const BLOCK_STYLE_NODE: &str = "block_style";
+const BLOCK_VERTEX_SHADER: &str = include_str!("../../assets/shaders/sprite.vert");
+const BLOCK_FRAGMENT_SHADER: &str = include_str!("../../assets/shaders/block.frag");
+
+const BLOCK_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0xb10c_5747_1e00_0744);
+
+pub struct BlockStylePlugin;
+
+impl Plugin for BlockStylePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // Nothing is drawn when running headless
+        let world = app.world_mut();
+        let shaders = world
+            .get_resource_mut::<Assets<Shader>>()
+            .map(|mut shaders| ShaderStages {
+                vertex: shaders.add(Shader::from_glsl(ShaderStage::Vertex, BLOCK_VERTEX_SHADER)),
+                fragment: Some(shaders.add(Shader::from_glsl(
+                    ShaderStage::Fragment,
+                    BLOCK_FRAGMENT_SHADER,
+                ))),
+            });
+        if let (Some(shaders), Some(mut pipelines)) = (
+            shaders,
+            world.get_resource_mut::<Assets<PipelineDescriptor>>(),
+        ) {
+            let mut pipeline = PipelineDescriptor::default_config(shaders);
+            // The sprite pipeline doesn't cull either
+            pipeline.primitive.cull_mode = CullMode::None;
+            pipelines.set_untracked(BLOCK_PIPELINE_HANDLE, pipeline);
+        }
+        if let Some(mut render_graph) = world.get_resource_mut::<RenderGraph>() {
+            add_block_style_node(&mut render_graph);
+        }
+
+        app.add_system(style_new_blocks.system())
+            .add_system(pulse_blocks.system())
+            .add_system(dissolve_destroyed_blocks.system())
+            .add_system(tick_dissolves.system());
+    }
+}
+
+// What the block shader draws on top of the texture. Each field is a
+// uniform of its own, named BlockStyle_<field> in the shader.
+#[derive(RenderResources, Default)]
+pub struct BlockStyle {
+    // The texture is tinted from one to the other, top to bottom
+    pub top_color: Color,
+    pub bottom_color: Color,
+    // How much the edges light up
+    pub glow: f32,
+    // 0 is the whole block, at 1 it has burnt away
+    pub dissolve: f32,
+}
+
+impl BlockStyle {
+    // Every kind has its own gradient so they can be told apart at a glance.
+    // Mirror blocks are tinted blue so players know not to shoot them.
+    pub fn for_kind(kind: BlockKind) -> Self {
+        let (top_color, bottom_color) = match kind {
+            BlockKind::Normal => (Color::WHITE, Color::rgb(0.75, 0.75, 0.75)),
+            BlockKind::Small => (Color::rgb(1.0, 0.95, 0.8), Color::rgb(0.9, 0.6, 0.4)),
+            BlockKind::Large => (Color::rgb(0.85, 0.85, 1.0), Color::rgb(0.5, 0.5, 0.8)),
+            BlockKind::Zigzag => (Color::rgb(0.9, 1.0, 0.8), Color::rgb(0.5, 0.8, 0.4)),
+            BlockKind::Homing => (Color::rgb(1.0, 0.8, 0.8), Color::rgb(0.8, 0.3, 0.3)),
+            BlockKind::Armored => (Color::rgb(0.9, 0.9, 0.9), Color::rgb(0.55, 0.55, 0.6)),
+            BlockKind::Mirror => (Color::rgb(0.7, 0.9, 1.0), Color::rgb(0.5, 0.7, 1.0)),
+        };
+
+        BlockStyle {
+            top_color,
+            bottom_color,
+            ..Default::default()
+        }
+    }
+}
+
+// A copy of a destroyed block, left behind to burn away
+struct Dissolving {
+    timer: Timer,
+}
+
+// Fills in the BlockStyle uniforms, before either pass draws the blocks
+fn add_block_style_node(render_graph: &mut RenderGraph) {
+    render_graph.add_system_node(
+        BLOCK_STYLE_NODE,
+        RenderResourcesNode::<BlockStyle>::new(true),
+    );
+
+    for pass in [MAIN_PASS, PIXEL_ART_PASS].iter() {
+        if let Err(error) = render_graph.add_node_edge(BLOCK_STYLE_NODE, *pass) {
+            println!(
+                "Could not draw the block style before {}: {:?}",
+                pass, error
+            );
+        }
+    }
+}
+
+fn block_pipelines() -> RenderPipelines {
+    RenderPipelines::from_pipelines(vec![RenderPipeline::new(BLOCK_PIPELINE_HANDLE.typed())])
+}
+
+// Every block is drawn with the block shader, however it was spawned
+fn style_new_blocks(
+    mut commands: Commands,
+    block_query: Query<(Entity, &BlockKind), Added<Block>>,
+) {
+    for (entity, kind) in block_query.iter() {
+        commands
+            .entity(entity)
+            .insert(block_pipelines())
+            .insert(BlockStyle::for_kind(*kind));
+    }
+}
+
+// Block edges flare up on every beat of the music and fade back down
+// before the next one
+fn pulse_blocks(
+    time: Res<GameTime>,
+    beat: Res<Beat>,
+    mut block_query: Query<&mut BlockStyle, With<Block>>,
+) {
+    let beats = beat.beats_since_start(&time) as f32;
+    let since_beat = beats.fract();
+    let glow = GLOW_PEAK * (1.0 - since_beat) * (1.0 - since_beat);
+
+    for mut style in block_query.iter_mut() {
+        style.glow = glow;
+    }
+}
+
+// The block is already gone by the time the event is read, so a copy of
+// it is put where it was to dissolve
+fn dissolve_destroyed_blocks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut block_destroyed_event: EventReader<BlockDestroyedEvent>,
+) {
+    for event in block_destroyed_event.iter() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    size: Vec2::new(BLOCKSIZEX, BLOCKSIZEY) * event.size.scale(),
+                    resize_mode: SpriteResizeMode::Manual,
+                    ..Default::default()
+                },
+                material: materials.add(block_texture(&asset_server, event.kind).into()),
+                render_pipelines: block_pipelines(),
+                transform: Transform::from_translation(event.position),
+                ..Default::default()
+            })
+            .insert(BlockStyle::for_kind(event.kind))
+            .insert(Dissolving {
+                timer: Timer::from_seconds(DISSOLVE_SECONDS, false),
+            })
+            .insert(RunScoped);
+    }
+}
+
+fn tick_dissolves(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut dissolving_query: Query<(Entity, &mut Dissolving, &mut BlockStyle)>,
+) {
+    for (entity, mut dissolving, mut style) in dissolving_query.iter_mut() {
+        dissolving.timer.tick(time.delta());
+        style.dissolve = dissolving.timer.percent();
+
+        if dissolving.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
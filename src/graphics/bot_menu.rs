@@ -0,0 +1,82 @@
+// BOT MENU CODE
+
+use bevy::prelude::*;
+
+use crate::logic::bot::BotSettings;
+use crate::AppState;
+
+pub struct BotMenuPlugin;
+
+impl Plugin for BotMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::MainMenu).with_system(spawn_bot_menu.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::MainMenu)
+                .with_system(change_bot_settings.system())
+                .with_system(update_bot_menu_text.system()),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::MainMenu).with_system(clear_bot_menu.system()),
+        );
+    }
+}
+
+struct BotMenuText;
+
+fn spawn_bot_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(80.0),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 30.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(BotMenuText);
+}
+
+// B turns the bot teammate on and off, N changes how good it is
+fn change_bot_settings(keyboard_input: Res<Input<KeyCode>>, mut bot_settings: ResMut<BotSettings>) {
+    if keyboard_input.just_pressed(KeyCode::B) {
+        bot_settings.enabled = !bot_settings.enabled;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::N) {
+        bot_settings.difficulty = bot_settings.difficulty.next();
+    }
+}
+
+fn update_bot_menu_text(
+    bot_settings: Res<BotSettings>,
+    mut text_query: Query<&mut Text, With<BotMenuText>>,
+) {
+    if let Ok(mut text) = text_query.single_mut() {
+        text.sections[0].value = format!(
+            "Bot teammate (B): {}\nBot difficulty (N): {}",
+            if bot_settings.enabled { "On" } else { "Off" },
+            bot_settings.difficulty
+        );
+    }
+}
+
+fn clear_bot_menu(mut commands: Commands, text_query: Query<Entity, With<BotMenuText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,142 @@
+// CAMERA CODE
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use super::pixel_art::{pixel_art_scale, world_per_pixel, PixelArtCamera};
+use crate::settings::{camera_scale, GameSettings};
+use crate::{WINDOWHEIGHT, WINDOWWIDTH};
+
+pub const APPLY_CAMERA_RIG: &str = "apply_camera_rig";
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<CameraRig>()
+            .add_startup_system(spawn_cameras.system())
+            // After everything has had its say for the frame, before drawing
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                apply_camera_rig
+                    .system()
+                    .label(APPLY_CAMERA_RIG)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+// The 2D camera that looks at the game
+pub struct GameplayCamera(pub Entity);
+
+// The camera the menus and HUD are drawn with, it never moves
+pub struct UiCamera(pub Entity);
+
+// What the rest of the game wants from the gameplay camera. Nothing else
+// touches the camera itself, apply_camera_rig sets it from this every frame.
+pub struct CameraRig {
+    // Where the camera looks, the middle of the playfield unless something
+    // like the kill cam wants a closer look
+    pub focus: Vec2,
+    // On top of fitting the playfield into the window, below 1 zooms in
+    pub zoom: f32,
+    // Set by the camera shake every frame, zero when it is still
+    pub shake: Vec2,
+    // The window in pixels, kept up to date by the display when it changes
+    // size. The playfield is scaled to fit it.
+    pub window_size: Vec2,
+    // Follows the pixel art setting
+    pub pixel_art: bool,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        CameraRig {
+            focus: Vec2::ZERO,
+            zoom: 1.0,
+            shake: Vec2::ZERO,
+            window_size: Vec2::new(WINDOWWIDTH, WINDOWHEIGHT),
+            pixel_art: false,
+        }
+    }
+}
+
+impl CameraRig {
+    // Back to the whole playfield
+    pub fn reset_view(&mut self) {
+        self.focus = Vec2::ZERO;
+        self.zoom = 1.0;
+    }
+
+    pub fn scale(&self) -> f32 {
+        let fit_scale = if self.pixel_art {
+            pixel_art_scale(self.window_size)
+        } else {
+            camera_scale(self.window_size.x, self.window_size.y)
+        };
+        fit_scale * self.zoom
+    }
+
+    // Where the cursor is in the world, if it is over the window.
+    // The shake is left out so aiming doesn't wobble with it.
+    pub fn cursor_world_position(&self, windows: &Windows) -> Option<Vec2> {
+        let window = windows.get_primary()?;
+        let screen = window.cursor_position()?;
+        let centered = screen - Vec2::new(window.width(), window.height()) / 2.0;
+        Some(self.focus + centered * self.scale())
+    }
+}
+
+fn spawn_cameras(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    mut camera_rig: ResMut<CameraRig>,
+) {
+    // Zoom to fit the playfield into the window, whatever size it is
+    camera_rig.window_size = Vec2::new(settings.window_width, settings.window_height);
+    camera_rig.pixel_art = settings.pixel_art;
+
+    let mut camera = OrthographicCameraBundle::new_2d();
+    camera.transform.scale = Vec3::new(camera_rig.scale(), camera_rig.scale(), 1.0);
+
+    let gameplay_camera = commands.spawn_bundle(camera).id();
+    let ui_camera = commands.spawn_bundle(UiCameraBundle::default()).id();
+
+    commands.insert_resource(GameplayCamera(gameplay_camera));
+    commands.insert_resource(UiCamera(ui_camera));
+}
+
+// With pixel art on, the pixel art camera takes the camera rig and draws
+// the playfield into its texture, and the gameplay camera just shows that
+// texture scaled up by a whole number
+fn apply_camera_rig(
+    camera_rig: Res<CameraRig>,
+    gameplay_camera: Res<GameplayCamera>,
+    pixel_art_camera: Option<Res<PixelArtCamera>>,
+    mut camera_query: Query<&mut Transform>,
+) {
+    let mut position = camera_rig.focus + camera_rig.shake;
+    let mut scale = camera_rig.scale();
+
+    if camera_rig.pixel_art {
+        if let Some(mut transform) = pixel_art_camera
+            .and_then(|pixel_art_camera| camera_query.get_mut(pixel_art_camera.0).ok())
+        {
+            // Whole pixels only, otherwise the big pixels shimmer as it moves
+            let snapped = (position / world_per_pixel()).round() * world_per_pixel();
+            transform.translation.x = snapped.x;
+            transform.translation.y = snapped.y;
+            let pixel_scale = world_per_pixel() * camera_rig.zoom;
+            transform.scale = Vec3::new(pixel_scale, pixel_scale, 1.0);
+        }
+
+        position = Vec2::ZERO;
+        scale = pixel_art_scale(camera_rig.window_size);
+    }
+
+    if let Ok(mut transform) = camera_query.get_mut(gameplay_camera.0) {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        transform.scale = Vec3::new(scale, scale, 1.0);
+    }
+}
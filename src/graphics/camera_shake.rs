@@ -0,0 +1,83 @@
+// CAMERA SHAKE CODE
+
+use bevy::prelude::*;
+
+use super::camera::CameraRig;
+use super::flash::EffectSettings;
+use crate::logic::factions::CollisionEvent;
+use crate::logic::game_time::GameTime;
+use crate::logic::player::PlayerHitEvent;
+use crate::logic::stats::NearMissEvent;
+
+// How far the camera moves at full trauma, in 'pixels'
+const MAX_SHAKE_OFFSET: f32 = 24.0;
+// Trauma lost per second, full trauma settles in a little under a second
+const TRAUMA_DECAY: f32 = 1.2;
+const HIT_TRAUMA: f32 = 0.6;
+const NEAR_MISS_TRAUMA: f32 = 0.15;
+// A little for every point of damage anything does to anything
+const IMPACT_TRAUMA: f32 = 0.05;
+
+pub struct CameraShakePlugin;
+
+impl Plugin for CameraShakePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<CameraShake>()
+            .add_system(add_trauma.system())
+            .add_system(shake_camera.system());
+    }
+}
+
+// Trauma is 0 to 1, the shake grows with its square so small knocks stay small
+#[derive(Default)]
+pub struct CameraShake {
+    trauma: f32,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
+
+fn add_trauma(
+    mut camera_shake: ResMut<CameraShake>,
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    mut near_miss_event: EventReader<NearMissEvent>,
+    mut collision_event: EventReader<CollisionEvent>,
+) {
+    for _event in player_hit_event.iter() {
+        camera_shake.add_trauma(HIT_TRAUMA);
+    }
+    for _event in near_miss_event.iter() {
+        camera_shake.add_trauma(NEAR_MISS_TRAUMA);
+    }
+    for event in collision_event.iter() {
+        camera_shake.add_trauma(IMPACT_TRAUMA * event.damage as f32);
+    }
+}
+
+// The camera rig adds the shake on top of wherever the camera is looking
+fn shake_camera(
+    mut camera_shake: ResMut<CameraShake>,
+    mut camera_rig: ResMut<CameraRig>,
+    effect_settings: Res<EffectSettings>,
+    time: Res<GameTime>,
+) {
+    camera_shake.trauma = (camera_shake.trauma - TRAUMA_DECAY * time.delta_seconds()).max(0.0);
+    if effect_settings.reduce_motion || camera_shake.trauma == 0.0 {
+        camera_rig.shake = Vec2::ZERO;
+        return;
+    }
+
+    let t = time.seconds_since_startup() as f32;
+    let amount = camera_shake.trauma * camera_shake.trauma * MAX_SHAKE_OFFSET;
+    camera_rig.shake = Vec2::new(shake_noise(t, 0.0), shake_noise(t, 17.0)) * amount;
+}
+
+// Smooth noise from -1 to 1, a few sine waves that never line up.
+// Cheaper than real perlin noise and looks the same when it's this quick.
+fn shake_noise(t: f32, seed: f32) -> f32 {
+    let t = t + seed;
+    ((t * 31.0).sin() * 0.5 + (t * 47.3).sin() * 0.3 + (t * 71.9).sin() * 0.2).clamp(-1.0, 1.0)
+}
@@ -0,0 +1,164 @@
+// CHALLENGE MENU CODE
+
+use bevy::prelude::*;
+
+use crate::logic::challenges::{ChallengeProgress, CHALLENGES};
+use crate::logic::input_map::take_just_pressed;
+use crate::logic::mutators::ActiveMutators;
+use crate::logic::profile::Profile;
+use crate::logic::rules::{GameRules, PlayerSetup};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+pub struct ChallengeMenuPlugin;
+
+impl Plugin for ChallengeMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<ChallengeCursor>()
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu).with_system(open_challenges.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Challenges)
+                    .with_system(spawn_challenge_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Challenges)
+                    .with_system(navigate_challenges.system())
+                    .with_system(update_challenge_menu_text.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Challenges).with_system(clear_challenge_menu.system()),
+            );
+    }
+}
+
+// Which challenge is selected
+#[derive(Default)]
+struct ChallengeCursor(usize);
+
+struct ChallengeMenuText;
+
+// C both opens and closes the menu
+fn open_challenges(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if take_just_pressed(&mut keyboard_input, KeyCode::C) {
+        let _ = app_state.set(AppState::Challenges);
+    }
+}
+
+fn spawn_challenge_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 32.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(ChallengeMenuText);
+        })
+        .insert(ChallengeMenuText);
+}
+
+// Up and down pick a challenge, Enter plays it, C goes back
+#[allow(clippy::too_many_arguments)]
+fn navigate_challenges(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut cursor: ResMut<ChallengeCursor>,
+    mut progress: ResMut<ChallengeProgress>,
+    mut mutators: ResMut<ActiveMutators>,
+    mut rules: ResMut<GameRules>,
+    mut player_setup: ResMut<PlayerSetup>,
+    settings: Res<GameSettings>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    let challenge_count = CHALLENGES.len();
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        cursor.0 = (cursor.0 + 1) % challenge_count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        cursor.0 = (cursor.0 + challenge_count - 1) % challenge_count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        // The challenge brings its own rules and mutators, the player's
+        // own come back at the main menu
+        let challenge = &CHALLENGES[cursor.0];
+        player_setup.set_aside(&rules, &mutators);
+        *rules = GameRules::from_preset(challenge.preset, &settings);
+        mutators.0 = challenge.mutators.to_vec();
+        progress.selected = Some(cursor.0);
+
+        let _ = app_state.set(AppState::InGame);
+    }
+
+    if take_just_pressed(&mut keyboard_input, KeyCode::C) {
+        let _ = app_state.set(AppState::MainMenu);
+    }
+}
+
+fn update_challenge_menu_text(
+    cursor: Res<ChallengeCursor>,
+    profile: Res<Profile>,
+    mut text_query: Query<&mut Text, With<ChallengeMenuText>>,
+) {
+    let mut lines = vec![
+        "Challenges".to_string(),
+        "Enter to play, C to go back".to_string(),
+        String::new(),
+    ];
+
+    lines.extend(CHALLENGES.iter().enumerate().map(|(index, challenge)| {
+        let cursor_mark = if index == cursor.0 { "> " } else { "  " };
+        let completed = profile
+            .completed_challenges
+            .iter()
+            .any(|id| id == challenge.id);
+        let status = if completed {
+            "done".to_string()
+        } else {
+            format!("{} coins", challenge.reward)
+        };
+
+        format!("{}{} ({})", cursor_mark, challenge.description, status)
+    }));
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_challenge_menu(
+    mut commands: Commands,
+    text_query: Query<Entity, With<ChallengeMenuText>>,
+) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,68 @@
+// CHAT VOTE CODE
+
+use bevy::prelude::*;
+
+use crate::logic::twitch::{ChatEvent, ChatVotes};
+use crate::AppState;
+
+pub struct ChatVoteTextPlugin;
+
+impl Plugin for ChatVoteTextPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(render_chat_votes.system())
+            .add_system(update_chat_vote_text.system());
+    }
+}
+
+struct ChatVoteText;
+
+fn render_chat_votes(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(40.0),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 28.0,
+                    color: Color::rgb(0.7, 0.5, 1.0),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(ChatVoteText);
+}
+
+// The tally only shows during a run
+fn update_chat_vote_text(
+    votes: Res<ChatVotes>,
+    app_state: Res<State<AppState>>,
+    mut text_query: Query<&mut Text, With<ChatVoteText>>,
+) {
+    if let Ok(mut text) = text_query.single_mut() {
+        if *app_state.current() != AppState::InGame {
+            text.sections[0].value = String::new();
+            return;
+        }
+
+        let seconds_left = votes.timer.duration().as_secs_f32() - votes.timer.elapsed_secs();
+        let mut lines = vec![format!("Chat vote, {}s left", seconds_left.ceil() as u32)];
+        lines.extend(
+            ChatEvent::ALL
+                .iter()
+                .zip(votes.tally.iter())
+                .map(|(event, count)| format!("{}: {}", event.command(), count)),
+        );
+
+        text.sections[0].value = lines.join("\n");
+    }
+}
@@ -0,0 +1,186 @@
+// CONTROLS MENU CODE
+
+use bevy::prelude::*;
+
+use crate::logic::input_map::{take_just_pressed, Action, InputMap};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+pub struct ControlsMenuPlugin;
+
+impl Plugin for ControlsMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<ControlsCursor>()
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu).with_system(open_controls.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Controls).with_system(spawn_controls_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Controls)
+                    .with_system(navigate_controls.system())
+                    .with_system(update_controls_menu_text.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Controls).with_system(clear_controls_menu.system()),
+            );
+    }
+}
+
+// Which action is selected, and whether the next key press rebinds it
+#[derive(Default)]
+struct ControlsCursor {
+    selected: usize,
+    waiting_for_key: bool,
+}
+
+struct ControlsMenu;
+
+struct ControlsMenuText;
+
+// K both opens and closes the menu
+fn open_controls(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if take_just_pressed(&mut keyboard_input, KeyCode::K) {
+        let _ = app_state.set(AppState::Controls);
+    }
+}
+
+fn spawn_controls_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut cursor: ResMut<ControlsCursor>,
+) {
+    *cursor = ControlsCursor::default();
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 32.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(ControlsMenuText);
+        })
+        .insert(ControlsMenu);
+}
+
+// Up and down pick an action, Enter waits for its new key,
+// M changes how a held action is triggered,
+// R puts every key back to the default and K goes back.
+// Any key can be bound, so nothing else is checked while waiting.
+fn navigate_controls(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut cursor: ResMut<ControlsCursor>,
+    mut input_map: ResMut<InputMap>,
+    mut settings: ResMut<GameSettings>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if cursor.waiting_for_key {
+        if let Some(key) = keyboard_input.get_just_pressed().next() {
+            input_map.bind(Action::ALL[cursor.selected], *key);
+            input_map.save();
+            cursor.waiting_for_key = false;
+        }
+        return;
+    }
+
+    let action_count = Action::ALL.len();
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        cursor.selected = (cursor.selected + 1) % action_count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        cursor.selected = (cursor.selected + action_count - 1) % action_count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        cursor.waiting_for_key = true;
+    }
+
+    let action = Action::ALL[cursor.selected];
+    if keyboard_input.just_pressed(KeyCode::M) && action.has_mode() {
+        let mode = input_map.mode(action).next();
+        input_map.set_mode(action, mode, &mut settings);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::R) {
+        input_map.reset();
+        input_map.save();
+    }
+
+    if take_just_pressed(&mut keyboard_input, KeyCode::K) {
+        let _ = app_state.set(AppState::MainMenu);
+    }
+}
+
+fn update_controls_menu_text(
+    cursor: Res<ControlsCursor>,
+    input_map: Res<InputMap>,
+    mut text_query: Query<&mut Text, With<ControlsMenuText>>,
+) {
+    let mut lines = vec![
+        "Controls".to_string(),
+        "Enter to rebind, M to change the mode, R to reset, K to go back".to_string(),
+        String::new(),
+    ];
+
+    lines.extend(Action::ALL.iter().enumerate().map(|(index, action)| {
+        let cursor_mark = if index == cursor.selected { "> " } else { "  " };
+        let keys = if index == cursor.selected && cursor.waiting_for_key {
+            "press a key...".to_string()
+        } else {
+            input_map
+                .keys(*action)
+                .iter()
+                .map(|key| format!("{:?}", key))
+                .collect::<Vec<String>>()
+                .join(" / ")
+        };
+
+        if action.has_mode() {
+            format!(
+                "{}{}: {} ({})",
+                cursor_mark,
+                action,
+                keys,
+                input_map.mode(*action)
+            )
+        } else {
+            format!("{}{}: {}", cursor_mark, action, keys)
+        }
+    }));
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_controls_menu(mut commands: Commands, menu_query: Query<Entity, With<ControlsMenu>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
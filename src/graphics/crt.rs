@@ -0,0 +1,287 @@
+// CRT FILTER CODE
+
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::pass::{
+    LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+    RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
+};
+use bevy::render::pipeline::{CullMode, PipelineDescriptor, RenderPipeline, RenderPipelines};
+use bevy::render::render_graph::base::{camera::CAMERA_2D, node, MainPass};
+use bevy::render::render_graph::{PassNode, RenderGraph, TextureNode};
+use bevy::render::shader::{Shader, ShaderStage, ShaderStages};
+use bevy::render::texture::{
+    Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage,
+};
+use bevy::transform::TransformSystem;
+use bevy::ui::Node;
+
+use super::block_style::BLOCK_STYLE_NODE;
+use super::camera::{CameraRig, GameplayCamera, APPLY_CAMERA_RIG};
+use super::flash::EffectSettings;
+use super::pixel_art::{PIXEL_ART_PASS, SPRITE_NODES};
+use crate::{WINDOWHEIGHT, WINDOWWIDTH};
+
+// Gap in pixels between each scanline
+const SCANLINE_SPACING: f32 = 4.0;
+const SCANLINE_THICKNESS: f32 = 1.0;
+
+const CRT_PASS: &str = "crt_pass";
+const CRT_TEXTURE: &str = "crt_texture";
+const CRT_DEPTH: &str = "crt_depth";
+const CRT_VERTEX_SHADER: &str = include_str!("../../assets/shaders/sprite.vert");
+const CRT_FRAGMENT_SHADER: &str = include_str!("../../assets/shaders/crt.frag");
+
+// The texture the world is drawn into, the screen draws it back with
+// bloom and a vignette
+const CRT_TEXTURE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Texture::TYPE_UUID, 0xc47_5c4ee_0743);
+const CRT_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0xc47_919e_0743);
+
+pub struct CrtPlugin;
+
+impl Plugin for CrtPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // Nothing is drawn when running headless
+        let world = app.world_mut();
+        let shaders = world
+            .get_resource_mut::<Assets<Shader>>()
+            .map(|mut shaders| ShaderStages {
+                vertex: shaders.add(Shader::from_glsl(ShaderStage::Vertex, CRT_VERTEX_SHADER)),
+                fragment: Some(shaders.add(Shader::from_glsl(
+                    ShaderStage::Fragment,
+                    CRT_FRAGMENT_SHADER,
+                ))),
+            });
+        if let (Some(shaders), Some(mut pipelines)) = (
+            shaders,
+            world.get_resource_mut::<Assets<PipelineDescriptor>>(),
+        ) {
+            let mut pipeline = PipelineDescriptor::default_config(shaders);
+            pipeline.primitive.cull_mode = CullMode::None;
+            pipelines.set_untracked(CRT_PIPELINE_HANDLE, pipeline);
+        }
+        if let Some(mut render_graph) = world.get_resource_mut::<RenderGraph>() {
+            add_crt_pass(&mut render_graph);
+        }
+
+        app.add_startup_system(spawn_scanlines.system())
+            .add_startup_system(spawn_crt_screen.system())
+            .add_system(toggle_scanlines.system())
+            .add_system(swap_render_passes.system())
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                follow_gameplay_camera
+                    .system()
+                    .after(APPLY_CAMERA_RIG)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+struct Scanline;
+
+// Sprites with this are drawn into the CRT texture instead of the window
+#[derive(Default)]
+pub struct CrtPass;
+
+// The CRT texture drawn over the window with bloom and a vignette
+pub struct CrtScreen;
+
+// The whole world, pixel art screen included, is drawn into a texture the
+// size of the window by the gameplay camera. The screen then draws it back
+// in the main pass, before the UI.
+fn add_crt_pass(render_graph: &mut RenderGraph) {
+    let size = Extent3d::new(WINDOWWIDTH as u32, WINDOWHEIGHT as u32, 1);
+
+    let mut pass_node = PassNode::<&CrtPass>::new(PassDescriptor {
+        color_attachments: vec![RenderPassColorAttachmentDescriptor {
+            attachment: TextureAttachment::Input("color_attachment".to_string()),
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(Color::BLACK),
+                store: true,
+            },
+        }],
+        depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+            attachment: TextureAttachment::Input("depth".to_string()),
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: None,
+        }),
+        sample_count: 1,
+    });
+    pass_node.add_camera(CAMERA_2D);
+
+    render_graph.add_node(CRT_PASS, pass_node);
+    render_graph.add_node(
+        CRT_TEXTURE,
+        TextureNode::new(
+            TextureDescriptor {
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::default(),
+                usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+            },
+            None,
+            Some(CRT_TEXTURE_HANDLE),
+        ),
+    );
+    render_graph.add_node(
+        CRT_DEPTH,
+        TextureNode::new(
+            TextureDescriptor {
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Depth32Float,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+            },
+            None,
+            None,
+        ),
+    );
+
+    let edges = render_graph
+        .add_node_edge(node::CAMERA_2D, CRT_PASS)
+        .and_then(|_| {
+            render_graph.add_slot_edge(
+                CRT_TEXTURE,
+                TextureNode::TEXTURE,
+                CRT_PASS,
+                "color_attachment",
+            )
+        })
+        .and_then(|_| {
+            render_graph.add_slot_edge(CRT_DEPTH, TextureNode::TEXTURE, CRT_PASS, "depth")
+        })
+        // The pixel art screen is drawn like the rest of the world
+        .and_then(|_| render_graph.add_node_edge(PIXEL_ART_PASS, CRT_PASS))
+        .and_then(|_| render_graph.add_node_edge(CRT_PASS, node::MAIN_PASS));
+    if let Err(error) = edges {
+        println!("Could not set up the CRT pass: {:?}", error);
+    }
+
+    for node in SPRITE_NODES.iter().chain([BLOCK_STYLE_NODE].iter()) {
+        if render_graph.add_node_edge(*node, CRT_PASS).is_err() {
+            println!("Could not draw the CRT pass after {}", node);
+        }
+    }
+}
+
+fn spawn_crt_screen(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    effect_settings: Res<EffectSettings>,
+) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(Vec2::new(WINDOWWIDTH, WINDOWHEIGHT)),
+            material: materials.add(ColorMaterial::texture(CRT_TEXTURE_HANDLE.typed())),
+            render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::new(
+                CRT_PIPELINE_HANDLE.typed(),
+            )]),
+            visible: Visible {
+                is_visible: effect_settings.crt_filter,
+                is_transparent: false,
+            },
+            ..Default::default()
+        })
+        .insert(CrtScreen);
+}
+
+// The screen covers exactly what the gameplay camera sees, so the texture
+// goes back where it was drawn from
+fn follow_gameplay_camera(
+    camera_rig: Res<CameraRig>,
+    gameplay_camera: Res<GameplayCamera>,
+    camera_query: Query<&Transform, Without<CrtScreen>>,
+    mut screen_query: Query<(&mut Transform, &mut Sprite), With<CrtScreen>>,
+) {
+    let camera_transform = match camera_query.get(gameplay_camera.0) {
+        Ok(transform) => *transform,
+        Err(_) => return,
+    };
+
+    for (mut transform, mut sprite) in screen_query.iter_mut() {
+        // Just in front of the camera, in front of everything else
+        transform.translation = camera_transform.translation - Vec3::new(0.0, 0.0, 1.0);
+        transform.scale = camera_transform.scale;
+        sprite.size = camera_rig.window_size;
+    }
+}
+
+// While the filter is on the world goes into the CRT texture, the menus
+// and HUD are drawn over it without the effects
+#[allow(clippy::type_complexity)]
+fn swap_render_passes(
+    mut commands: Commands,
+    effect_settings: Res<EffectSettings>,
+    main_pass_query: Query<Entity, (With<MainPass>, Without<Node>, Without<CrtScreen>)>,
+    crt_pass_query: Query<Entity, With<CrtPass>>,
+) {
+    if effect_settings.crt_filter {
+        for entity in main_pass_query.iter() {
+            commands.entity(entity).remove::<MainPass>().insert(CrtPass);
+        }
+    } else {
+        for entity in crt_pass_query.iter() {
+            commands.entity(entity).remove::<CrtPass>().insert(MainPass);
+        }
+    }
+}
+
+// NOTE
+// The scanlines are still thin translucent lines laid over the screen
+// as UI nodes, the CRT pass only does the bloom and vignette.
+fn spawn_scanlines(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    effect_settings: Res<EffectSettings>,
+) {
+    let material = materials.add(Color::rgba(0.0, 0.0, 0.0, 0.25).into());
+    let scanline_count = (WINDOWHEIGHT / SCANLINE_SPACING) as usize;
+
+    for i in 0..scanline_count {
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        top: Val::Px(i as f32 * SCANLINE_SPACING),
+                        left: Val::Px(0.0),
+                        ..Default::default()
+                    },
+                    size: Size::new(Val::Percent(100.0), Val::Px(SCANLINE_THICKNESS)),
+                    ..Default::default()
+                },
+                material: material.clone(),
+                visible: Visible {
+                    is_visible: effect_settings.crt_filter,
+                    is_transparent: true,
+                },
+                ..Default::default()
+            })
+            .insert(Scanline);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_scanlines(
+    effect_settings: Res<EffectSettings>,
+    mut scanline_query: Query<&mut Visible, Or<(With<Scanline>, With<CrtScreen>)>>,
+) {
+    if !effect_settings.is_changed() {
+        return;
+    }
+
+    for mut visible in scanline_query.iter_mut() {
+        visible.is_visible = effect_settings.crt_filter;
+    }
+}
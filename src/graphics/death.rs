@@ -0,0 +1,108 @@
+// DEATH ANIMATION CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+
+use crate::logic::game_time::GameTime;
+use crate::logic::lifetime::DespawnAfter;
+use crate::logic::player::{Player, PlayerHitEvent, DEATH_SEQUENCE_SECONDS};
+use crate::logic::reset_game::RunScoped;
+
+const FRAGMENT_COUNT: usize = 4;
+const FRAGMENT_SPEED: f32 = 120.0;
+const FRAGMENT_SPIN: f32 = 6.0;
+
+pub struct DeathAnimationPlugin;
+
+impl Plugin for DeathAnimationPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(shatter_player.system())
+            .add_system(fragment_system.system());
+    }
+}
+
+// A piece of the player that flies off, spins, and fades out
+struct Fragment {
+    velocity: Vec2,
+    spin: f32,
+}
+
+// Hide the player and break it into fragments when it gets hit
+fn shatter_player(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    mut player_query: Query<(&mut Visible, &Sprite), With<Player>>,
+) {
+    for event in player_hit_event.iter() {
+        let mut player_size = Vec2::new(40.0, 40.0);
+
+        if let Ok((mut visible, sprite)) = player_query.get_mut(event.player) {
+            visible.is_visible = false;
+            player_size = sprite.size;
+        }
+
+        let texture_handle = asset_server.load("textures/block_3.png");
+        let fragment_size = player_size / 2.0;
+
+        for i in 0..FRAGMENT_COUNT {
+            // Each fragment goes out a different diagonal, one per quarter of the player
+            let angle = std::f32::consts::FRAC_PI_4 + i as f32 * std::f32::consts::FRAC_PI_2;
+            let direction = Vec2::new(angle.cos(), angle.sin());
+            let offset = direction * fragment_size.x / 2.0;
+
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        size: fragment_size,
+                        resize_mode: SpriteResizeMode::Manual,
+                        ..Default::default()
+                    },
+                    // Every fragment gets its own material so they can fade
+                    material: materials.add(texture_handle.clone().into()),
+                    transform: Transform::from_xyz(
+                        event.position.x + offset.x,
+                        event.position.y + offset.y,
+                        2.0,
+                    ),
+                    ..Default::default()
+                })
+                .insert(Fragment {
+                    velocity: direction * FRAGMENT_SPEED,
+                    spin: if i % 2 == 0 {
+                        FRAGMENT_SPIN
+                    } else {
+                        -FRAGMENT_SPIN
+                    },
+                })
+                .insert(DespawnAfter::seconds(DEATH_SEQUENCE_SECONDS))
+                .insert(RunScoped);
+        }
+    }
+}
+
+// NOTE
+// Fragments run on real time rather than the time scale,
+// so they keep moving during the slow motion beat.
+fn fragment_system(
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut fragment_query: Query<(
+        &Fragment,
+        &DespawnAfter,
+        &mut Transform,
+        &Handle<ColorMaterial>,
+    )>,
+    time: Res<GameTime>,
+) {
+    let delta_time = time.delta_seconds();
+
+    for (fragment, despawn_after, mut transform, material) in fragment_query.iter_mut() {
+        transform.translation += fragment.velocity.extend(0.0) * delta_time;
+        transform.rotate(Quat::from_rotation_z(fragment.spin * delta_time));
+
+        if let Some(material) = materials.get_mut(material) {
+            material.color.set_a(1.0 - despawn_after.percent());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// DEBUG OVERLAY CODE
+
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticId, Diagnostics, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+};
+use bevy::prelude::*;
+
+use super::particles::ParticlePool;
+use crate::logic::blocks::Block;
+use crate::logic::player::InputLatency;
+
+// Blocks in play, measured like the engine's own diagnostics
+const BLOCK_COUNT: DiagnosticId =
+    DiagnosticId::from_u128(0x5b6f_52c1_8e4d_4a0f_9f3c_2d71_b0a6_e913);
+const BLOCK_COUNT_HISTORY: usize = 20;
+
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_plugin(FrameTimeDiagnosticsPlugin::default())
+            .add_plugin(EntityCountDiagnosticsPlugin::default())
+            .add_startup_system(setup_block_count_diagnostic.system())
+            .add_startup_system(render_debug_overlay.system())
+            .add_system(measure_block_count.system())
+            .add_system(toggle_debug_overlay.system())
+            .add_system(update_debug_overlay.system());
+    }
+}
+
+struct DebugOverlayText;
+
+fn render_debug_overlay(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(20.0),
+                    left: Val::Px(20.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 24.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            visible: Visible {
+                is_visible: false,
+                is_transparent: true,
+            },
+            ..Default::default()
+        })
+        .insert(DebugOverlayText);
+}
+
+fn setup_block_count_diagnostic(mut diagnostics: ResMut<Diagnostics>) {
+    diagnostics.add(Diagnostic::new(
+        BLOCK_COUNT,
+        "block_count",
+        BLOCK_COUNT_HISTORY,
+    ));
+}
+
+fn measure_block_count(mut diagnostics: ResMut<Diagnostics>, block_query: Query<&Block>) {
+    diagnostics.add_measurement(BLOCK_COUNT, block_query.iter().count() as f64);
+}
+
+// F3 shows and hides the overlay
+fn toggle_debug_overlay(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut overlay_query: Query<&mut Visible, With<DebugOverlayText>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F3) {
+        return;
+    }
+
+    for mut visible in overlay_query.iter_mut() {
+        visible.is_visible = !visible.is_visible;
+    }
+}
+
+fn update_debug_overlay(
+    diagnostics: Res<Diagnostics>,
+    input_latency: Res<InputLatency>,
+    particle_pool: Res<ParticlePool>,
+    mut overlay_query: Query<(&mut Text, &Visible), With<DebugOverlayText>>,
+) {
+    for (mut text, visible) in overlay_query.iter_mut() {
+        if !visible.is_visible {
+            continue;
+        }
+
+        let fps = diagnostics
+            .get(FrameTimeDiagnosticsPlugin::FPS)
+            .and_then(|fps| fps.average())
+            .unwrap_or(0.0);
+        let frame_time = diagnostics
+            .get(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+            .and_then(|frame_time| frame_time.average())
+            .unwrap_or(0.0);
+        // The latest count rather than an average, these are whole numbers
+        let count = |id: DiagnosticId| {
+            diagnostics
+                .get(id)
+                .and_then(|diagnostic| diagnostic.value())
+                .unwrap_or(0.0)
+        };
+
+        // The frame still has to be presented after the movement,
+        // so a full frame goes on top of the measured latency
+        let latency = match input_latency.last {
+            Some(latency) => format!(
+                "{:.2}ms + {:.1}ms frame",
+                latency.as_secs_f64() * 1000.0,
+                frame_time * 1000.0
+            ),
+            None => "press a key".to_string(),
+        };
+
+        text.sections[0].value = format!(
+            "FPS: {:.0}\nFrame time: {:.1}ms\nInput latency: {}\nEntities: {:.0}\nBlocks: {:.0}\nParticles: {}/{}",
+            fps,
+            frame_time * 1000.0,
+            latency,
+            count(EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+            count(BLOCK_COUNT),
+            particle_pool.in_use(),
+            particle_pool.size()
+        );
+    }
+}
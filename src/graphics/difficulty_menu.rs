@@ -0,0 +1,244 @@
+// DIFFICULTY MENU CODE
+
+use bevy::prelude::*;
+
+use crate::logic::daily::DailyChallenge;
+use crate::logic::rules::{CoopLives, CoopScoring, DifficultyPreset, GameRules};
+use crate::logic::weekly::WeeklyChallenge;
+use crate::settings::GameSettings;
+use crate::AppState;
+
+const ROW_COUNT: usize = 10;
+
+pub struct DifficultyMenuPlugin;
+
+impl Plugin for DifficultyMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<DifficultyMenu>()
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver)
+                    .with_system(toggle_difficulty_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::GameOver).with_system(close_difficulty_menu.system()),
+            )
+            .add_system(navigate_difficulty_menu.system())
+            .add_system(update_difficulty_menu_text.system());
+    }
+}
+
+#[derive(Default)]
+struct DifficultyMenu {
+    open: bool,
+    selected: usize,
+}
+
+struct DifficultyMenuNode;
+
+struct DifficultyMenuText;
+
+// The menu can be opened with F1 on the game over screen,
+// except after a daily or weekly challenge where the rules are fixed
+#[allow(clippy::too_many_arguments)]
+fn toggle_difficulty_menu(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut difficulty_menu: ResMut<DifficultyMenu>,
+    daily: Res<DailyChallenge>,
+    weekly: Res<WeeklyChallenge>,
+    menu_query: Query<Entity, With<DifficultyMenuNode>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F1) || daily.is_active() || weekly.is_active() {
+        return;
+    }
+
+    if difficulty_menu.open {
+        close_difficulty_menu(commands, difficulty_menu, menu_query);
+        return;
+    }
+
+    difficulty_menu.open = true;
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(60.0),
+                    left: Val::Px(80.0),
+                    ..Default::default()
+                },
+                padding: Rect::all(Val::Px(20.0)),
+                ..Default::default()
+            },
+            material: materials.add(Color::rgba(0.0, 0.0, 0.0, 0.7).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 32.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(DifficultyMenuText);
+        })
+        .insert(DifficultyMenuNode);
+}
+
+fn close_difficulty_menu(
+    mut commands: Commands,
+    mut difficulty_menu: ResMut<DifficultyMenu>,
+    menu_query: Query<Entity, With<DifficultyMenuNode>>,
+) {
+    difficulty_menu.open = false;
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Up and down pick a row, left and right change it
+fn navigate_difficulty_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut difficulty_menu: ResMut<DifficultyMenu>,
+    mut rules: ResMut<GameRules>,
+    settings: Res<GameSettings>,
+) {
+    if !difficulty_menu.open {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        difficulty_menu.selected = (difficulty_menu.selected + 1) % ROW_COUNT;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        difficulty_menu.selected = (difficulty_menu.selected + ROW_COUNT - 1) % ROW_COUNT;
+    }
+
+    let step = keyboard_input.just_pressed(KeyCode::Right) as i32
+        - keyboard_input.just_pressed(KeyCode::Left) as i32;
+
+    if step != 0 {
+        adjust_rules(&mut rules, &settings, difficulty_menu.selected, step);
+    }
+}
+
+fn adjust_rules(rules: &mut GameRules, settings: &GameSettings, row: usize, step: i32) {
+    if row == 0 {
+        // Cycle through the presets, custom goes back to normal
+        let presets = DifficultyPreset::ALL;
+        let current = presets
+            .iter()
+            .position(|preset| *preset == rules.preset)
+            .unwrap_or(1) as i32;
+        let next = (current + step).rem_euclid(presets.len() as i32) as usize;
+
+        let previous = *rules;
+        *rules = GameRules::from_preset(presets[next], settings);
+        rules.adaptive = previous.adaptive;
+        rules.coop_scoring = previous.coop_scoring;
+        rules.coop_lives = previous.coop_lives;
+        rules.player_collision = previous.player_collision;
+        rules.friendly_fire = previous.friendly_fire;
+        rules.safe_spawn_radius = previous.safe_spawn_radius;
+        return;
+    }
+
+    // Adaptive difficulty and the co-op options sit on top of any preset
+    match row {
+        5 => {
+            rules.adaptive = !rules.adaptive;
+            return;
+        }
+        6 => {
+            rules.coop_scoring = match rules.coop_scoring {
+                CoopScoring::Shared => CoopScoring::Split,
+                CoopScoring::Split => CoopScoring::Shared,
+            };
+            return;
+        }
+        7 => {
+            rules.player_collision = !rules.player_collision;
+            return;
+        }
+        8 => {
+            rules.friendly_fire = !rules.friendly_fire;
+            return;
+        }
+        9 => {
+            rules.coop_lives = match rules.coop_lives {
+                CoopLives::Shared => CoopLives::PerPlayer,
+                CoopLives::PerPlayer => CoopLives::Shared,
+            };
+            return;
+        }
+        _ => (),
+    }
+
+    let step_f32 = step as f32;
+
+    match row {
+        1 => rules.spawn_interval = (rules.spawn_interval + step_f32 * 0.25).clamp(0.25, 10.0),
+        2 => rules.speed_multiplier = (rules.speed_multiplier + step_f32 * 0.1).clamp(0.25, 3.0),
+        3 => rules.lives = (rules.lives as i32 + step).clamp(1, 9) as u32,
+        _ => rules.powerup_interval = (rules.powerup_interval + step_f32).clamp(1.0, 120.0),
+    }
+
+    // Changing any knob by hand makes it a custom difficulty
+    rules.preset = DifficultyPreset::Custom;
+}
+
+fn update_difficulty_menu_text(
+    difficulty_menu: Res<DifficultyMenu>,
+    rules: Res<GameRules>,
+    mut text_query: Query<&mut Text, With<DifficultyMenuText>>,
+) {
+    if let Ok(mut text) = text_query.single_mut() {
+        let rows = [
+            format!("Difficulty: {}", rules.preset),
+            format!("Spawn interval: {:.2}s", rules.spawn_interval),
+            format!("Block speed: {:.1}x", rules.speed_multiplier),
+            format!("Lives: {}", rules.lives),
+            format!("Power up interval: {:.0}s", rules.powerup_interval),
+            format!(
+                "Adaptive difficulty: {}",
+                if rules.adaptive { "On" } else { "Off" }
+            ),
+            format!("Co-op score: {}", rules.coop_scoring),
+            format!(
+                "Co-op player collision: {}",
+                if rules.player_collision { "On" } else { "Off" }
+            ),
+            format!(
+                "Co-op friendly fire: {}",
+                if rules.friendly_fire { "On" } else { "Off" }
+            ),
+            format!("Co-op lives: {}", rules.coop_lives),
+        ];
+
+        text.sections[0].value = rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let cursor = if index == difficulty_menu.selected {
+                    "> "
+                } else {
+                    "  "
+                };
+                format!("{}{}", cursor, row)
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+}
@@ -0,0 +1,54 @@
+// DISPLAY CODE
+
+use bevy::prelude::*;
+use bevy::window::{WindowMode, WindowResized};
+
+use super::camera::CameraRig;
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(toggle_fullscreen.system())
+            .add_system(fit_playfield.system());
+    }
+}
+
+// F11 or Alt+Enter, anywhere in the game
+fn toggle_fullscreen(keyboard_input: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
+    let alt = keyboard_input.pressed(KeyCode::LAlt) || keyboard_input.pressed(KeyCode::RAlt);
+    let toggle = keyboard_input.just_pressed(KeyCode::F11)
+        || (alt && keyboard_input.just_pressed(KeyCode::Return));
+
+    if !toggle {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary_mut() {
+        let mode = match window.mode() {
+            WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+            _ => WindowMode::Windowed,
+        };
+        window.set_mode(mode);
+    }
+}
+
+// The playfield is always the same size in the world, so wrapping and
+// spawning don't change with the window. The camera zooms so the whole
+// playfield still fits after a resize or going fullscreen.
+fn fit_playfield(
+    mut window_resized_event: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    mut camera_rig: ResMut<CameraRig>,
+) {
+    for event in window_resized_event.iter() {
+        let is_primary = windows
+            .get_primary()
+            .map_or(false, |window| window.id() == event.id);
+        if !is_primary || event.width <= 0.0 || event.height <= 0.0 {
+            continue;
+        }
+
+        camera_rig.window_size = Vec2::new(event.width, event.height);
+    }
+}
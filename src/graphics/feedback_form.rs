@@ -0,0 +1,154 @@
+// FEEDBACK FORM CODE
+
+use bevy::diagnostic::Diagnostics;
+use bevy::prelude::*;
+use bevy::window::ReceivedCharacter;
+
+use crate::graphics::toast::ToastEvent;
+use crate::logic::feedback::{diagnostic_bundle, post_report, save_report};
+use crate::logic::stats::RunStats;
+use crate::settings::GameSettings;
+use crate::AppState;
+
+// Short enough to fit on screen while it is typed
+const MAX_FEEDBACK_LENGTH: usize = 400;
+
+pub struct FeedbackFormPlugin;
+
+impl Plugin for FeedbackFormPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<FeedbackEntry>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Feedback).with_system(spawn_feedback_form.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Feedback)
+                    .with_system(type_feedback.system())
+                    .with_system(update_feedback_text.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Feedback).with_system(clear_feedback_form.system()),
+            );
+    }
+}
+
+// What has been typed so far
+#[derive(Default)]
+struct FeedbackEntry(String);
+
+struct FeedbackFormText;
+
+fn spawn_feedback_form(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut feedback_entry: ResMut<FeedbackEntry>,
+) {
+    feedback_entry.0.clear();
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        max_size: Size::new(Val::Px(1200.0), Val::Undefined),
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 36.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(FeedbackFormText);
+        })
+        .insert(FeedbackFormText);
+}
+
+// Enter saves the report with the diagnostics and goes back, Escape throws
+// it away. An empty report is ignored, which also keeps the Enter that
+// opened this screen from sending one straight away.
+#[allow(clippy::too_many_arguments)]
+fn type_feedback(
+    mut received_characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut feedback_entry: ResMut<FeedbackEntry>,
+    settings: Res<GameSettings>,
+    run_stats: Res<RunStats>,
+    diagnostics: Res<Diagnostics>,
+    mut toast_event: EventWriter<ToastEvent>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    for event in received_characters.iter() {
+        if !event.char.is_control() && feedback_entry.0.chars().count() < MAX_FEEDBACK_LENGTH {
+            feedback_entry.0.push(event.char);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        feedback_entry.0.pop();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        let _ = app_state.set(AppState::MainMenu);
+        return;
+    }
+
+    if !keyboard_input.just_pressed(KeyCode::Return) || feedback_entry.0.trim().is_empty() {
+        return;
+    }
+
+    let report = format!(
+        "{}\n\n{}\n",
+        feedback_entry.0.trim(),
+        diagnostic_bundle(&settings, &run_stats, &diagnostics)
+    );
+
+    if let Some(endpoint) = &settings.feedback_endpoint {
+        post_report(endpoint, report.clone());
+    }
+
+    match save_report(&report) {
+        Some(_) => toast_event.send(ToastEvent("Thanks for the feedback!".to_string())),
+        None => toast_event.send(ToastEvent("Could not save the feedback".to_string())),
+    }
+    let _ = app_state.set(AppState::MainMenu);
+}
+
+fn update_feedback_text(
+    feedback_entry: Res<FeedbackEntry>,
+    mut text_query: Query<&mut Text, With<FeedbackFormText>>,
+) {
+    let lines = [
+        "Found a bug or have an idea?".to_string(),
+        "Type it here, Enter to send, Escape to go back".to_string(),
+        "Your settings and last run are sent along with it".to_string(),
+        String::new(),
+        format!("{}_", feedback_entry.0),
+    ];
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_feedback_form(mut commands: Commands, text_query: Query<Entity, With<FeedbackFormText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,129 @@
+// SCREEN FLASH CODE
+
+use bevy::prelude::*;
+
+use crate::logic::game_time::GameTime;
+use crate::logic::player::PlayerHitEvent;
+use crate::logic::powerups::PowerUpPickedEvent;
+
+const FLASH_SECONDS: f32 = 0.35;
+const FLASH_PEAK_ALPHA: f32 = 0.6;
+
+pub struct FlashPlugin;
+
+impl Plugin for FlashPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<ScreenFlashEvent>()
+            .init_resource::<EffectSettings>()
+            .add_startup_system(spawn_flash_overlay.system())
+            .add_system(flash_on_damage.system())
+            .add_system(flash_on_pickup.system())
+            .add_system(start_flash.system())
+            .add_system(fade_flash.system());
+    }
+}
+
+// Player facing effect toggles
+pub struct EffectSettings {
+    // Turns off full screen flashes and other intense effects
+    pub reduce_motion: bool,
+    // Draws retro scanlines over the whole screen, with bloom and a vignette
+    pub crt_filter: bool,
+    // Drop shadows under the players and blocks
+    pub shadows: bool,
+}
+
+impl Default for EffectSettings {
+    fn default() -> Self {
+        EffectSettings {
+            reduce_motion: false,
+            crt_filter: false,
+            shadows: true,
+        }
+    }
+}
+
+// Send this to flash the whole screen a color, white for
+// pickups and red for damage
+pub struct ScreenFlashEvent(pub Color);
+
+struct ScreenFlash {
+    color: Color,
+    timer: Timer,
+}
+
+// A fullscreen UI node that stays invisible until something flashes
+fn spawn_flash_overlay(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    let mut timer = Timer::from_seconds(FLASH_SECONDS, false);
+    timer.tick(timer.duration());
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .insert(ScreenFlash {
+            color: Color::NONE,
+            timer,
+        });
+}
+
+fn flash_on_damage(
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    mut flash_event: EventWriter<ScreenFlashEvent>,
+) {
+    for _event in player_hit_event.iter() {
+        flash_event.send(ScreenFlashEvent(Color::RED));
+    }
+}
+
+fn flash_on_pickup(
+    mut powerup_picked_event: EventReader<PowerUpPickedEvent>,
+    mut flash_event: EventWriter<ScreenFlashEvent>,
+) {
+    for _event in powerup_picked_event.iter() {
+        flash_event.send(ScreenFlashEvent(Color::WHITE));
+    }
+}
+
+fn start_flash(
+    mut flash_event: EventReader<ScreenFlashEvent>,
+    mut flash_query: Query<&mut ScreenFlash>,
+    effect_settings: Res<EffectSettings>,
+) {
+    for event in flash_event.iter() {
+        if effect_settings.reduce_motion {
+            continue;
+        }
+
+        if let Ok(mut flash) = flash_query.single_mut() {
+            flash.color = event.0;
+            flash.timer.reset();
+        }
+    }
+}
+
+fn fade_flash(
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut flash_query: Query<(&mut ScreenFlash, &Handle<ColorMaterial>)>,
+    time: Res<GameTime>,
+) {
+    if let Ok((mut flash, material)) = flash_query.single_mut() {
+        if flash.timer.finished() {
+            return;
+        }
+
+        flash.timer.tick(time.delta());
+
+        if let Some(material) = materials.get_mut(material) {
+            let mut color = flash.color;
+            color.set_a(FLASH_PEAK_ALPHA * (1.0 - flash.timer.percent()));
+            material.color = color;
+        }
+    }
+}
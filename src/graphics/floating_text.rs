@@ -0,0 +1,69 @@
+// FLOATING TEXT CODE
+
+use bevy::prelude::*;
+
+use crate::graphics::score::ScoreEvent;
+use crate::logic::game_time::GameTime;
+use crate::logic::lifetime::DespawnAfter;
+use crate::logic::reset_game::RunScoped;
+
+// How long the points hang around
+const FLOAT_SECONDS: f32 = 0.8;
+// How fast the points drift up in 'pixels/second'
+const FLOAT_SPEED: f32 = 60.0;
+
+pub struct FloatingTextPlugin;
+
+impl Plugin for FloatingTextPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(spawn_score_text.system())
+            .add_system(float_text.system());
+    }
+}
+
+struct FloatingText;
+
+// Pops the points up right where they were earned
+fn spawn_score_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut score_event: EventReader<ScoreEvent>,
+) {
+    for event in score_event.iter() {
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    format!("+{}", event.points),
+                    TextStyle {
+                        font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                        font_size: 32.0,
+                        color: Color::rgb(1.0, 0.9, 0.3),
+                    },
+                    TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                ),
+                transform: Transform::from_xyz(event.position.x, event.position.y, 3.0),
+                ..Default::default()
+            })
+            .insert(FloatingText)
+            .insert(DespawnAfter::seconds(FLOAT_SECONDS))
+            .insert(RunScoped);
+    }
+}
+
+// Drift up and fade out until it goes away
+fn float_text(
+    time: Res<GameTime>,
+    mut text_query: Query<(&DespawnAfter, &mut Transform, &mut Text), With<FloatingText>>,
+) {
+    for (despawn_after, mut transform, mut text) in text_query.iter_mut() {
+        transform.translation.y += FLOAT_SPEED * time.delta_seconds();
+
+        let remaining = 1.0 - despawn_after.percent();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(remaining);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// FONTS CODE
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::logic::locale::Locales;
+use crate::settings::GameSettings;
+
+pub struct FontPlugin;
+
+impl Plugin for FontPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(load_ui_font.system())
+            .add_system(switch_ui_font.system())
+            .add_system(fall_back_on_missing_font.system())
+            .add_system(swap_fonts.system());
+    }
+}
+
+// The font for the selected language. Text is still made with the default
+// font everywhere, swap_fonts changes it over once it is spawned.
+pub struct UiFont(pub Handle<Font>);
+
+fn load_ui_font(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locales: Res<Locales>,
+    settings: Res<GameSettings>,
+) {
+    let font = asset_server.load(locales.current(&settings).font.as_str());
+    commands.insert_resource(UiFont(font));
+}
+
+fn switch_ui_font(
+    asset_server: Res<AssetServer>,
+    locales: Res<Locales>,
+    settings: Res<GameSettings>,
+    mut ui_font: ResMut<UiFont>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let font = asset_server.load(locales.current(&settings).font.as_str());
+    if font != ui_font.0 {
+        ui_font.0 = font;
+    }
+}
+
+// Boxes instead of letters are better than no text at all
+fn fall_back_on_missing_font(
+    asset_server: Res<AssetServer>,
+    locales: Res<Locales>,
+    mut ui_font: ResMut<UiFont>,
+) {
+    if asset_server.get_load_state(&ui_font.0) != LoadState::Failed {
+        return;
+    }
+
+    let default_font = &locales.default_locale().font;
+    let fallback = asset_server.load(default_font.as_str());
+    if fallback == ui_font.0 {
+        return;
+    }
+
+    println!(
+        "Could not load the font for this language, using {}",
+        default_font
+    );
+    ui_font.0 = fallback;
+}
+
+// New text, and all of it when the language changes
+fn swap_fonts(ui_font: Res<UiFont>, mut text_query: Query<(&mut Text, ChangeTrackers<Text>)>) {
+    for (mut text, tracker) in text_query.iter_mut() {
+        if !ui_font.is_changed() && !tracker.is_changed() {
+            continue;
+        }
+
+        // Checked first so text that is already right isn't marked changed
+        let needs_swap = text
+            .sections
+            .iter()
+            .any(|section| section.style.font != ui_font.0);
+        if !needs_swap {
+            continue;
+        }
+
+        for section in text.sections.iter_mut() {
+            section.style.font = ui_font.0.clone();
+        }
+    }
+}
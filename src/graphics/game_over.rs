@@ -0,0 +1,220 @@
+// GAME OVER CODE
+
+use bevy::prelude::*;
+
+use crate::graphics::score::Score;
+use crate::logic::high_scores::PendingHighScore;
+use crate::logic::mutators::ActiveMutators;
+use crate::logic::stats::RunStats;
+use crate::logic::upgrades::RunUpgrades;
+use crate::AppState;
+
+const BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON_COLOR: Color = Color::rgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON_COLOR: Color = Color::rgb(0.35, 0.75, 0.35);
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(load_button_materials.system())
+            .add_system_set(
+                SystemSet::on_enter(AppState::GameOver).with_system(spawn_run_summary.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver)
+                    .with_system(color_buttons.system())
+                    .with_system(press_buttons.system())
+                    .with_system(back_to_menu_on_q.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::GameOver).with_system(clear_run_summary.system()),
+            );
+    }
+}
+
+struct RunSummary;
+
+#[derive(Clone, Copy)]
+enum GameOverButton {
+    Retry,
+    BackToMenu,
+}
+
+struct ButtonMaterials {
+    normal: Handle<ColorMaterial>,
+    hovered: Handle<ColorMaterial>,
+    pressed: Handle<ColorMaterial>,
+}
+
+fn load_button_materials(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    commands.insert_resource(ButtonMaterials {
+        normal: materials.add(BUTTON_COLOR.into()),
+        hovered: materials.add(HOVERED_BUTTON_COLOR.into()),
+        pressed: materials.add(PRESSED_BUTTON_COLOR.into()),
+    });
+}
+
+// The run's numbers bottom left and the buttons along the bottom,
+// the rest of the game over text is centered between them
+#[allow(clippy::too_many_arguments)]
+fn spawn_run_summary(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    asset_server: Res<AssetServer>,
+    button_materials: Res<ButtonMaterials>,
+    run_stats: Res<RunStats>,
+    score: Res<Score>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+) {
+    let font = asset_server.load("fonts/Roboto-Thin.ttf");
+
+    let lines = [
+        "Run".to_string(),
+        format!("Survived: {:.1}s", run_stats.survival_seconds),
+        format!("Blocks dodged: {}", run_stats.blocks_dodged),
+        format!("Near misses: {}", run_stats.near_misses),
+        format!("Power-ups: {}", run_stats.powerups_collected),
+        format!("Score: {}", score.total(&mutators, &upgrades)),
+    ];
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(160.0),
+                    left: Val::Px(80.0),
+                    ..Default::default()
+                },
+                padding: Rect::all(Val::Px(20.0)),
+                ..Default::default()
+            },
+            material: materials.add(Color::rgba(0.0, 0.0, 0.0, 0.5).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    lines.join("\n"),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 32.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+        })
+        .insert(RunSummary);
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(40.0),
+                    left: Val::Px(0.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (button, label) in [
+                (GameOverButton::Retry, "Retry"),
+                (GameOverButton::BackToMenu, "Back to Menu"),
+            ]
+            .iter()
+            {
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(260.0), Val::Px(64.0)),
+                            margin: Rect::all(Val::Px(12.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        material: button_materials.normal.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                *label,
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 36.0,
+                                    color: Color::rgb(0.9, 0.9, 0.9),
+                                },
+                                Default::default(),
+                            ),
+                            ..Default::default()
+                        });
+                    })
+                    .insert(*button);
+            }
+        })
+        .insert(RunSummary);
+}
+
+fn color_buttons(
+    button_materials: Res<ButtonMaterials>,
+    mut button_query: Query<
+        (&Interaction, &mut Handle<ColorMaterial>),
+        (Changed<Interaction>, With<GameOverButton>),
+    >,
+) {
+    for (interaction, mut material) in button_query.iter_mut() {
+        *material = match *interaction {
+            Interaction::Clicked => button_materials.pressed.clone(),
+            Interaction::Hovered => button_materials.hovered.clone(),
+            Interaction::None => button_materials.normal.clone(),
+        };
+    }
+}
+
+// Retry does what R does, the menu clears any challenge or versus match.
+// Like R and Q the buttons wait for the initials, leaving now would
+// throw the high score away.
+fn press_buttons(
+    button_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    pending: Res<PendingHighScore>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Clicked || pending.is_pending() {
+            continue;
+        }
+
+        let _ = match button {
+            GameOverButton::Retry => app_state.set(AppState::InGame),
+            GameOverButton::BackToMenu => app_state.set(AppState::MainMenu),
+        };
+    }
+}
+
+// Q is a letter for the initials while a high score is being entered.
+// Not M, the main menu would take the same press as switching to mouse.
+fn back_to_menu_on_q(
+    keyboard_input: Res<Input<KeyCode>>,
+    pending: Res<PendingHighScore>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Q) && !pending.is_pending() {
+        let _ = app_state.set(AppState::MainMenu);
+    }
+}
+
+fn clear_run_summary(mut commands: Commands, summary_query: Query<Entity, With<RunSummary>>) {
+    for entity in summary_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
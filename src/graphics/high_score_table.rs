@@ -0,0 +1,253 @@
+// HIGH SCORE TABLE CODE
+
+use bevy::prelude::*;
+
+use crate::logic::high_scores::{HighScores, PendingHighScore};
+use crate::logic::leaderboard::{Leaderboard, LeaderboardQuery, LeaderboardView};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+pub struct HighScoreTablePlugin;
+
+impl Plugin for HighScoreTablePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(LeaderboardQuery {
+            view: LeaderboardView::Global,
+            page: 0,
+        })
+        .add_system_set(
+            SystemSet::on_enter(AppState::GameOver).with_system(spawn_high_score_table.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::GameOver).with_system(update_high_score_table.system()),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::GameOver).with_system(clear_high_score_table.system()),
+        )
+        // The same table on its own, from the main menu
+        .add_system_set(
+            SystemSet::on_enter(AppState::HighScores)
+                .with_system(spawn_high_score_table.system())
+                .with_system(spawn_leaderboard.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::HighScores)
+                .with_system(update_high_score_table.system())
+                .with_system(browse_leaderboard.system())
+                .with_system(update_leaderboard.system())
+                .with_system(leave_high_scores.system()),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::HighScores)
+                .with_system(clear_high_score_table.system())
+                .with_system(clear_leaderboard.system()),
+        );
+    }
+}
+
+struct HighScoreTableText;
+
+struct LeaderboardText;
+
+fn spawn_high_score_table(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(200.0),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 32.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(HighScoreTableText);
+}
+
+// The initials prompt sits on top of the table until it is filled in
+fn update_high_score_table(
+    high_scores: Res<HighScores>,
+    pending: Res<PendingHighScore>,
+    app_state: Res<State<AppState>>,
+    mut text_query: Query<&mut Text, With<HighScoreTableText>>,
+) {
+    let mut lines = Vec::new();
+
+    if let Some(entry) = pending.current() {
+        lines.push(match (&entry.player, entry.points) {
+            (Some(player), Some(points)) => format!(
+                "New high score for {}! {:.1}s, {} points",
+                player, entry.survival_seconds, points
+            ),
+            _ => format!("New high score! {:.1}s", entry.survival_seconds),
+        });
+        // The letter being changed is in brackets
+        let initials: Vec<String> = pending
+            .initials
+            .iter()
+            .enumerate()
+            .map(|(index, letter)| {
+                if index == pending.cursor {
+                    format!("[{}]", letter)
+                } else {
+                    format!(" {} ", letter)
+                }
+            })
+            .collect();
+        lines.push(format!("Your initials: {}", initials.concat()));
+        lines.push("Up/Down change, Left/Right move, Enter saves".to_string());
+        lines.push(String::new());
+    }
+
+    lines.push("High scores".to_string());
+    if high_scores.entries.is_empty() {
+        lines.push("None yet".to_string());
+    }
+    lines.extend(
+        high_scores
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| match entry.points {
+                Some(points) => format!(
+                    "{}. {} {:.1}s {} points",
+                    index + 1,
+                    entry.initials,
+                    entry.survival_seconds,
+                    points
+                ),
+                None => format!(
+                    "{}. {} {:.1}s",
+                    index + 1,
+                    entry.initials,
+                    entry.survival_seconds
+                ),
+            }),
+    );
+
+    if *app_state.current() == AppState::HighScores {
+        lines.push(String::new());
+        lines.push("Escape to go back".to_string());
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+// The leaderboard sits on the other side of the screen from the table
+fn spawn_leaderboard(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(200.0),
+                    left: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 32.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(LeaderboardText);
+}
+
+// Tab switches the view and starts it from the top, Left and Right page
+fn browse_leaderboard(keyboard_input: Res<Input<KeyCode>>, mut query: ResMut<LeaderboardQuery>) {
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        *query = LeaderboardQuery {
+            view: query.view.next(),
+            page: 0,
+        };
+    }
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        query.page += 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::Left) {
+        query.page = query.page.saturating_sub(1);
+    }
+}
+
+fn update_leaderboard(
+    mut leaderboard: ResMut<Leaderboard>,
+    query: Res<LeaderboardQuery>,
+    settings: Res<GameSettings>,
+    mut text_query: Query<&mut Text, With<LeaderboardText>>,
+) {
+    let mut lines = vec![format!(
+        "Leaderboard: {} (Tab to switch)",
+        query.view.label()
+    )];
+
+    match leaderboard.page(*query, &settings.username, &settings.friends) {
+        Some(page) => {
+            if page.runs.is_empty() {
+                lines.push("No runs here".to_string());
+            }
+            lines.extend(page.runs.iter().map(|ranked| {
+                let marker = if ranked.run.username.eq_ignore_ascii_case(&settings.username) {
+                    ">"
+                } else {
+                    " "
+                };
+                format!(
+                    "{}{}. {} {:.1}s",
+                    marker, ranked.rank, ranked.run.username, ranked.run.survival_seconds
+                )
+            }));
+            lines.push(String::new());
+            lines.push(format!(
+                "Page {} of {} (Left/Right)",
+                page.page + 1,
+                page.page_count
+            ));
+        }
+        None => lines.push("Offline, nothing saved for this page yet".to_string()),
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn leave_high_scores(keyboard_input: Res<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        let _ = app_state.set(AppState::MainMenu);
+    }
+}
+
+fn clear_high_score_table(
+    mut commands: Commands,
+    text_query: Query<Entity, With<HighScoreTableText>>,
+) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn clear_leaderboard(mut commands: Commands, text_query: Query<Entity, With<LeaderboardText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,366 @@
+// HUD CODE
+
+use bevy::prelude::*;
+
+use crate::graphics::score::Score;
+use crate::logic::combo::Combo;
+use crate::logic::lives::Lives;
+use crate::logic::mutators::ActiveMutators;
+use crate::logic::player::{Controls, Player, PlayerId};
+use crate::logic::rules::GameRules;
+use crate::logic::upgrades::RunUpgrades;
+use crate::logic::waves::WaveManager;
+
+// More lives than this still count, there just isn't room to show them
+const MAX_LIFE_ICONS: usize = 10;
+const LIFE_ICON_SIZE: f32 = 28.0;
+// Size of the cooldown bar in pixels
+const COOLDOWN_WIDTH: f32 = 240.0;
+const COOLDOWN_HEIGHT: f32 = 12.0;
+// Size of the bar under the combo multiplier
+const COMBO_BAR_WIDTH: f32 = 160.0;
+const COMBO_BAR_HEIGHT: f32 = 8.0;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(spawn_hud.system())
+            .add_system(update_score_text.system())
+            .add_system(update_life_icons.system())
+            .add_system(update_cooldown_bar.system())
+            .add_system(update_wave_indicator.system())
+            .add_system(update_combo_meter.system());
+    }
+}
+
+struct ScoreText;
+
+// The index is which life it stands for, they are shown left to right
+struct LifeIcon(usize);
+
+struct CooldownFill;
+
+struct WaveIndicator;
+
+// The multiplier text and bar, hidden while there is no combo
+struct ComboMeter;
+
+struct ComboText;
+
+struct ComboFill;
+
+// Everything is spawned once and only updated when what it shows changes.
+// Score top right with the wave and the combo under it, lives top left
+// and the teleport cooldown bottom center.
+fn spawn_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let font = asset_server.load("fonts/Roboto-Thin.ttf");
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(60.0),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "0",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 60.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Right,
+                },
+            ),
+            ..Default::default()
+        })
+        .insert(ScoreText);
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(130.0),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 32.0,
+                    color: Color::rgb(0.7, 0.7, 0.7),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(WaveIndicator);
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(175.0),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::FlexEnd,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font,
+                            font_size: 40.0,
+                            color: Color::rgb(1.0, 0.9, 0.3),
+                        },
+                        Default::default(),
+                    ),
+                    visible: Visible {
+                        is_visible: false,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .insert(ComboMeter)
+                .insert(ComboText);
+
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(COMBO_BAR_WIDTH), Val::Px(COMBO_BAR_HEIGHT)),
+                        ..Default::default()
+                    },
+                    material: materials.add(Color::rgba(0.2, 0.2, 0.2, 0.8).into()),
+                    visible: Visible {
+                        is_visible: false,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .insert(ComboMeter)
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                                ..Default::default()
+                            },
+                            material: materials.add(Color::rgb(1.0, 0.9, 0.3).into()),
+                            visible: Visible {
+                                is_visible: false,
+                                is_transparent: true,
+                            },
+                            ..Default::default()
+                        })
+                        .insert(ComboMeter)
+                        .insert(ComboFill);
+                });
+        });
+
+    let life_material = materials.add(asset_server.load("textures/block_3.png").into());
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(60.0),
+                    left: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for index in 0..MAX_LIFE_ICONS {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(LIFE_ICON_SIZE), Val::Px(LIFE_ICON_SIZE)),
+                            margin: Rect {
+                                right: Val::Px(8.0),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        material: life_material.clone(),
+                        ..Default::default()
+                    })
+                    .insert(LifeIcon(index));
+            }
+        });
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(40.0),
+                    left: Val::Percent(50.0),
+                    ..Default::default()
+                },
+                margin: Rect {
+                    left: Val::Px(-COOLDOWN_WIDTH / 2.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Px(COOLDOWN_WIDTH), Val::Px(COOLDOWN_HEIGHT)),
+                ..Default::default()
+            },
+            material: materials.add(Color::rgba(0.2, 0.2, 0.2, 0.8).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        ..Default::default()
+                    },
+                    material: materials.add(Color::rgb(0.3, 0.8, 1.0).into()),
+                    ..Default::default()
+                })
+                .insert(CooldownFill);
+        });
+}
+
+fn update_score_text(
+    score: Res<Score>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+    rules: Res<GameRules>,
+    mut text_query: Query<&mut Text, With<ScoreText>>,
+) {
+    let changed =
+        score.is_changed() || mutators.is_changed() || upgrades.is_changed() || rules.is_changed();
+    if !changed {
+        return;
+    }
+
+    let mut text = match text_query.single_mut() {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+
+    // Split scores get a line per player
+    text.sections[0].value = if score.is_split(&rules) {
+        score
+            .players
+            .iter()
+            .map(|player| {
+                format!(
+                    "{}: {}",
+                    player.controls,
+                    score.player_total(player, &mutators, &upgrades)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    } else {
+        score.total(&mutators, &upgrades).to_string()
+    };
+}
+
+// One icon per life the primary player has left, the rest are hidden
+fn update_life_icons(lives: Res<Lives>, mut icon_query: Query<(&LifeIcon, &mut Visible)>) {
+    if !lives.is_changed() {
+        return;
+    }
+
+    let lives_left = lives.left(PlayerId::from(Controls::Primary));
+    for (icon, mut visible) in icon_query.iter_mut() {
+        visible.is_visible = icon.0 < lives_left as usize;
+    }
+}
+
+// Refills as the primary player's teleport comes back,
+// and is brighter once it is ready
+fn update_cooldown_bar(
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    player_query: Query<(&Player, &Controls), Changed<Player>>,
+    mut fill_query: Query<(&mut Style, &Handle<ColorMaterial>), With<CooldownFill>>,
+) {
+    let charge = match player_query
+        .iter()
+        .find(|(_player, controls)| **controls == Controls::Primary)
+    {
+        Some((player, _controls)) => player.teleport_cooldown.percent(),
+        None => return,
+    };
+
+    for (mut style, material) in fill_query.iter_mut() {
+        style.size.width = Val::Percent(charge * 100.0);
+
+        if let Some(material) = materials.get_mut(material) {
+            material.color = if charge >= 1.0 {
+                Color::rgb(0.3, 0.8, 1.0)
+            } else {
+                Color::rgb(0.2, 0.4, 0.5)
+            };
+        }
+    }
+}
+
+fn update_wave_indicator(
+    waves: Res<WaveManager>,
+    mut text_query: Query<&mut Text, With<WaveIndicator>>,
+) {
+    if !waves.is_changed() {
+        return;
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        text.sections[0].value = format!("Wave {}", waves.wave);
+    }
+}
+
+// The bar drains as the window to keep the combo going runs out
+fn update_combo_meter(
+    combo: Res<Combo>,
+    mut meter_query: Query<&mut Visible, With<ComboMeter>>,
+    mut text_query: Query<&mut Text, With<ComboText>>,
+    mut fill_query: Query<&mut Style, With<ComboFill>>,
+) {
+    if !combo.is_changed() {
+        return;
+    }
+
+    let showing = combo.count > 0;
+    for mut visible in meter_query.iter_mut() {
+        if visible.is_visible != showing {
+            visible.is_visible = showing;
+        }
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        text.sections[0].value = format!("x{} combo", combo.multiplier());
+    }
+
+    for mut style in fill_query.iter_mut() {
+        style.size.width = Val::Percent(combo.time_left() * 100.0);
+    }
+}
@@ -0,0 +1,213 @@
+// KILL CAM CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+
+use super::camera::CameraRig;
+use crate::logic::blocks::Block;
+use crate::logic::game_time::GameTime;
+use crate::logic::player::{PlayerDeathEvent, PlayerHitEvent};
+use crate::logic::reset_game::{Resettable, RunScoped};
+use crate::logic::snapshots::{Snapshot, Snapshots};
+use crate::logic::time_scale::TimeScale;
+
+// How fast the replay plays compared to the real thing
+const REPLAY_SPEED: f32 = 0.4;
+const KILL_CAM_ZOOM: f32 = 0.5;
+// How long to linger on the impact once the replay catches up
+const HOLD_SECONDS: f64 = 0.5;
+
+pub struct KillCamPlugin;
+
+impl Plugin for KillCamPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<KillCamFinishedEvent>()
+            .init_resource::<KillCam>()
+            // A reset can cut the replay short, the ghosts are run scoped
+            .reset_on_restart::<KillCam>()
+            .reset_with(|camera_rig: &mut CameraRig| camera_rig.reset_view())
+            .add_system(remember_impact.system())
+            .add_system(start_kill_cam.system())
+            .add_system(play_kill_cam.system());
+    }
+}
+
+// Sent when the kill cam is over or has been skipped
+pub struct KillCamFinishedEvent;
+
+#[derive(Default)]
+struct KillCam {
+    playing: bool,
+    frames: Vec<Snapshot>,
+    elapsed: f32,
+    impact: Vec3,
+}
+
+// Stand in sprites that act out the replay
+struct ReplayGhost;
+
+// Which block in each snapshot the ghost follows
+struct BlockGhost(usize);
+
+fn remember_impact(
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    mut kill_cam: ResMut<KillCam>,
+) {
+    for event in player_hit_event.iter() {
+        kill_cam.impact = event.position;
+    }
+}
+
+// Once the player is gone, freeze the game and rewind
+// to the last couple seconds before the hit
+#[allow(clippy::too_many_arguments)]
+fn start_kill_cam(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    mut finished_event: EventWriter<KillCamFinishedEvent>,
+    mut kill_cam: ResMut<KillCam>,
+    mut snapshots: ResMut<Snapshots>,
+    mut time_scale: ResMut<TimeScale>,
+    mut camera_rig: ResMut<CameraRig>,
+) {
+    for _event in player_death_event.iter() {
+        if snapshots.0.is_empty() {
+            finished_event.send(KillCamFinishedEvent);
+            continue;
+        }
+
+        kill_cam.playing = true;
+        kill_cam.elapsed = 0.0;
+        kill_cam.frames = snapshots.0.drain(..).collect();
+        time_scale.0 = 0.0;
+
+        // Zoom in on the collision
+        camera_rig.focus = kill_cam.impact.truncate();
+        camera_rig.zoom = KILL_CAM_ZOOM;
+
+        let player_material = materials.add(asset_server.load("textures/block_3.png").into());
+        let block_material = materials.add(asset_server.load("textures/block_1.png").into());
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: ghost_sprite(),
+                material: player_material,
+                ..Default::default()
+            })
+            .insert(ReplayGhost)
+            .insert(RunScoped);
+
+        // Enough ghosts for the most crowded frame, extras get hidden
+        let most_blocks = kill_cam
+            .frames
+            .iter()
+            .map(|frame| frame.blocks.len())
+            .max()
+            .unwrap_or(0);
+
+        for index in 0..most_blocks {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: ghost_sprite(),
+                    material: block_material.clone(),
+                    ..Default::default()
+                })
+                .insert(ReplayGhost)
+                .insert(BlockGhost(index))
+                .insert(RunScoped);
+        }
+    }
+}
+
+fn ghost_sprite() -> Sprite {
+    Sprite {
+        resize_mode: SpriteResizeMode::Manual,
+        ..Default::default()
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn play_kill_cam(
+    mut commands: Commands,
+    mut kill_cam: ResMut<KillCam>,
+    mut time_scale: ResMut<TimeScale>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut finished_event: EventWriter<KillCamFinishedEvent>,
+    mut camera_rig: ResMut<CameraRig>,
+    ghost_entity_query: Query<Entity, With<ReplayGhost>>,
+    mut queries: QuerySet<(
+        Query<
+            (
+                &mut Transform,
+                &mut Sprite,
+                &mut Visible,
+                Option<&BlockGhost>,
+            ),
+            With<ReplayGhost>,
+        >,
+        Query<&mut Visible, With<Block>>,
+    )>,
+    time: Res<GameTime>,
+) {
+    if !kill_cam.playing {
+        return;
+    }
+
+    let skipped = keyboard_input.get_just_pressed().next().is_some();
+
+    kill_cam.elapsed += time.delta_seconds() * REPLAY_SPEED;
+
+    let start = kill_cam.frames[0].time;
+    let end = kill_cam.frames[kill_cam.frames.len() - 1].time;
+    let replay_time = start + kill_cam.elapsed as f64;
+
+    if skipped || replay_time > end + HOLD_SECONDS {
+        kill_cam.playing = false;
+        kill_cam.frames.clear();
+        time_scale.0 = 1.0;
+
+        for entity in ghost_entity_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        camera_rig.reset_view();
+
+        for mut visible in queries.q1_mut().iter_mut() {
+            visible.is_visible = true;
+        }
+
+        finished_event.send(KillCamFinishedEvent);
+        return;
+    }
+
+    // The latest snapshot that has happened by now in the replay
+    let frame = kill_cam
+        .frames
+        .iter()
+        .rev()
+        .find(|frame| frame.time <= replay_time)
+        .unwrap_or(&kill_cam.frames[0]);
+
+    for (mut transform, mut sprite, mut visible, block_ghost) in queries.q0_mut().iter_mut() {
+        let placement = match block_ghost {
+            Some(block_ghost) => frame.blocks.get(block_ghost.0),
+            None => Some(&frame.player),
+        };
+
+        match placement {
+            Some((translation, size)) => {
+                transform.translation = *translation;
+                sprite.size = *size;
+                visible.is_visible = true;
+            }
+            None => visible.is_visible = false,
+        }
+    }
+
+    // The real blocks are frozen in place, keep them out of the replay
+    for mut visible in queries.q1_mut().iter_mut() {
+        visible.is_visible = false;
+    }
+}
@@ -0,0 +1,72 @@
+// LIVES TEXT CODE
+
+use bevy::prelude::*;
+
+use crate::logic::lives::Respawn;
+
+pub struct LivesTextPlugin;
+
+impl Plugin for LivesTextPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(render_respawn_text.system())
+            .add_system(update_respawn_text.system());
+    }
+}
+
+struct RespawnText;
+
+// The lives themselves are icons on the HUD
+fn render_respawn_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // The countdown sits in the middle of the screen and is empty
+    // unless the player is waiting to come back
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 60.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(RespawnText);
+        });
+}
+
+fn update_respawn_text(respawn: Res<Respawn>, mut text_query: Query<&mut Text, With<RespawnText>>) {
+    if let Ok(mut text) = text_query.single_mut() {
+        // With co-op the soonest respawn is the one shown
+        let seconds_left = respawn
+            .pending
+            .iter()
+            .map(|pending| pending.timer.duration().as_secs_f32() - pending.timer.elapsed_secs())
+            .fold(None, |soonest: Option<f32>, seconds| {
+                Some(soonest.map_or(seconds, |soonest| soonest.min(seconds)))
+            });
+
+        text.sections[0].value = match seconds_left {
+            Some(seconds_left) => format!("Respawning in {}", seconds_left.ceil() as u32),
+            None => String::new(),
+        };
+    }
+}
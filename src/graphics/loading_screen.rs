@@ -0,0 +1,105 @@
+// LOADING SCREEN CODE
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use super::fonts::UiFont;
+use crate::AppState;
+
+// Small enough to load in a moment, and needed before anything can be shown.
+// The music isn't here, it can come in after the menu is up.
+// The font for the selected language is waited on as well.
+const ESSENTIAL_ASSETS: [&str; 5] = [
+    "fonts/Roboto-Thin.ttf",
+    "textures/bg.png",
+    "textures/block_1.png",
+    "textures/block_2.png",
+    "textures/block_3.png",
+];
+
+pub struct LoadingScreenPlugin;
+
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<LoadingAssets>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Loading).with_system(start_loading.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Loading).with_system(finish_loading.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Loading).with_system(clear_loading_screen.system()),
+            );
+    }
+}
+
+// Kept after loading too, so the assets stay loaded while nothing uses them
+#[derive(Default)]
+struct LoadingAssets(Vec<HandleUntyped>);
+
+struct LoadingText;
+
+fn start_loading(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ui_font: Res<UiFont>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    loading_assets.0 = ESSENTIAL_ASSETS
+        .iter()
+        .map(|path| asset_server.load_untyped(*path))
+        .collect();
+    loading_assets.0.push(ui_font.0.clone_untyped());
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(40.0),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "Loading...",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 40.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(LoadingText);
+}
+
+// Something that failed to load is missing from the game rather than
+// keeping it on the loading screen forever
+fn finish_loading(
+    asset_server: Res<AssetServer>,
+    loading_assets: Res<LoadingAssets>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    let ids = loading_assets.0.iter().map(|handle| handle.id);
+
+    match asset_server.get_group_load_state(ids) {
+        LoadState::Loaded => {
+            let _ = app_state.set(AppState::MainMenu);
+        }
+        LoadState::Failed => {
+            println!("Some assets could not be loaded, starting without them");
+            let _ = app_state.set(AppState::MainMenu);
+        }
+        _ => {}
+    }
+}
+
+fn clear_loading_screen(mut commands: Commands, text_query: Query<Entity, With<LoadingText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,231 @@
+// MENU CODE
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::graphics::stick_menu::StickMenu;
+use crate::logic::gamepad::ConnectedGamepads;
+use crate::AppState;
+
+const SELECTED_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+const UNSELECTED_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<MenuCursor>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(spawn_main_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu)
+                    .with_system(navigate_main_menu.system())
+                    .with_system(update_main_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::MainMenu).with_system(clear_main_menu.system()),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MenuItem {
+    Play,
+    Settings,
+    HighScores,
+    Feedback,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 5] = [
+        MenuItem::Play,
+        MenuItem::Settings,
+        MenuItem::HighScores,
+        MenuItem::Feedback,
+        MenuItem::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Play => "Play",
+            MenuItem::Settings => "Settings",
+            MenuItem::HighScores => "High Scores",
+            MenuItem::Feedback => "Send Feedback",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+// Kept between visits, so coming back from a screen lands on its item
+#[derive(Default)]
+struct MenuCursor(usize);
+
+struct MainMenu;
+
+struct MenuItemText(MenuItem);
+
+fn spawn_main_menu(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let font = asset_server.load("fonts/Roboto-Thin.ttf");
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                // Column reverse so the children go top to bottom
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    margin: Rect {
+                        bottom: Val::Px(40.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text::with_section(
+                    "Block Game",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 80.0,
+                        color: UNSELECTED_COLOR,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+
+            for item in MenuItem::ALL.iter() {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            item.label(),
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 48.0,
+                                color: UNSELECTED_COLOR,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(MenuItemText(*item));
+            }
+
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    margin: Rect {
+                        top: Val::Px(40.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text::with_section(
+                    "D daily, W weekly, X versus, Tab seed code, C challenges, U unlocks, K controls",
+                    TextStyle {
+                        font,
+                        font_size: 28.0,
+                        color: Color::rgb(0.6, 0.6, 0.6),
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+        })
+        .insert(MainMenu);
+}
+
+// Up and down or the d-pad pick an item, Enter or the bottom face button
+// picks it. The stick menu uses the arrows too while it is open.
+fn navigate_main_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<ConnectedGamepads>,
+    stick_menu: Res<StickMenu>,
+    mut cursor: ResMut<MenuCursor>,
+    mut app_state: ResMut<State<AppState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if stick_menu.open {
+        return;
+    }
+
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keyboard_input.just_pressed(key)
+            || gamepads
+                .0
+                .iter()
+                .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton(*gamepad, button)))
+    };
+
+    let item_count = MenuItem::ALL.len();
+
+    if pressed(KeyCode::Down, GamepadButtonType::DPadDown) {
+        cursor.0 = (cursor.0 + 1) % item_count;
+    }
+
+    if pressed(KeyCode::Up, GamepadButtonType::DPadUp) {
+        cursor.0 = (cursor.0 + item_count - 1) % item_count;
+    }
+
+    if !pressed(KeyCode::Return, GamepadButtonType::South) {
+        return;
+    }
+
+    match MenuItem::ALL[cursor.0] {
+        MenuItem::Play => {
+            let _ = app_state.set(AppState::InGame);
+        }
+        MenuItem::Settings => {
+            let _ = app_state.set(AppState::Settings);
+        }
+        MenuItem::HighScores => {
+            let _ = app_state.set(AppState::HighScores);
+        }
+        MenuItem::Feedback => {
+            let _ = app_state.set(AppState::Feedback);
+        }
+        MenuItem::Quit => exit.send(AppExit),
+    }
+}
+
+// Also runs once the items are spawned, so the cursor shows right away
+fn update_main_menu(
+    cursor: Res<MenuCursor>,
+    added_query: Query<Entity, Added<MenuItemText>>,
+    mut text_query: Query<(&MenuItemText, &mut Text)>,
+) {
+    if !cursor.is_changed() && added_query.iter().next().is_none() {
+        return;
+    }
+
+    let selected = MenuItem::ALL[cursor.0];
+    for (item, mut text) in text_query.iter_mut() {
+        let section = &mut text.sections[0];
+        if item.0 == selected {
+            section.value = format!("> {} <", item.0.label());
+            section.style.color = SELECTED_COLOR;
+        } else {
+            section.value = item.0.label().to_string();
+            section.style.color = UNSELECTED_COLOR;
+        }
+    }
+}
+
+fn clear_main_menu(mut commands: Commands, menu_query: Query<Entity, With<MainMenu>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
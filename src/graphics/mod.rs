@@ -1,3 +1,47 @@
 /// Block Game Graphics
+pub mod block_style;
+pub mod bot_menu;
+pub mod camera;
+pub mod camera_shake;
+pub mod challenge_menu;
+#[cfg(feature = "twitch")]
+pub mod chat_votes;
+pub mod controls_menu;
+pub mod crt;
+pub mod death;
+pub mod debug_overlay;
+pub mod difficulty_menu;
+pub mod display;
+pub mod feedback_form;
+pub mod flash;
+pub mod floating_text;
+pub mod fonts;
+pub mod game_over;
+pub mod high_score_table;
+pub mod hud;
+pub mod kill_cam;
+pub mod lives;
+pub mod loading_screen;
+pub mod menu;
+pub mod mutator_menu;
+pub mod particles;
+pub mod pause_menu;
+pub mod pixel_art;
+pub mod player_animation;
+pub mod rtl_layout;
 pub mod score;
+pub mod seed_menu;
+pub mod settings_menu;
+pub mod shadows;
+pub mod sprites;
+pub mod starfield;
+pub mod stick_menu;
 pub mod text;
+pub mod toast;
+pub mod trail;
+pub mod tween;
+pub mod unlock_tree;
+pub mod upgrade_cards;
+pub mod versus_hud;
+pub mod wave_banner;
+pub mod wrap_ghosts;
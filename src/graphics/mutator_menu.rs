@@ -0,0 +1,101 @@
+// MUTATOR MENU CODE
+
+use bevy::prelude::*;
+
+use crate::logic::mutators::{ActiveMutators, Mutator};
+use crate::AppState;
+
+pub struct MutatorMenuPlugin;
+
+impl Plugin for MutatorMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::MainMenu).with_system(spawn_mutator_menu.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::MainMenu)
+                .with_system(toggle_mutators.system())
+                .with_system(update_mutator_menu_text.system()),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::MainMenu).with_system(clear_mutator_menu.system()),
+        );
+    }
+}
+
+struct MutatorMenuText;
+
+// The number keys that toggle each mutator, in the same order as Mutator::ALL
+const MUTATOR_KEYS: [KeyCode; 5] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+];
+
+fn spawn_mutator_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(80.0),
+                    left: Val::Px(80.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 30.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(MutatorMenuText);
+}
+
+fn toggle_mutators(keyboard_input: Res<Input<KeyCode>>, mut mutators: ResMut<ActiveMutators>) {
+    for (key, mutator) in MUTATOR_KEYS.iter().zip(Mutator::ALL.iter()) {
+        if keyboard_input.just_pressed(*key) {
+            mutators.toggle(*mutator);
+        }
+    }
+}
+
+fn update_mutator_menu_text(
+    mutators: Res<ActiveMutators>,
+    mut text_query: Query<&mut Text, With<MutatorMenuText>>,
+) {
+    if let Ok(mut text) = text_query.single_mut() {
+        let mut lines = vec!["Mutators, press 1-5 to toggle".to_string()];
+        lines.extend(Mutator::ALL.iter().enumerate().map(|(index, mutator)| {
+            let check = if mutators.has(*mutator) { "x" } else { " " };
+            format!(
+                "{}: [{}] {} (x{:.2})",
+                index + 1,
+                check,
+                mutator,
+                mutator.score_multiplier()
+            )
+        }));
+
+        lines.push(format!(
+            "Score multiplier: x{:.2}",
+            mutators.score_multiplier()
+        ));
+
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_mutator_menu(mut commands: Commands, text_query: Query<Entity, With<MutatorMenuText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,252 @@
+// PARTICLES CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+use rand::{thread_rng, Rng};
+
+use crate::logic::blocks::BlockDestroyedEvent;
+use crate::logic::game_time::GameTime;
+use crate::logic::player::{PlayerHitEvent, TeleportEvent};
+use crate::logic::stats::NearMissEvent;
+
+// Every particle there can ever be is spawned up front and hidden
+const POOL_SIZE: usize = 512;
+// The most particles that can start in one frame, anything past it is
+// dropped so a bomb clearing the screen can't stall a frame
+const EMIT_BUDGET: usize = 96;
+const PARTICLE_SIZE: f32 = 6.0;
+// In front of the blocks and players
+const PARTICLE_DEPTH: f32 = 3.0;
+
+const DEATH_PARTICLES: usize = 40;
+const DEATH_PARTICLE_SPEED: f32 = 320.0;
+const DEATH_PARTICLE_SECONDS: f32 = 0.9;
+const DEATH_PARTICLE_COLOR: Color = Color::rgb(1.0, 0.85, 0.4);
+// A puff where the player left and another where they came out
+const TELEPORT_PARTICLES: usize = 16;
+const TELEPORT_PARTICLE_SPEED: f32 = 140.0;
+const TELEPORT_PARTICLE_SECONDS: f32 = 0.4;
+const TELEPORT_PARTICLE_COLOR: Color = Color::rgb(0.4, 0.8, 1.0);
+const DESTROYED_PARTICLES: usize = 16;
+const DESTROYED_PARTICLE_SPEED: f32 = 220.0;
+const DESTROYED_PARTICLE_SECONDS: f32 = 0.5;
+const DESTROYED_PARTICLE_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+// A few quick sparks off the block that got away
+const GRAZE_PARTICLES: usize = 8;
+const GRAZE_PARTICLE_SPEED: f32 = 180.0;
+const GRAZE_PARTICLE_SECONDS: f32 = 0.25;
+const GRAZE_PARTICLE_COLOR: Color = Color::rgb(1.0, 1.0, 0.7);
+
+const EMIT_PARTICLES: &str = "emit_particles";
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<ParticleBurstEvent>()
+            .init_resource::<ParticlePool>()
+            .add_startup_system(fill_particle_pool.system())
+            .add_system(burst_on_player_hit.system().before(EMIT_PARTICLES))
+            .add_system(burst_on_teleport.system().before(EMIT_PARTICLES))
+            .add_system(burst_on_block_destroyed.system().before(EMIT_PARTICLES))
+            .add_system(burst_on_graze.system().before(EMIT_PARTICLES))
+            .add_system(emit_particles.system().label(EMIT_PARTICLES))
+            .add_system(update_particles.system());
+    }
+}
+
+// Send this for a burst of particles flying out from a point
+pub struct ParticleBurstEvent {
+    pub position: Vec3,
+    pub color: Color,
+    pub count: usize,
+    // The fastest a particle goes in 'pixels/second', the rest are slower
+    pub speed: f32,
+    pub seconds: f32,
+}
+
+// The hidden particles waiting to be used
+#[derive(Default)]
+pub struct ParticlePool {
+    free: Vec<Entity>,
+}
+
+impl ParticlePool {
+    pub fn size(&self) -> usize {
+        POOL_SIZE
+    }
+
+    pub fn in_use(&self) -> usize {
+        POOL_SIZE - self.free.len()
+    }
+}
+
+struct Particle {
+    velocity: Vec2,
+    color: Color,
+    timer: Timer,
+}
+
+// Each particle has its own material so it can fade on its own
+fn fill_particle_pool(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pool: ResMut<ParticlePool>,
+) {
+    for _ in 0..POOL_SIZE {
+        let particle = commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    size: Vec2::new(PARTICLE_SIZE, PARTICLE_SIZE),
+                    resize_mode: SpriteResizeMode::Manual,
+                    ..Default::default()
+                },
+                material: materials.add(Color::NONE.into()),
+                visible: Visible {
+                    is_visible: false,
+                    is_transparent: true,
+                },
+                ..Default::default()
+            })
+            .insert(Particle {
+                velocity: Vec2::ZERO,
+                color: Color::NONE,
+                timer: Timer::from_seconds(0.0, false),
+            })
+            .id();
+        pool.free.push(particle);
+    }
+}
+
+fn burst_on_player_hit(
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    mut particle_burst_event: EventWriter<ParticleBurstEvent>,
+) {
+    for event in player_hit_event.iter() {
+        particle_burst_event.send(ParticleBurstEvent {
+            position: event.position,
+            color: DEATH_PARTICLE_COLOR,
+            count: DEATH_PARTICLES,
+            speed: DEATH_PARTICLE_SPEED,
+            seconds: DEATH_PARTICLE_SECONDS,
+        });
+    }
+}
+
+fn burst_on_teleport(
+    mut teleport_event: EventReader<TeleportEvent>,
+    mut particle_burst_event: EventWriter<ParticleBurstEvent>,
+) {
+    for event in teleport_event.iter() {
+        for position in [event.from, event.to].iter() {
+            particle_burst_event.send(ParticleBurstEvent {
+                position: *position,
+                color: TELEPORT_PARTICLE_COLOR,
+                count: TELEPORT_PARTICLES,
+                speed: TELEPORT_PARTICLE_SPEED,
+                seconds: TELEPORT_PARTICLE_SECONDS,
+            });
+        }
+    }
+}
+
+// Bombs send their own bursts, they clear blocks without this event
+fn burst_on_block_destroyed(
+    mut block_destroyed_event: EventReader<BlockDestroyedEvent>,
+    mut particle_burst_event: EventWriter<ParticleBurstEvent>,
+) {
+    for event in block_destroyed_event.iter() {
+        particle_burst_event.send(ParticleBurstEvent {
+            position: event.position,
+            color: DESTROYED_PARTICLE_COLOR,
+            count: DESTROYED_PARTICLES,
+            speed: DESTROYED_PARTICLE_SPEED,
+            seconds: DESTROYED_PARTICLE_SECONDS,
+        });
+    }
+}
+
+fn burst_on_graze(
+    mut near_miss_event: EventReader<NearMissEvent>,
+    mut particle_burst_event: EventWriter<ParticleBurstEvent>,
+) {
+    for event in near_miss_event.iter() {
+        particle_burst_event.send(ParticleBurstEvent {
+            position: event.position,
+            color: GRAZE_PARTICLE_COLOR,
+            count: GRAZE_PARTICLES,
+            speed: GRAZE_PARTICLE_SPEED,
+            seconds: GRAZE_PARTICLE_SECONDS,
+        });
+    }
+}
+
+// Wakes up particles from the pool, up to the budget for the frame.
+// Bursts past the budget or past the end of the pool come out smaller.
+fn emit_particles(
+    mut particle_burst_event: EventReader<ParticleBurstEvent>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(&mut Particle, &mut Transform, &mut Visible)>,
+) {
+    let mut rng = thread_rng();
+    let mut budget = EMIT_BUDGET;
+
+    for event in particle_burst_event.iter() {
+        for _ in 0..event.count.min(budget) {
+            let entity = match pool.free.pop() {
+                Some(entity) => entity,
+                None => return,
+            };
+            budget -= 1;
+
+            if let Ok((mut particle, mut transform, mut visible)) = particle_query.get_mut(entity) {
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let speed = rng.gen_range(0.3..1.0) * event.speed;
+                particle.velocity = Vec2::new(angle.cos(), angle.sin()) * speed;
+                particle.color = event.color;
+                particle.timer = Timer::from_seconds(event.seconds, false);
+                transform.translation = event.position.truncate().extend(PARTICLE_DEPTH);
+                visible.is_visible = true;
+            }
+        }
+    }
+}
+
+// NOTE
+// Particles run on real time like the death fragments,
+// so they keep moving during the slow motion beat.
+fn update_particles(
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut Visible,
+        &Handle<ColorMaterial>,
+    )>,
+    time: Res<GameTime>,
+) {
+    let delta_time = time.delta_seconds();
+
+    for (entity, mut particle, mut transform, mut visible, material) in particle_query.iter_mut() {
+        if !visible.is_visible {
+            continue;
+        }
+
+        particle.timer.tick(time.delta());
+        if particle.timer.finished() {
+            visible.is_visible = false;
+            pool.free.push(entity);
+            continue;
+        }
+
+        transform.translation += particle.velocity.extend(0.0) * delta_time;
+
+        if let Some(material) = materials.get_mut(material) {
+            let mut color = particle.color;
+            color.set_a(particle.color.a() * (1.0 - particle.timer.percent()));
+            material.color = color;
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// PAUSE MENU CODE
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::logic::idle::AutoPaused;
+use crate::logic::input_map::{Action, InputMap};
+use crate::logic::time_scale::RampTimeScaleEvent;
+use crate::AppState;
+
+const PAUSE_OPTIONS: [PauseOption; 2] = [PauseOption::Resume, PauseOption::Quit];
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<PauseCursor>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(pause_on_key.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Paused).with_system(spawn_pause_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Paused)
+                    .with_system(navigate_pause_menu.system())
+                    .with_system(update_pause_menu_text.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Paused).with_system(clear_pause_menu.system()),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PauseOption {
+    Resume,
+    Quit,
+}
+
+// Which option is selected
+#[derive(Default)]
+struct PauseCursor(usize);
+
+struct PauseMenu;
+
+struct PauseMenuText;
+
+// Paused is pushed on top of InGame so every InGame system stops
+// where it is, and popping it carries on from the same spot.
+fn pause_on_key(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if input_map.take_just_pressed(&mut keyboard_input, Action::Pause) {
+        let _ = app_state.push(AppState::Paused);
+    }
+}
+
+fn spawn_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut cursor: ResMut<PauseCursor>,
+) {
+    cursor.0 = 0;
+
+    // A translucent overlay over the whole screen to dim the game
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::rgba(0.0, 0.0, 0.0, 0.6).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 48.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(PauseMenuText);
+        })
+        .insert(PauseMenu);
+}
+
+// Up and down pick an option, Enter uses it, the pause key resumes
+fn navigate_pause_menu(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut cursor: ResMut<PauseCursor>,
+    mut app_state: ResMut<State<AppState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let option_count = PAUSE_OPTIONS.len();
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        cursor.0 = (cursor.0 + 1) % option_count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        cursor.0 = (cursor.0 + option_count - 1) % option_count;
+    }
+
+    if input_map.take_just_pressed(&mut keyboard_input, Action::Pause) {
+        let _ = app_state.pop();
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        match PAUSE_OPTIONS[cursor.0] {
+            PauseOption::Resume => {
+                let _ = app_state.pop();
+            }
+            PauseOption::Quit => exit.send(AppExit),
+        }
+    }
+}
+
+fn update_pause_menu_text(
+    cursor: Res<PauseCursor>,
+    auto_paused: Res<AutoPaused>,
+    mut text_query: Query<&mut Text, With<PauseMenuText>>,
+) {
+    let title = if auto_paused.0 {
+        "Are you still there?"
+    } else {
+        "Paused"
+    };
+    let mut lines = vec![title.to_string(), String::new()];
+
+    lines.extend(PAUSE_OPTIONS.iter().enumerate().map(|(index, option)| {
+        let cursor_mark = if index == cursor.0 { "> " } else { "  " };
+        let label = match option {
+            PauseOption::Resume => "Resume",
+            PauseOption::Quit => "Quit",
+        };
+
+        format!("{}{}", cursor_mark, label)
+    }));
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_pause_menu(
+    mut commands: Commands,
+    pause_menu_query: Query<Entity, With<PauseMenu>>,
+    mut ramp_event: EventWriter<RampTimeScaleEvent>,
+) {
+    for entity in pause_menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // Ease back in so the player has a moment to react
+    ramp_event.send(RampTimeScaleEvent);
+}
@@ -0,0 +1,318 @@
+// PIXEL ART CODE
+
+use bevy::asset::HandleId;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::camera::{ActiveCameras, OrthographicProjection, ScalingMode};
+use bevy::render::pass::{
+    LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+    RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
+};
+use bevy::render::render_graph::base::{node::MAIN_PASS, MainPass};
+use bevy::render::render_graph::{CameraNode, PassNode, RenderGraph, TextureNode};
+use bevy::render::texture::{
+    Extent3d, FilterMode, SamplerDescriptor, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureUsage,
+};
+use bevy::ui::Node;
+
+use super::camera::CameraRig;
+use super::crt::{CrtPass, CrtScreen};
+use crate::settings::GameSettings;
+use crate::WINDOWWIDTH;
+
+// The size of the texture the playfield is drawn into. It is a little
+// taller than the playfield, the rows above and below it are border.
+const VIRTUAL_WIDTH: f32 = 480.0;
+const VIRTUAL_HEIGHT: f32 = 432.0;
+
+pub const PIXEL_ART_PASS: &str = "pixel_art_pass";
+const PIXEL_ART_CAMERA: &str = "pixel_art_camera";
+const PIXEL_ART_TEXTURE: &str = "pixel_art_texture";
+const PIXEL_ART_DEPTH: &str = "pixel_art_depth";
+
+// The texture the pixel art pass draws into, the screen shows it
+const PIXEL_ART_TEXTURE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Texture::TYPE_UUID, 0x5e1f_c0de_b10c_0795);
+
+// The sprite nodes fill in what the sprites are drawn with, they have to
+// be done before the pass like they are before the main pass
+pub const SPRITE_NODES: [&str; 4] = [
+    "color_material",
+    "sprite",
+    "sprite_sheet",
+    "sprite_sheet_sprite",
+];
+
+pub struct PixelArtPlugin;
+
+impl Plugin for PixelArtPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let world = app.world_mut();
+        // Nothing is drawn when running headless
+        if let Some(mut active_cameras) = world.get_resource_mut::<ActiveCameras>() {
+            active_cameras.add(PIXEL_ART_CAMERA);
+        }
+        if let Some(mut render_graph) = world.get_resource_mut::<RenderGraph>() {
+            add_pixel_art_pass(&mut render_graph);
+        }
+
+        app.add_startup_system(spawn_pixel_art_camera.system())
+            .add_system(follow_pixel_art_setting.system())
+            .add_system(swap_render_passes.system())
+            .add_system(filter_textures.system());
+    }
+}
+
+// Sprites with this are drawn into the small texture instead of the window
+#[derive(Default)]
+pub struct PixelArtPass;
+
+// Draws the playfield into the small texture, the gameplay camera moves
+// it around with the camera rig while pixel art is on
+pub struct PixelArtCamera(pub Entity);
+
+// The small texture scaled up on the window
+struct PixelArtScreen;
+
+// How many world units one of the big pixels covers
+pub fn world_per_pixel() -> f32 {
+    WINDOWWIDTH / VIRTUAL_WIDTH
+}
+
+// World units per window pixel with every big pixel a whole number of
+// window pixels wide. Whatever is left of the window is border.
+pub fn pixel_art_scale(window_size: Vec2) -> f32 {
+    let zoom = (window_size.x / VIRTUAL_WIDTH)
+        .min(window_size.y / VIRTUAL_HEIGHT)
+        .floor()
+        .max(1.0);
+    world_per_pixel() / zoom
+}
+
+// A pass of its own that draws into a texture, before the main pass
+// draws that texture on the screen
+fn add_pixel_art_pass(render_graph: &mut RenderGraph) {
+    let size = Extent3d::new(VIRTUAL_WIDTH as u32, VIRTUAL_HEIGHT as u32, 1);
+
+    let mut pass_node = PassNode::<&PixelArtPass>::new(PassDescriptor {
+        color_attachments: vec![RenderPassColorAttachmentDescriptor {
+            attachment: TextureAttachment::Input("color_attachment".to_string()),
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(Color::BLACK),
+                store: true,
+            },
+        }],
+        depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+            attachment: TextureAttachment::Input("depth".to_string()),
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: None,
+        }),
+        sample_count: 1,
+    });
+    pass_node.add_camera(PIXEL_ART_CAMERA);
+
+    render_graph.add_node(PIXEL_ART_PASS, pass_node);
+    render_graph.add_system_node(PIXEL_ART_CAMERA, CameraNode::new(PIXEL_ART_CAMERA));
+    render_graph.add_node(
+        PIXEL_ART_TEXTURE,
+        TextureNode::new(
+            TextureDescriptor {
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                // The same as the window, the sprite pipelines are made for it
+                format: TextureFormat::default(),
+                usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+            },
+            // Nearest neighbour keeps the big pixels square
+            Some(SamplerDescriptor {
+                mag_filter: FilterMode::Nearest,
+                min_filter: FilterMode::Nearest,
+                ..Default::default()
+            }),
+            Some(PIXEL_ART_TEXTURE_HANDLE),
+        ),
+    );
+    render_graph.add_node(
+        PIXEL_ART_DEPTH,
+        TextureNode::new(
+            TextureDescriptor {
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Depth32Float,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+            },
+            None,
+            None,
+        ),
+    );
+
+    let edges = render_graph
+        .add_node_edge(PIXEL_ART_CAMERA, PIXEL_ART_PASS)
+        .and_then(|_| {
+            render_graph.add_slot_edge(
+                PIXEL_ART_TEXTURE,
+                TextureNode::TEXTURE,
+                PIXEL_ART_PASS,
+                "color_attachment",
+            )
+        })
+        .and_then(|_| {
+            render_graph.add_slot_edge(
+                PIXEL_ART_DEPTH,
+                TextureNode::TEXTURE,
+                PIXEL_ART_PASS,
+                "depth",
+            )
+        })
+        .and_then(|_| render_graph.add_node_edge(PIXEL_ART_PASS, MAIN_PASS));
+    if let Err(error) = edges {
+        println!("Could not set up the pixel art pass: {:?}", error);
+    }
+
+    for node in SPRITE_NODES.iter() {
+        if render_graph.add_node_edge(*node, PIXEL_ART_PASS).is_err() {
+            println!("Could not draw the pixel art pass after {}", node);
+        }
+    }
+}
+
+// The camera always sees the whole texture, however big the window is.
+// The screen is as big in the world as the texture, so the gameplay
+// camera shows it like it would the playfield.
+fn spawn_pixel_art_camera(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    settings: Res<GameSettings>,
+) {
+    let mut camera = OrthographicCameraBundle::new_2d();
+    camera.camera.name = Some(PIXEL_ART_CAMERA.to_string());
+    camera.orthographic_projection = OrthographicProjection {
+        left: -VIRTUAL_WIDTH / 2.0,
+        right: VIRTUAL_WIDTH / 2.0,
+        bottom: -VIRTUAL_HEIGHT / 2.0,
+        top: VIRTUAL_HEIGHT / 2.0,
+        far: camera.orthographic_projection.far,
+        scaling_mode: ScalingMode::None,
+        ..Default::default()
+    };
+    camera.transform.scale = Vec3::new(world_per_pixel(), world_per_pixel(), 1.0);
+    let pixel_art_camera = commands.spawn_bundle(camera).id();
+    commands.insert_resource(PixelArtCamera(pixel_art_camera));
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(Vec2::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT) * world_per_pixel()),
+            material: materials.add(ColorMaterial::texture(PIXEL_ART_TEXTURE_HANDLE.typed())),
+            visible: Visible {
+                is_visible: settings.pixel_art,
+                is_transparent: false,
+            },
+            ..Default::default()
+        })
+        .insert(PixelArtScreen);
+}
+
+fn follow_pixel_art_setting(
+    settings: Res<GameSettings>,
+    mut camera_rig: ResMut<CameraRig>,
+    mut screen_query: Query<&mut Visible, With<PixelArtScreen>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if camera_rig.pixel_art != settings.pixel_art {
+        camera_rig.pixel_art = settings.pixel_art;
+    }
+    for mut visible in screen_query.iter_mut() {
+        visible.is_visible = settings.pixel_art;
+    }
+}
+
+// Everything drawn in the world goes into the small texture while pixel
+// art is on, and back to the window once it is off. The menus and HUD
+// stay sharp either way. With the CRT filter on the world is in its pass
+// instead of the main one, the CRT swap takes it back from the window.
+#[allow(clippy::type_complexity)]
+fn swap_render_passes(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    world_query: Query<
+        Entity,
+        (
+            Or<(With<MainPass>, With<CrtPass>)>,
+            Without<Node>,
+            Without<PixelArtScreen>,
+            Without<CrtScreen>,
+        ),
+    >,
+    pixel_art_pass_query: Query<Entity, With<PixelArtPass>>,
+) {
+    if settings.pixel_art {
+        for entity in world_query.iter() {
+            commands
+                .entity(entity)
+                .remove::<MainPass>()
+                .remove::<CrtPass>()
+                .insert(PixelArtPass);
+        }
+    } else {
+        for entity in pixel_art_pass_query.iter() {
+            commands
+                .entity(entity)
+                .remove::<PixelArtPass>()
+                .insert(MainPass);
+        }
+    }
+}
+
+// Nearest neighbour on the sprites themselves as well, so they stay
+// blocky when they are drawn smaller than their texture.
+// New textures are set as they load, all of them when the setting changes.
+fn filter_textures(
+    settings: Res<GameSettings>,
+    mut texture_event: EventReader<AssetEvent<Texture>>,
+    mut textures: ResMut<Assets<Texture>>,
+) {
+    let filter = if settings.pixel_art {
+        FilterMode::Nearest
+    } else {
+        FilterMode::Linear
+    };
+
+    let handles: Vec<HandleId> = if settings.is_changed() {
+        textures.ids().collect()
+    } else {
+        texture_event
+            .iter()
+            .filter_map(|event| match event {
+                AssetEvent::Created { handle } => Some(handle.id),
+                _ => None,
+            })
+            .collect()
+    };
+
+    for id in handles {
+        // Checked first, changing a texture sends it to the GPU again
+        let needs_filter = textures
+            .get(id)
+            .map_or(false, |texture| texture.sampler.mag_filter != filter);
+        if !needs_filter {
+            continue;
+        }
+
+        if let Some(texture) = textures.get_mut(id) {
+            texture.sampler.mag_filter = filter;
+            texture.sampler.min_filter = filter;
+        }
+    }
+}
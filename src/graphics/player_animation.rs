@@ -0,0 +1,207 @@
+// PLAYER ANIMATION CODE
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::logic::game_time::GameTime;
+use crate::logic::player::{AnimationState, Player};
+
+// One row per animation, the rows are picked in animation()
+const SHEET_PATH: &str = "sprites/player_sheet.png";
+const FRAME_SIZE: f32 = 32.0;
+const SHEET_COLUMNS: usize = 6;
+const SHEET_ROWS: usize = 4;
+// Just over the player's own sprite
+const ANIMATION_DEPTH: f32 = 0.01;
+
+pub struct PlayerAnimationPlugin;
+
+impl Plugin for PlayerAnimationPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(load_player_sheet.system())
+            .add_system(add_player_animation.system())
+            .add_system(animate_players.system())
+            .add_system(clear_orphaned_animations.system());
+    }
+}
+
+struct Animation {
+    row: usize,
+    frames: usize,
+    frames_per_second: f32,
+    looping: bool,
+}
+
+fn animation(state: AnimationState) -> Animation {
+    match state {
+        AnimationState::Idle => Animation {
+            row: 0,
+            frames: 4,
+            frames_per_second: 6.0,
+            looping: true,
+        },
+        AnimationState::Moving => Animation {
+            row: 1,
+            frames: 6,
+            frames_per_second: 12.0,
+            looping: true,
+        },
+        AnimationState::Teleporting => Animation {
+            row: 2,
+            frames: 5,
+            frames_per_second: 30.0,
+            looping: false,
+        },
+        AnimationState::Dying => Animation {
+            row: 3,
+            frames: 6,
+            frames_per_second: 8.0,
+            looping: false,
+        },
+    }
+}
+
+struct PlayerSheet {
+    texture: Handle<Texture>,
+    atlas: Handle<TextureAtlas>,
+}
+
+// Drawn as a child of the player. The player keeps its own sprite since
+// everything from collisions to shadows goes by it, it is just hidden
+// once the sheet has loaded. Without the sheet the player looks as before.
+// Wrap ghosts copy this along with the hidden sprite.
+struct PlayerAnimation {
+    playing: AnimationState,
+    frame: usize,
+    timer: Timer,
+}
+
+impl PlayerAnimation {
+    fn new(state: AnimationState) -> Self {
+        PlayerAnimation {
+            playing: state,
+            frame: 0,
+            timer: Timer::from_seconds(1.0 / animation(state).frames_per_second, true),
+        }
+    }
+}
+
+fn load_player_sheet(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    let texture = asset_server.load(SHEET_PATH);
+    let atlas = TextureAtlas::from_grid(
+        texture.clone(),
+        Vec2::new(FRAME_SIZE, FRAME_SIZE),
+        SHEET_COLUMNS,
+        SHEET_ROWS,
+    );
+
+    commands.insert_resource(PlayerSheet {
+        texture,
+        atlas: atlases.add(atlas),
+    });
+}
+
+fn add_player_animation(
+    mut commands: Commands,
+    sheet: Res<PlayerSheet>,
+    player_query: Query<(Entity, &Sprite), Added<Player>>,
+) {
+    for (entity, sprite) in player_query.iter() {
+        let mut transform = Transform::from_xyz(0.0, 0.0, ANIMATION_DEPTH);
+        transform.scale = (sprite.size / FRAME_SIZE).extend(1.0);
+
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteSheetBundle {
+                    texture_atlas: sheet.atlas.clone(),
+                    transform,
+                    visible: Visible {
+                        is_visible: false,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .insert(PlayerAnimation::new(AnimationState::Idle));
+        });
+    }
+}
+
+// NOTE
+// Runs on real time like the death fragments, so dying plays out at
+// full speed while the rest of the game is slowed down.
+#[allow(clippy::type_complexity)]
+fn animate_players(
+    asset_server: Res<AssetServer>,
+    sheet: Res<PlayerSheet>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut player_query: Query<(&mut AnimationState, &Visible, &Handle<ColorMaterial>), With<Player>>,
+    mut animation_query: Query<
+        (
+            &Parent,
+            &mut PlayerAnimation,
+            &mut TextureAtlasSprite,
+            &mut Visible,
+        ),
+        Without<Player>,
+    >,
+    time: Res<GameTime>,
+) {
+    let loaded = asset_server.get_load_state(&sheet.texture) == LoadState::Loaded;
+
+    for (parent, mut player_animation, mut atlas_sprite, mut visible) in animation_query.iter_mut()
+    {
+        let (mut state, player_visible, player_material) = match player_query.get_mut(parent.0) {
+            Ok(player) => player,
+            Err(_) => continue,
+        };
+
+        // The player is hidden while dying, the animation is how it goes
+        visible.is_visible =
+            loaded && (player_visible.is_visible || *state == AnimationState::Dying);
+        if !loaded {
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(player_material) {
+            if material.color.a() != 0.0 {
+                material.color.set_a(0.0);
+            }
+        }
+
+        let clip = animation(*state);
+        if player_animation.playing != *state {
+            *player_animation = PlayerAnimation::new(*state);
+        }
+
+        player_animation.timer.tick(time.delta());
+        for _ in 0..player_animation.timer.times_finished() {
+            if player_animation.frame + 1 < clip.frames {
+                player_animation.frame += 1;
+            } else if clip.looping {
+                player_animation.frame = 0;
+            } else if *state == AnimationState::Teleporting {
+                // Back to walking, the movement picks which on the next frame
+                *state = AnimationState::Idle;
+            }
+        }
+
+        atlas_sprite.index = (clip.row * SHEET_COLUMNS + player_animation.frame) as u32;
+    }
+}
+
+// Players are despawned on their own rather than recursively
+fn clear_orphaned_animations(
+    mut commands: Commands,
+    animation_query: Query<(Entity, &Parent), With<PlayerAnimation>>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    for (entity, parent) in animation_query.iter() {
+        if player_query.get(parent.0).is_err() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
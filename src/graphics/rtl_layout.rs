@@ -0,0 +1,88 @@
+// RTL LAYOUT CODE
+
+use bevy::prelude::*;
+
+use crate::logic::locale::Locales;
+use crate::settings::GameSettings;
+
+pub struct RtlLayoutPlugin;
+
+impl Plugin for RtlLayoutPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(mirror_layout.system());
+    }
+}
+
+// Set on everything that is laid out right to left right now
+struct Mirrored;
+
+// Every screen is laid out left to right in code. For a right to left
+// language each node is flipped once it is spawned, and flipped back if
+// the language changes again. That covers the HUD and the menus, and the
+// floating text through its alignment, without each of them knowing.
+//
+// NOTE
+// Only the layout is mirrored. Bevy lays text out one glyph after another
+// with no shaping, so Arabic letters don't join up yet.
+#[allow(clippy::type_complexity)]
+fn mirror_layout(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    locales: Res<Locales>,
+    mut layout_query: Query<
+        (
+            Entity,
+            Option<&mut Style>,
+            Option<&mut Text>,
+            Option<&Parent>,
+            Option<&Mirrored>,
+        ),
+        Or<(With<Style>, With<Text>)>,
+    >,
+) {
+    let right_to_left = locales.current(&settings).right_to_left;
+
+    for (entity, style, text, parent, mirrored) in layout_query.iter_mut() {
+        if mirrored.is_some() == right_to_left {
+            continue;
+        }
+
+        if let Some(mut style) = style {
+            mirror_style(&mut style, parent.is_none());
+        }
+        if let Some(mut text) = text {
+            mirror_alignment(&mut text.alignment);
+        }
+
+        if right_to_left {
+            commands.entity(entity).insert(Mirrored);
+        } else {
+            commands.entity(entity).remove::<Mirrored>();
+        }
+    }
+}
+
+// Only the top level nodes are anchored to the screen, the ones inside them
+// follow along from their parent's flipped rows and margins.
+// Running it twice puts everything back.
+fn mirror_style(style: &mut Style, is_root: bool) {
+    if is_root && style.position_type == PositionType::Absolute {
+        std::mem::swap(&mut style.position.left, &mut style.position.right);
+    }
+    std::mem::swap(&mut style.margin.left, &mut style.margin.right);
+    std::mem::swap(&mut style.padding.left, &mut style.padding.right);
+
+    style.flex_direction = match style.flex_direction {
+        FlexDirection::Row => FlexDirection::RowReverse,
+        FlexDirection::RowReverse => FlexDirection::Row,
+        column => column,
+    };
+}
+
+fn mirror_alignment(alignment: &mut TextAlignment) {
+    alignment.horizontal = match alignment.horizontal {
+        HorizontalAlign::Left => HorizontalAlign::Right,
+        HorizontalAlign::Right => HorizontalAlign::Left,
+        HorizontalAlign::Center => HorizontalAlign::Center,
+    };
+}
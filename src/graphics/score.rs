@@ -1,102 +1,247 @@
 // SCORE CODE
 
-use bevy::asset::AssetServer;
-use bevy::core::FixedTimestep;
 use bevy::prelude::*;
 
-use crate::logic::player::PlayerDeathEvent;
+use crate::logic::blocks::{BlockDestroyedEvent, SpawnBlockEvent};
+use crate::logic::combo::Combo;
+use crate::logic::factions::{CollisionEvent, DETECT_COLLISIONS};
+use crate::logic::game_time::GameTime;
+use crate::logic::lives::Invulnerable;
+use crate::logic::mutators::ActiveMutators;
+use crate::logic::player::{Controls, Dashing, Dying, Player, PlayerDeathEvent};
+use crate::logic::reset_game::Resettable;
+use crate::logic::rules::{CoopScoring, GameRules};
+use crate::logic::upgrades::RunUpgrades;
+use crate::AppState;
 
-// For SCORE_ACC_TIMESTEP, it's once every two seconds
-const SCORE_ACC_TIMESTEP: f64 = 1.0;
+// Points for every block that spawns while the player is alive
+const BLOCK_SPAWN_BONUS: u32 = 5;
+// Bonus points lost whenever something hits a player, even a shield
+const COLLISION_PENALTY: u32 = 50;
+
+// Label for everything that gives out points, the combo multiplier is
+// updated before any of them
+pub const AWARD_POINTS: &str = "award_points";
 
 pub struct ScorePlugin;
 
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_startup_system(render_score.system())
+        app.init_resource::<Score>()
+            .reset_on_restart::<Score>()
+            .add_event::<ScoreEvent>()
             .add_system(stop_score_counter.system())
             .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(SCORE_ACC_TIMESTEP))
-                    .with_system(score_update_system.system()),
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(accumulate_score.system().label(AWARD_POINTS))
+                    .with_system(destroy_block_points.system().label(AWARD_POINTS))
+                    .with_system(penalize_collisions.system().after(DETECT_COLLISIONS))
+                    .with_system(apply_score_events.system()),
             );
     }
 }
 
+// The score is a point per second survived plus bonus points
 pub struct Score {
-    pub value: i32,
+    pub survival_seconds: f32,
+    pub bonus: u32,
     pub active: bool,
+    // Every player's own score for split co-op scoring
+    pub players: Vec<PlayerScore>,
+}
+
+// One player's share of the run, only counted while they are alive
+pub struct PlayerScore {
+    pub controls: Controls,
+    pub survival_seconds: f32,
+    pub bonus: u32,
+}
+
+impl PlayerScore {
+    pub fn points(&self) -> u32 {
+        self.survival_seconds as u32 + self.bonus
+    }
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Score {
+            survival_seconds: 0.0,
+            bonus: 0,
+            active: true,
+            players: Vec::new(),
+        }
+    }
+}
+
+impl Score {
+    // The points before any multipliers
+    pub fn points(&self) -> u32 {
+        self.survival_seconds as u32 + self.bonus
+    }
+
+    // The points with mutators and upgrades applied
+    pub fn total(&self, mutators: &ActiveMutators, upgrades: &RunUpgrades) -> u32 {
+        let multiplier = mutators.score_multiplier() * upgrades.score_multiplier();
+        (self.points() as f32 * multiplier).round() as u32
+    }
+
+    // A single player's points with mutators and upgrades applied
+    pub fn player_total(
+        &self,
+        player: &PlayerScore,
+        mutators: &ActiveMutators,
+        upgrades: &RunUpgrades,
+    ) -> u32 {
+        let multiplier = mutators.score_multiplier() * upgrades.score_multiplier();
+        (player.points() as f32 * multiplier).round() as u32
+    }
+
+    // Whether the score should be shown per player
+    pub fn is_split(&self, rules: &GameRules) -> bool {
+        rules.coop_scoring == CoopScoring::Split && self.players.len() > 1
+    }
+}
+
+// Sent whenever points are earned for something other than surviving,
+// everything that shows or counts points reads these
+pub struct ScoreEvent {
+    pub points: u32,
+    // Where the points were earned
+    pub position: Vec3,
+    // Who earned them, if it was a single player
+    pub player: Option<Controls>,
 }
 
 fn stop_score_counter(
-    mut reset_game_event: EventReader<PlayerDeathEvent>,
-    mut score_query: Query<&mut Score>,
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    mut score: ResMut<Score>,
 ) {
-    for _event in reset_game_event.iter() {
+    for _event in player_death_event.iter() {
         // Stop accumulating the score
-        let mut score = score_query
-            .single_mut()
-            .expect("There should only be one score in the game.");
-
         score.active = false;
     }
 }
 
-// NOTE
-// I have no clue what a lot of the styling/positions does here.
-// Will need to come back to this at some point.
-fn render_score(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let text_section = TextSection {
-        value: 0.to_string(),
-        style: TextStyle {
-            font: asset_server.load("fonts/Roboto-Thin.ttf"),
-            font_size: 60.0,
-            color: Color::rgb(0.9, 0.9, 0.9),
-        },
-    };
-
-    let text = Text {
-        sections: vec![text_section],
-        alignment: TextAlignment {
-            vertical: VerticalAlign::Center,
-            horizontal: HorizontalAlign::Center,
-        },
-    };
-
-    let style = Style {
-        align_self: AlignSelf::FlexEnd,
-        position_type: PositionType::Absolute,
-        position: Rect {
-            top: Val::Px(60.0),
-            right: Val::Px(80.0),
-
-            // default is spawning in the lower left hand corner
-            ..Default::default()
-        },
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(TextBundle {
-            style,
-            text,
-            ..Default::default()
-        })
-        .insert(Score {
-            value: 0,
-            active: true,
+fn accumulate_score(
+    mut score: ResMut<Score>,
+    combo: Res<Combo>,
+    mut spawn_event: EventReader<SpawnBlockEvent>,
+    player_query: Query<&Controls, (With<Player>, Without<Dying>)>,
+    time: Res<GameTime>,
+) {
+    let spawned_blocks = spawn_event.iter().count() as u32;
+
+    // accumulate the score if its active
+    if !score.active {
+        return;
+    }
+
+    let multiplier = combo.multiplier();
+    let survived = time.delta_seconds() * multiplier as f32;
+    let spawn_bonus = spawned_blocks * BLOCK_SPAWN_BONUS * multiplier;
+    score.survival_seconds += survived;
+    score.bonus += spawn_bonus;
+
+    // Each player only earns their own points while they are alive
+    for controls in player_query.iter() {
+        let index = match score
+            .players
+            .iter()
+            .position(|player| player.controls == *controls)
+        {
+            Some(index) => index,
+            None => {
+                score.players.push(PlayerScore {
+                    controls: *controls,
+                    survival_seconds: 0.0,
+                    bonus: 0,
+                });
+                score.players.len() - 1
+            }
+        };
+
+        let player = &mut score.players[index];
+        player.survival_seconds += survived;
+        player.bonus += spawn_bonus;
+    }
+}
+
+// Only blocks that are destroyed are worth points, blocks cleared
+// by a bomb or the end of a wave just go away
+fn destroy_block_points(
+    score: Res<Score>,
+    combo: Res<Combo>,
+    mut block_destroyed_event: EventReader<BlockDestroyedEvent>,
+    mut score_event: EventWriter<ScoreEvent>,
+) {
+    for event in block_destroyed_event.iter() {
+        if !score.active {
+            continue;
+        }
+
+        score_event.send(ScoreEvent {
+            points: event.kind.score_value() * combo.multiplier(),
+            position: event.position,
+            player: event.destroyed_by,
         });
+    }
 }
 
-fn score_update_system(mut score_query: Query<(&mut Score, &mut Text)>) {
-    let (mut score, mut text) = score_query
-        .single_mut()
-        .expect("There should only be one score in the game.");
+// Getting hit costs bonus points, the shared score and the player's own.
+// Players that can't be hit right now don't lose any.
+#[allow(clippy::type_complexity)]
+fn penalize_collisions(
+    mut score: ResMut<Score>,
+    mut collision_event: EventReader<CollisionEvent>,
+    player_query: Query<
+        &Controls,
+        (
+            With<Player>,
+            Without<Dying>,
+            Without<Invulnerable>,
+            Without<Dashing>,
+        ),
+    >,
+) {
+    // Once a frame per player, like the damage
+    let mut hit: Vec<Entity> = Vec::new();
 
-    // accumulate the score if its active
-    if score.active {
-        score.value += 1;
-        let string_score: String = score.value.to_string();
-        text.sections[0].value = string_score;
+    for event in collision_event.iter() {
+        if !score.active || hit.contains(&event.target) {
+            continue;
+        }
+
+        let controls = match player_query.get(event.target) {
+            Ok(controls) => controls,
+            Err(_) => continue,
+        };
+        hit.push(event.target);
+
+        score.bonus = score.bonus.saturating_sub(COLLISION_PENALTY);
+        if let Some(player) = score
+            .players
+            .iter_mut()
+            .find(|player| player.controls == *controls)
+        {
+            player.bonus = player.bonus.saturating_sub(COLLISION_PENALTY);
+        }
+    }
+}
+
+// Whoever earned the points gets them in split scoring
+fn apply_score_events(mut score: ResMut<Score>, mut score_event: EventReader<ScoreEvent>) {
+    for event in score_event.iter() {
+        score.bonus += event.points;
+
+        if let Some(earned_by) = event.player {
+            if let Some(player) = score
+                .players
+                .iter_mut()
+                .find(|player| player.controls == earned_by)
+            {
+                player.bonus += event.points;
+            }
+        }
     }
 }
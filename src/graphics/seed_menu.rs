@@ -0,0 +1,165 @@
+// SEED MENU CODE
+
+use bevy::prelude::*;
+use bevy::window::ReceivedCharacter;
+
+use crate::graphics::toast::ToastEvent;
+use crate::logic::clipboard;
+use crate::logic::game_rng::GameRng;
+use crate::logic::seed_codes;
+use crate::AppState;
+
+pub struct SeedMenuPlugin;
+
+impl Plugin for SeedMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<SeedEntry>()
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu).with_system(open_seed_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::SeedEntry).with_system(spawn_seed_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::SeedEntry)
+                    .with_system(type_seed_code.system())
+                    .with_system(update_seed_menu_text.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::SeedEntry).with_system(clear_seed_menu.system()),
+            );
+    }
+}
+
+// What has been typed so far
+#[derive(Default)]
+struct SeedEntry(String);
+
+struct SeedMenuText;
+
+fn open_seed_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut seed_entry: ResMut<SeedEntry>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    // Not a letter, or it would end up typed into the code
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        seed_entry.0.clear();
+        let _ = app_state.set(AppState::SeedEntry);
+    }
+}
+
+fn spawn_seed_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 40.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(SeedMenuText);
+        })
+        .insert(SeedMenuText);
+}
+
+// Type or paste the code a friend shared, Enter plays it and Escape goes
+// back. A code that doesn't check out says why instead of playing.
+fn type_seed_code(
+    mut received_characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut seed_entry: ResMut<SeedEntry>,
+    mut game_rng: ResMut<GameRng>,
+    mut toast_event: EventWriter<ToastEvent>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    // Command on a Mac
+    let pasting = [
+        KeyCode::LControl,
+        KeyCode::RControl,
+        KeyCode::LWin,
+        KeyCode::RWin,
+    ]
+    .iter()
+    .any(|key| keyboard_input.pressed(*key));
+
+    let mut typed: Vec<char> = received_characters.iter().map(|event| event.char).collect();
+    if pasting {
+        // The V itself is part of the shortcut, not the code
+        typed.clear();
+        if keyboard_input.just_pressed(KeyCode::V) {
+            match clipboard::paste() {
+                Some(text) => typed.extend(text.chars()),
+                None => toast_event.send(ToastEvent("Couldn't reach the clipboard".to_string())),
+            }
+        }
+    }
+
+    for character in typed {
+        if character.is_ascii_alphanumeric() || character == '-' {
+            seed_entry.0.push(character.to_ascii_uppercase());
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        seed_entry.0.pop();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        let _ = app_state.set(AppState::MainMenu);
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        match seed_codes::decode(&seed_entry.0) {
+            Ok(seed) => {
+                game_rng.set_fixed_seed(Some(seed));
+                let _ = app_state.set(AppState::InGame);
+            }
+            Err(error) => toast_event.send(ToastEvent(error.to_string())),
+        }
+    }
+}
+
+fn update_seed_menu_text(
+    seed_entry: Res<SeedEntry>,
+    mut text_query: Query<&mut Text, With<SeedMenuText>>,
+) {
+    let lines = [
+        "Race a friend's run".to_string(),
+        "Type or paste their seed code, Enter to play, Escape to go back".to_string(),
+        String::new(),
+        format!("{}_", seed_entry.0),
+    ];
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_seed_menu(mut commands: Commands, text_query: Query<Entity, With<SeedMenuText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,156 @@
+// SETTINGS MENU CODE
+
+use bevy::prelude::*;
+
+use super::flash::EffectSettings;
+use crate::logic::locale::Locales;
+use crate::settings::GameSettings;
+use crate::AppState;
+
+const ROW_COUNT: usize = 5;
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<SettingsCursor>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Settings).with_system(spawn_settings_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Settings)
+                    .with_system(navigate_settings.system())
+                    .with_system(update_settings_menu_text.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Settings).with_system(clear_settings_menu.system()),
+            );
+    }
+}
+
+#[derive(Default)]
+struct SettingsCursor(usize);
+
+struct SettingsMenu;
+
+struct SettingsMenuText;
+
+fn spawn_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut cursor: ResMut<SettingsCursor>,
+) {
+    *cursor = SettingsCursor::default();
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 32.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(SettingsMenuText);
+        })
+        .insert(SettingsMenu);
+}
+
+// Up and down pick a setting, left and right change it and it is
+// saved straight away. Effects only last until the game closes.
+// Escape goes back to the main menu.
+fn navigate_settings(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut cursor: ResMut<SettingsCursor>,
+    mut settings: ResMut<GameSettings>,
+    mut effect_settings: ResMut<EffectSettings>,
+    locales: Res<Locales>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        cursor.0 = (cursor.0 + 1) % ROW_COUNT;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        cursor.0 = (cursor.0 + ROW_COUNT - 1) % ROW_COUNT;
+    }
+
+    let step = if keyboard_input.just_pressed(KeyCode::Left) {
+        -1
+    } else if keyboard_input.just_pressed(KeyCode::Right) {
+        1
+    } else {
+        0
+    };
+    if step != 0 {
+        match cursor.0 {
+            0 => settings.vsync = !settings.vsync,
+            1 => settings.block_bounce = !settings.block_bounce,
+            2 => effect_settings.shadows = !effect_settings.shadows,
+            3 => settings.pixel_art = !settings.pixel_art,
+            _ => settings.language = locales.next(&settings, step).code.clone(),
+        }
+        if cursor.0 != 2 {
+            settings.save();
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        let _ = app_state.set(AppState::MainMenu);
+    }
+}
+
+fn update_settings_menu_text(
+    cursor: Res<SettingsCursor>,
+    settings: Res<GameSettings>,
+    effect_settings: Res<EffectSettings>,
+    locales: Res<Locales>,
+    mut text_query: Query<&mut Text, With<SettingsMenuText>>,
+) {
+    let on_off = |on: bool| if on { "On" } else { "Off" };
+    // The window is only made once, so vsync waits for the next launch
+    let rows = [
+        format!("Vsync: {} (after a restart)", on_off(settings.vsync)),
+        format!("Block bounce: {}", on_off(settings.block_bounce)),
+        format!("Shadows: {}", on_off(effect_settings.shadows)),
+        format!("Pixel art: {}", on_off(settings.pixel_art)),
+        format!("Language: {}", locales.current(&settings).name),
+    ];
+
+    let mut lines = vec![
+        "Settings".to_string(),
+        "Left/Right to change, Escape to go back".to_string(),
+        String::new(),
+    ];
+    lines.extend(rows.iter().enumerate().map(|(index, row)| {
+        let cursor_mark = if index == cursor.0 { "> " } else { "  " };
+        format!("{}{}", cursor_mark, row)
+    }));
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_settings_menu(mut commands: Commands, menu_query: Query<Entity, With<SettingsMenu>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,106 @@
+// SHADOWS CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+
+use super::flash::EffectSettings;
+use crate::logic::blocks::Block;
+use crate::logic::player::Player;
+
+// Down and to the right by this much, as if lit from the top left
+const SHADOW_OFFSET: f32 = 6.0;
+// Just under the thing casting it, still above the background
+const SHADOW_DEPTH: f32 = -0.5;
+const SHADOW_ALPHA: f32 = 0.35;
+
+pub struct ShadowPlugin;
+
+impl Plugin for ShadowPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(load_shadow_material.system())
+            .add_system(add_shadows.system())
+            .add_system(toggle_shadows.system())
+            .add_system(keep_shadows_down_right.system())
+            .add_system(clear_orphaned_shadows.system());
+    }
+}
+
+struct Shadow;
+
+// Every shadow shares the one material
+struct ShadowMaterial(Handle<ColorMaterial>);
+
+fn load_shadow_material(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    let material = materials.add(Color::rgba(0.0, 0.0, 0.0, SHADOW_ALPHA).into());
+    commands.insert_resource(ShadowMaterial(material));
+}
+
+// A dark copy of the sprite as a child, so it follows and scales with it
+fn add_shadows(
+    mut commands: Commands,
+    shadow_material: Res<ShadowMaterial>,
+    effect_settings: Res<EffectSettings>,
+    caster_query: Query<(Entity, &Sprite), Or<(Added<Player>, Added<Block>)>>,
+) {
+    for (entity, sprite) in caster_query.iter() {
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        size: sprite.size,
+                        resize_mode: SpriteResizeMode::Manual,
+                        ..Default::default()
+                    },
+                    material: shadow_material.0.clone(),
+                    transform: Transform::from_xyz(SHADOW_OFFSET, -SHADOW_OFFSET, SHADOW_DEPTH),
+                    visible: Visible {
+                        is_visible: effect_settings.shadows,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .insert(Shadow);
+        });
+    }
+}
+
+fn toggle_shadows(
+    effect_settings: Res<EffectSettings>,
+    mut shadow_query: Query<&mut Visible, With<Shadow>>,
+) {
+    if !effect_settings.is_changed() {
+        return;
+    }
+
+    for mut visible in shadow_query.iter_mut() {
+        visible.is_visible = effect_settings.shadows;
+    }
+}
+
+// Shadows turn with a tumbling block, but stay on the same side of it
+fn keep_shadows_down_right(
+    mut shadow_query: Query<(&Parent, &mut Transform), With<Shadow>>,
+    caster_query: Query<&Transform, Without<Shadow>>,
+) {
+    for (parent, mut transform) in shadow_query.iter_mut() {
+        if let Ok(caster_transform) = caster_query.get(parent.0) {
+            let offset =
+                caster_transform.rotation.inverse() * Vec3::new(SHADOW_OFFSET, -SHADOW_OFFSET, 0.0);
+            transform.translation = Vec3::new(offset.x, offset.y, SHADOW_DEPTH);
+        }
+    }
+}
+
+// Players and blocks are despawned on their own rather than recursively,
+// so their shadows are left behind to clean up here
+fn clear_orphaned_shadows(
+    mut commands: Commands,
+    shadow_query: Query<(Entity, &Parent), With<Shadow>>,
+    caster_query: Query<Entity, Or<(With<Player>, With<Block>)>>,
+) {
+    for (entity, parent) in shadow_query.iter() {
+        if caster_query.get(parent.0).is_err() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// SPRITES CODE
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::logic::block_kinds::BlockKind;
+use crate::logic::blocks::Block;
+use crate::logic::game_time::GameTime;
+use crate::logic::player::Player;
+use crate::logic::powerups::SlowMotion;
+use crate::logic::time_scale::TimeScale;
+use crate::Velocity;
+
+// What the game looked like before it had sprites, used for anything
+// whose sprite is missing from assets/sprites
+const PLAYER_FALLBACK: &str = "textures/block_3.png";
+const BLOCK_FALLBACK: &str = "textures/block_1.png";
+// How fast blocks spin compared to really rolling along the ground
+const TUMBLE_RATE: f32 = 0.15;
+
+pub struct SpritePlugin;
+
+impl Plugin for SpritePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(fall_back_on_missing_sprites.system())
+            .add_system(tumble_blocks.system());
+    }
+}
+
+pub fn player_texture(asset_server: &AssetServer) -> Handle<Texture> {
+    asset_server.load("sprites/player.png")
+}
+
+pub fn block_texture(asset_server: &AssetServer, kind: BlockKind) -> Handle<Texture> {
+    let path = match kind {
+        BlockKind::Normal => "sprites/block_normal.png",
+        BlockKind::Small => "sprites/block_small.png",
+        BlockKind::Large => "sprites/block_large.png",
+        BlockKind::Zigzag => "sprites/block_zigzag.png",
+        BlockKind::Homing => "sprites/block_homing.png",
+        BlockKind::Armored => "sprites/block_armored.png",
+        BlockKind::Mirror => "sprites/block_mirror.png",
+    };
+    asset_server.load(path)
+}
+
+// Missing files only show up once they fail to load, so the sprite is
+// swapped back to the old texture then. It may show blank for a frame.
+fn fall_back_on_missing_sprites(
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    sprite_query: Query<(&Handle<ColorMaterial>, Option<&Player>), Or<(With<Player>, With<Block>)>>,
+) {
+    for (material, player) in sprite_query.iter() {
+        let material = match materials.get_mut(material) {
+            Some(material) => material,
+            None => continue,
+        };
+
+        let failed = material.texture.as_ref().map_or(false, |texture| {
+            asset_server.get_load_state(texture) == LoadState::Failed
+        });
+        if !failed {
+            continue;
+        }
+
+        let fallback = if player.is_some() {
+            PLAYER_FALLBACK
+        } else {
+            BLOCK_FALLBACK
+        };
+        material.texture = Some(asset_server.load(fallback));
+    }
+}
+
+// Blocks roll the way they are going, faster the faster they go.
+// Only the sprite turns, they still collide as squares.
+fn tumble_blocks(
+    mut block_query: Query<(&Velocity, &Sprite, &mut Transform), With<Block>>,
+    time: Res<GameTime>,
+    time_scale: Res<TimeScale>,
+    slow_motion: Res<SlowMotion>,
+) {
+    let scale = time.delta_seconds() * time_scale.0 * slow_motion.multiplier();
+
+    for (velocity, sprite, mut transform) in block_query.iter_mut() {
+        let radius = sprite.size.x / 2.0;
+        if radius <= 0.0 {
+            continue;
+        }
+
+        // Moving right rolls clockwise, which is a negative angle
+        let direction = if velocity.0.x != 0.0 {
+            -velocity.0.x.signum()
+        } else {
+            velocity.0.y.signum()
+        };
+        let spin = direction * velocity.0.length() / radius * TUMBLE_RATE;
+        transform.rotate(Quat::from_rotation_z(spin * scale));
+    }
+}
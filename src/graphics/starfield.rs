@@ -0,0 +1,126 @@
+// STARFIELD CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+use rand::{thread_rng, Rng};
+
+use super::flash::EffectSettings;
+use crate::logic::game_time::GameTime;
+use crate::logic::player::Player;
+use crate::{Velocity, WINDOWHEIGHT, WINDOWWIDTH};
+
+// Far to near. The nearer a layer is the bigger and brighter its stars,
+// and the more of the player's movement it scrolls with.
+const STAR_LAYERS: [StarLayer; 3] = [
+    StarLayer {
+        count: 70,
+        size: 2.0,
+        alpha: 0.35,
+        parallax: 0.04,
+    },
+    StarLayer {
+        count: 40,
+        size: 3.0,
+        alpha: 0.55,
+        parallax: 0.1,
+    },
+    StarLayer {
+        count: 15,
+        size: 4.0,
+        alpha: 0.8,
+        parallax: 0.2,
+    },
+];
+// In front of the background image, behind the shadows and everything else
+const STAR_DEPTH: f32 = 0.1;
+
+pub struct StarfieldPlugin;
+
+impl Plugin for StarfieldPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(spawn_starfield.system())
+            .add_system(scroll_starfield.system());
+    }
+}
+
+struct StarLayer {
+    count: usize,
+    size: f32,
+    alpha: f32,
+    // How much of the player's velocity the layer moves against
+    parallax: f32,
+}
+
+struct Star {
+    parallax: f32,
+}
+
+// Stars are only for looks, so they don't use the game's seeded rng
+fn spawn_starfield(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    let mut rng = thread_rng();
+
+    for (index, layer) in STAR_LAYERS.iter().enumerate() {
+        let material = materials.add(Color::rgba(1.0, 1.0, 1.0, layer.alpha).into());
+
+        for _ in 0..layer.count {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        size: Vec2::new(layer.size, layer.size),
+                        resize_mode: SpriteResizeMode::Manual,
+                        ..Default::default()
+                    },
+                    material: material.clone(),
+                    transform: Transform::from_xyz(
+                        rng.gen_range(-WINDOWWIDTH / 2.0..WINDOWWIDTH / 2.0),
+                        rng.gen_range(-WINDOWHEIGHT / 2.0..WINDOWHEIGHT / 2.0),
+                        // Nearer layers draw over farther ones
+                        STAR_DEPTH + index as f32 * 0.01,
+                    ),
+                    visible: Visible {
+                        is_visible: true,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .insert(Star {
+                    parallax: layer.parallax,
+                });
+        }
+    }
+}
+
+// The stars drift the opposite way to the players, as if the camera followed
+// them, and come back around on the other side of the screen
+fn scroll_starfield(
+    effect_settings: Res<EffectSettings>,
+    player_query: Query<&Velocity, With<Player>>,
+    mut star_query: Query<(&Star, &mut Transform)>,
+    time: Res<GameTime>,
+) {
+    if effect_settings.reduce_motion {
+        return;
+    }
+
+    let player_count = player_query.iter().count();
+    if player_count == 0 {
+        return;
+    }
+
+    // With two players the field follows them both a bit
+    let velocity = player_query
+        .iter()
+        .fold(Vec2::ZERO, |sum, velocity| sum + velocity.0)
+        / player_count as f32;
+    let delta_time = time.delta_seconds();
+
+    for (star, mut transform) in star_query.iter_mut() {
+        let moved = -velocity * star.parallax * delta_time;
+        transform.translation.x = (transform.translation.x + moved.x + WINDOWWIDTH / 2.0)
+            .rem_euclid(WINDOWWIDTH)
+            - WINDOWWIDTH / 2.0;
+        transform.translation.y = (transform.translation.y + moved.y + WINDOWHEIGHT / 2.0)
+            .rem_euclid(WINDOWHEIGHT)
+            - WINDOWHEIGHT / 2.0;
+    }
+}
@@ -0,0 +1,317 @@
+// STICK MENU CODE
+
+use bevy::prelude::*;
+
+use crate::logic::gamepad::{
+    apply_stick_settings, raw_left_stick, ConnectedGamepads, StickSettings,
+};
+use crate::logic::input_map::{Action, InputMap};
+use crate::logic::player::{ControlScheme, MovementAbility};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+const ROW_COUNT: usize = 3;
+// Size of the square that shows the stick, in pixels
+const STICK_AREA: f32 = 200.0;
+const DOT_SIZE: f32 = 12.0;
+
+pub struct StickMenuPlugin;
+
+impl Plugin for StickMenuPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<StickMenu>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(spawn_stick_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu)
+                    .with_system(navigate_stick_menu.system())
+                    .with_system(update_stick_menu_text.system())
+                    .with_system(update_stick_preview.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::MainMenu).with_system(clear_stick_menu.system()),
+            );
+    }
+}
+
+#[derive(Default)]
+pub struct StickMenu {
+    pub open: bool,
+    selected: usize,
+}
+
+struct StickMenuNode;
+
+struct StickMenuText;
+
+struct StickPreview;
+
+struct InnerDeadzoneMarker;
+
+// Where the stick actually is
+struct RawStickDot;
+
+// Where the stick is after the settings, what the player moves with
+struct OutputStickDot;
+
+fn spawn_stick_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut stick_menu: ResMut<StickMenu>,
+) {
+    stick_menu.open = false;
+
+    let dot = |color: Color, materials: &mut Assets<ColorMaterial>| NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: Size::new(Val::Px(DOT_SIZE), Val::Px(DOT_SIZE)),
+            ..Default::default()
+        },
+        material: materials.add(color.into()),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(80.0),
+                    left: Val::Px(80.0),
+                    ..Default::default()
+                },
+                flex_direction: FlexDirection::ColumnReverse,
+                padding: Rect::all(Val::Px(20.0)),
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 30.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(StickMenuText);
+
+            // The full range of the stick, with the deadzone and both dots inside
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(STICK_AREA), Val::Px(STICK_AREA)),
+                        margin: Rect {
+                            top: Val::Px(20.0),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    material: materials.add(Color::rgba(0.2, 0.2, 0.2, 0.8).into()),
+                    visible: Visible {
+                        is_visible: false,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .with_children(|preview| {
+                    preview
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                ..Default::default()
+                            },
+                            material: materials.add(Color::rgba(0.6, 0.1, 0.1, 0.6).into()),
+                            ..Default::default()
+                        })
+                        .insert(InnerDeadzoneMarker);
+                    preview
+                        .spawn_bundle(dot(Color::rgb(1.0, 0.3, 0.3), &mut materials))
+                        .insert(RawStickDot);
+                    preview
+                        .spawn_bundle(dot(Color::rgb(0.3, 1.0, 0.4), &mut materials))
+                        .insert(OutputStickDot);
+                })
+                .insert(StickPreview);
+        })
+        .insert(StickMenuNode);
+}
+
+// G opens the menu, up and down pick a row, left and right change it.
+// M switches between keyboard and mouse controls at any time,
+// T goes through the fire modes and V swaps teleporting for dashing.
+fn navigate_stick_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut stick_menu: ResMut<StickMenu>,
+    mut settings: ResMut<StickSettings>,
+    mut control_scheme: ResMut<ControlScheme>,
+    mut input_map: ResMut<InputMap>,
+    mut game_settings: ResMut<GameSettings>,
+    mut movement_ability: ResMut<MovementAbility>,
+) {
+    if keyboard_input.just_pressed(KeyCode::T) {
+        let mode = input_map.mode(Action::Fire).next();
+        input_map.set_mode(Action::Fire, mode, &mut game_settings);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::V) {
+        *movement_ability = match *movement_ability {
+            MovementAbility::Teleport => MovementAbility::Dash,
+            MovementAbility::Dash => MovementAbility::Teleport,
+        };
+    }
+
+    if keyboard_input.just_pressed(KeyCode::M) {
+        *control_scheme = match *control_scheme {
+            ControlScheme::Keyboard => ControlScheme::Mouse,
+            ControlScheme::Mouse => ControlScheme::Keyboard,
+        };
+    }
+
+    if keyboard_input.just_pressed(KeyCode::G) {
+        stick_menu.open = !stick_menu.open;
+    }
+
+    if !stick_menu.open {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        stick_menu.selected = (stick_menu.selected + 1) % ROW_COUNT;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        stick_menu.selected = (stick_menu.selected + ROW_COUNT - 1) % ROW_COUNT;
+    }
+
+    let step = keyboard_input.just_pressed(KeyCode::Right) as i32
+        - keyboard_input.just_pressed(KeyCode::Left) as i32;
+    if step == 0 {
+        return;
+    }
+
+    // The inner deadzone always stays inside the outer one
+    let step_f32 = step as f32 * 0.05;
+    match stick_menu.selected {
+        0 => {
+            settings.inner_deadzone =
+                (settings.inner_deadzone + step_f32).clamp(0.0, settings.outer_deadzone - 0.1)
+        }
+        1 => {
+            settings.outer_deadzone =
+                (settings.outer_deadzone + step_f32).clamp(settings.inner_deadzone + 0.1, 1.0)
+        }
+        _ => settings.curve = settings.curve.next(),
+    }
+}
+
+fn update_stick_menu_text(
+    stick_menu: Res<StickMenu>,
+    settings: Res<StickSettings>,
+    control_scheme: Res<ControlScheme>,
+    input_map: Res<InputMap>,
+    movement_ability: Res<MovementAbility>,
+    mut text_query: Query<&mut Text, With<StickMenuText>>,
+) {
+    if let Ok(mut text) = text_query.single_mut() {
+        let option_rows = format!(
+            "Mouse controls (M): {}\nFire mode (T): {}\nTeleport key (V): {}",
+            if *control_scheme == ControlScheme::Mouse {
+                "On"
+            } else {
+                "Off"
+            },
+            input_map.mode(Action::Fire),
+            *movement_ability
+        );
+
+        if !stick_menu.open {
+            text.sections[0].value = format!("Controller settings (G)\n{}", option_rows);
+            return;
+        }
+
+        let rows = [
+            format!("Inner deadzone: {:.2}", settings.inner_deadzone),
+            format!("Outer deadzone: {:.2}", settings.outer_deadzone),
+            format!("Response curve: {}", settings.curve),
+        ];
+
+        text.sections[0].value = rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let cursor = if index == stick_menu.selected {
+                    "> "
+                } else {
+                    "  "
+                };
+                format!("{}{}", cursor, row)
+            })
+            .chain(option_rows.lines().map(|row| format!("  {}", row)))
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+}
+
+// Moves the dots around live so the settings can be tuned by feel
+#[allow(clippy::type_complexity)]
+fn update_stick_preview(
+    stick_menu: Res<StickMenu>,
+    settings: Res<StickSettings>,
+    gamepads: Res<ConnectedGamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut preview_query: Query<&mut Visible, With<StickPreview>>,
+    mut node_query: QuerySet<(
+        Query<(&mut Style, &mut Visible), With<InnerDeadzoneMarker>>,
+        Query<(&mut Style, &mut Visible), With<RawStickDot>>,
+        Query<(&mut Style, &mut Visible), With<OutputStickDot>>,
+    )>,
+) {
+    for mut visible in preview_query.iter_mut() {
+        visible.is_visible = stick_menu.open;
+    }
+
+    let raw = gamepads
+        .active()
+        .map_or(Vec2::ZERO, |gamepad| raw_left_stick(&gamepad_axes, gamepad));
+    let output = apply_stick_settings(raw, &settings);
+
+    // -1 to 1 on the stick is the full width of the area
+    let to_area = |stick: Vec2, size: f32| Rect {
+        left: Val::Px((stick.x + 1.0) / 2.0 * STICK_AREA - size / 2.0),
+        bottom: Val::Px((stick.y + 1.0) / 2.0 * STICK_AREA - size / 2.0),
+        ..Default::default()
+    };
+
+    for (mut style, mut visible) in node_query.q0_mut().iter_mut() {
+        let size = settings.inner_deadzone * STICK_AREA;
+        style.size = Size::new(Val::Px(size), Val::Px(size));
+        style.position = to_area(Vec2::ZERO, size);
+        visible.is_visible = stick_menu.open;
+    }
+
+    for (mut style, mut visible) in node_query.q1_mut().iter_mut() {
+        style.position = to_area(raw, DOT_SIZE);
+        visible.is_visible = stick_menu.open;
+    }
+
+    for (mut style, mut visible) in node_query.q2_mut().iter_mut() {
+        style.position = to_area(output, DOT_SIZE);
+        visible.is_visible = stick_menu.open;
+    }
+}
+
+fn clear_stick_menu(mut commands: Commands, menu_query: Query<Entity, With<StickMenuNode>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -2,42 +2,284 @@
 
 use bevy::prelude::*;
 
-use crate::logic::{player::PlayerDeathEvent, reset_game::ResetGameEvent};
+use crate::graphics::score::Score;
+use crate::graphics::toast::ToastEvent;
+use crate::logic::challenges::{ChallengeOutcome, ChallengeProgress};
+use crate::logic::clipboard;
+use crate::logic::daily::{date_string, DailyChallenge};
+use crate::logic::game_rng::GameRng;
+use crate::logic::high_scores::PendingHighScore;
+use crate::logic::hints::choose_hint;
+use crate::logic::input_map::take_just_pressed;
+use crate::logic::mutators::ActiveMutators;
+use crate::logic::player::ControlScheme;
+use crate::logic::profile::NewBest;
+use crate::logic::rules::{GameRules, PlayerSetup};
+use crate::logic::seed_codes;
+use crate::logic::stats::{DeathHistory, RunStats};
+use crate::logic::upgrades::RunUpgrades;
+use crate::logic::versus::VersusMatch;
+use crate::logic::weekly::{WeeklyChallenge, WeeklySchedule};
+use crate::settings::GameSettings;
+use crate::AppState;
 
 pub struct TextPlugin;
 
 impl Plugin for TextPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_system(game_over_text.system())
-            .add_system(clear_game_over_text.system());
+        app.add_system_set(
+            SystemSet::on_update(AppState::MainMenu)
+                .with_system(start_daily_on_d.system())
+                .with_system(start_weekly_on_w.system())
+                .with_system(start_versus_on_x.system()),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::GameOver).with_system(game_over_text.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::GameOver)
+                .with_system(restart_on_r.system())
+                .with_system(copy_seed_code_on_c.system()),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::GameOver).with_system(clear_game_over_text.system()),
+        );
     }
 }
 
 struct GameOverText;
 
+// Everyone gets the same seed and rules for the day
+#[allow(clippy::too_many_arguments)]
+fn start_daily_on_d(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut daily: ResMut<DailyChallenge>,
+    mut game_rng: ResMut<GameRng>,
+    mut rules: ResMut<GameRules>,
+    mut mutators: ResMut<ActiveMutators>,
+    mut setup: ResMut<PlayerSetup>,
+    settings: Res<GameSettings>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::D) {
+        daily.start(
+            &mut game_rng,
+            &mut rules,
+            &mut mutators,
+            &mut setup,
+            &settings,
+        );
+        let _ = app_state.set(AppState::InGame);
+    }
+}
+
+// The same seed and mutators for everyone all week
+#[allow(clippy::too_many_arguments)]
+fn start_weekly_on_w(
+    keyboard_input: Res<Input<KeyCode>>,
+    schedule: Res<WeeklySchedule>,
+    mut weekly: ResMut<WeeklyChallenge>,
+    mut game_rng: ResMut<GameRng>,
+    mut rules: ResMut<GameRules>,
+    mut mutators: ResMut<ActiveMutators>,
+    mut setup: ResMut<PlayerSetup>,
+    settings: Res<GameSettings>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::W)
+        && weekly.start(
+            &schedule,
+            &mut game_rng,
+            &mut rules,
+            &mut mutators,
+            &mut setup,
+            &settings,
+        )
+    {
+        let _ = app_state.set(AppState::InGame);
+    }
+}
+
+// The dodger is on the keyboard, the mouse launches the blocks
+fn start_versus_on_x(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut versus: ResMut<VersusMatch>,
+    mut control_scheme: ResMut<ControlScheme>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::X) {
+        versus.start(&mut control_scheme);
+        let _ = app_state.set(AppState::InGame);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn game_over_text(
     mut commands: Commands,
-    mut player_death_event: EventReader<PlayerDeathEvent>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     asset_server: Res<AssetServer>,
+    run_stats: Res<RunStats>,
+    death_history: Res<DeathHistory>,
+    challenge_progress: Res<ChallengeProgress>,
+    score: Res<Score>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+    rules: Res<GameRules>,
+    new_best: Res<NewBest>,
+    daily: Res<DailyChallenge>,
+    weekly: Res<WeeklyChallenge>,
+    versus: Res<VersusMatch>,
+    game_rng: Res<GameRng>,
 ) {
-    for _event in player_death_event.iter() {
-        commands
-            .spawn_bundle(NodeBundle {
-                style: Style {
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::Center,
-                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                    ..Default::default()
-                },
-                material: materials.add(Color::NONE.into()),
-                ..Default::default()
+    let hint = choose_hint(&run_stats, &death_history);
+
+    // With split co-op scoring the players are ranked against each other
+    let comparison = if score.is_split(&rules) {
+        let mut totals: Vec<(String, u32)> = score
+            .players
+            .iter()
+            .map(|player| {
+                (
+                    player.controls.to_string(),
+                    score.player_total(player, &mutators, &upgrades),
+                )
             })
-            .with_children(|parent| {
+            .collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut lines = vec![format!("{} wins!", totals[0].0)];
+        lines.extend(
+            totals
+                .iter()
+                .map(|(name, total)| format!("{}: {}", name, total)),
+        );
+        Some(lines.join("\n"))
+    } else {
+        None
+    };
+    let challenge_result = challenge_progress
+        .challenge()
+        .map(|challenge| match challenge_progress.outcome {
+            Some(ChallengeOutcome::Completed) => {
+                format!("Challenge complete: {}", challenge.description)
+            }
+            _ => format!("Challenge failed: {}", challenge.description),
+        });
+
+    let seeded_challenge = daily
+        .day
+        .map(|day| format!("Daily challenge {}", date_string(day)))
+        .or_else(|| {
+            weekly.entry.as_ref().map(|entry| {
+                let mutators: Vec<String> = entry
+                    .mutators
+                    .iter()
+                    .map(|mutator| mutator.to_string())
+                    .collect();
+                format!("Weekly challenge: {}", mutators.join(", "))
+            })
+        });
+    // Each player's time, and who won once both have dodged
+    let versus_result = if versus.active {
+        let mut lines: Vec<String> = versus
+            .times
+            .iter()
+            .enumerate()
+            .filter_map(|(index, time)| {
+                time.map(|time| format!("Player {} survived {:.1}s", index + 1, time))
+            })
+            .collect();
+        lines.push(if !versus.is_over() {
+            "Swap! Player 2 dodges, player 1 launches blocks with the mouse".to_string()
+        } else {
+            match versus.winner() {
+                Some(winner) => format!("Player {} wins!", winner + 1),
+                None => "It's a tie!".to_string(),
+            }
+        });
+        Some(lines.join("\n"))
+    } else {
+        None
+    };
+
+    // The daily's and weekly's rules are fixed
+    let restart_prompt = if versus.active && !versus.is_over() {
+        "Press R to start the next round, Q for the menu, or ESC to quit."
+    } else if versus.active {
+        "Press R for a rematch, Q for the menu, or ESC to quit."
+    } else if daily.is_active() || weekly.is_active() {
+        "Press R to play the challenge again, Q for the menu, or ESC to quit."
+    } else {
+        "Press R to play again, F1 to change the difficulty, Q for the menu, or ESC to quit."
+    };
+
+    // Someone else can type or paste this in to get the same blocks
+    let seed_code = format!(
+        "Seed code: {} (C to copy)",
+        seed_codes::encode(game_rng.seed())
+    );
+
+    let mut new_bests = Vec::new();
+    if new_best.score {
+        new_bests.push(format!("{} points", score.total(&mutators, &upgrades)));
+    }
+    if new_best.survival_seconds {
+        new_bests.push(format!("{:.1}s survived", run_stats.survival_seconds));
+    }
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                // Column reverse so the children go top to bottom
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            if !new_bests.is_empty() {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            format!("NEW BEST! {}", new_bests.join(", ")),
+                            TextStyle {
+                                font: asset_server.load("fonts/Roboto-thin.ttf"),
+                                font_size: 56.0,
+                                color: Color::rgb(1.0, 0.85, 0.3),
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(GameOverText);
+            }
+
+            if let Some(versus_result) = versus_result {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            versus_result,
+                            TextStyle {
+                                font: asset_server.load("fonts/Roboto-thin.ttf"),
+                                font_size: 48.0,
+                                color: Color::rgb(0.9, 0.9, 0.9),
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(GameOverText);
+            }
+
+            if let Some(comparison) = comparison {
                 parent
                     .spawn_bundle(TextBundle {
                         text: Text::with_section(
-                            "Press R to reset the game. Press ESC to quit.",
+                            comparison,
                             TextStyle {
                                 font: asset_server.load("fonts/Roboto-thin.ttf"),
                                 font_size: 40.0,
@@ -48,19 +290,129 @@ fn game_over_text(
                         ..Default::default()
                     })
                     .insert(GameOverText);
-            })
-            .insert(GameOverText);
+            }
+
+            if let Some(seeded_challenge) = seeded_challenge {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            seeded_challenge,
+                            TextStyle {
+                                font: asset_server.load("fonts/Roboto-thin.ttf"),
+                                font_size: 48.0,
+                                color: Color::rgb(0.9, 0.9, 0.9),
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(GameOverText);
+            }
+
+            if let Some(challenge_result) = challenge_result {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            challenge_result,
+                            TextStyle {
+                                font: asset_server.load("fonts/Roboto-thin.ttf"),
+                                font_size: 48.0,
+                                color: Color::rgb(0.9, 0.9, 0.9),
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(GameOverText);
+            }
+
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        restart_prompt,
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-thin.ttf"),
+                            font_size: 40.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(GameOverText);
+
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        seed_code,
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-thin.ttf"),
+                            font_size: 32.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(GameOverText);
+
+            if let Some(hint) = hint {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            hint,
+                            TextStyle {
+                                font: asset_server.load("fonts/Roboto-thin.ttf"),
+                                font_size: 28.0,
+                                color: Color::rgb(0.9, 0.8, 0.4),
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(GameOverText);
+            }
+        })
+        .insert(GameOverText);
+}
+
+// R is a letter for the initials while a high score is being entered.
+// Starting the run already resets it, the restart key in the game
+// mustn't see the same R.
+fn restart_on_r(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    pending: Res<PendingHighScore>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if !pending.is_pending() && take_just_pressed(&mut keyboard_input, KeyCode::R) {
+        let _ = app_state.set(AppState::InGame);
+    }
+}
+
+// C is a letter for the initials too
+fn copy_seed_code_on_c(
+    keyboard_input: Res<Input<KeyCode>>,
+    pending: Res<PendingHighScore>,
+    game_rng: Res<GameRng>,
+    mut toast_event: EventWriter<ToastEvent>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::C) || pending.is_pending() {
+        return;
     }
+
+    let message = if clipboard::copy(&seed_codes::encode(game_rng.seed())) {
+        "Seed code copied"
+    } else {
+        "Couldn't reach the clipboard"
+    };
+    toast_event.send(ToastEvent(message.to_string()));
 }
 
 fn clear_game_over_text(
     mut commands: Commands,
-    mut reset_game_event: EventReader<ResetGameEvent>,
     game_over_text_query: Query<Entity, With<GameOverText>>,
 ) {
-    for _event in reset_game_event.iter() {
-        for entity in game_over_text_query.iter() {
-            commands.entity(entity).despawn();
-        }
+    for entity in game_over_text_query.iter() {
+        commands.entity(entity).despawn();
     }
 }
@@ -0,0 +1,64 @@
+// TOAST CODE
+
+use bevy::prelude::*;
+
+use crate::logic::lifetime::DespawnAfter;
+
+const TOAST_SECONDS: f32 = 2.5;
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<ToastEvent>()
+            .add_system(show_toasts.system());
+    }
+}
+
+// A short message near the bottom of the screen that goes away on its own,
+// for things like a bad seed code. Works in any state.
+pub struct ToastEvent(pub String);
+
+struct Toast;
+
+// A new toast replaces the one that is up
+fn show_toasts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut toast_event: EventReader<ToastEvent>,
+    toast_query: Query<Entity, With<Toast>>,
+) {
+    let message = match toast_event.iter().last() {
+        Some(event) => event.0.clone(),
+        None => return,
+    };
+
+    for entity in toast_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(160.0),
+                    left: Val::Percent(35.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                message,
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 36.0,
+                    color: Color::rgb(1.0, 0.6, 0.4),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(Toast)
+        .insert(DespawnAfter::seconds(TOAST_SECONDS));
+}
@@ -0,0 +1,190 @@
+// TRAIL CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+
+use crate::logic::game_time::GameTime;
+use crate::logic::player::{Player, TeleportEvent};
+use crate::Velocity;
+
+// A new after-image every time the player covers this many 'pixels',
+// so the faster they go the more there are
+const TRAIL_SPACING: f32 = 14.0;
+const TRAIL_SECONDS: f32 = 0.25;
+// The alpha of an after-image left at FULL_TRAIL_SPEED or faster
+const TRAIL_ALPHA: f32 = 0.35;
+const FULL_TRAIL_SPEED: f32 = 300.0;
+// Teleports leave a solid line of after-images from one end to the other
+const TELEPORT_TRAIL_IMAGES: usize = 6;
+const TELEPORT_TRAIL_SECONDS: f32 = 0.4;
+const TELEPORT_TRAIL_ALPHA: f32 = 0.6;
+// Just under the player
+const TRAIL_DEPTH_OFFSET: f32 = -0.1;
+
+pub struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(leave_trail.system())
+            .add_system(leave_teleport_trail.system())
+            .add_system(fade_system.system());
+    }
+}
+
+// Fades the sprite's material out and despawns it when the timer is up.
+// The material has to be its own, every sprite using it fades together.
+pub struct Fade {
+    pub timer: Timer,
+    pub start_alpha: f32,
+}
+
+impl Fade {
+    pub fn new(seconds: f32, start_alpha: f32) -> Self {
+        Fade {
+            timer: Timer::from_seconds(seconds, false),
+            start_alpha,
+        }
+    }
+}
+
+// How far each player has gone since their last after-image
+#[derive(Default)]
+struct TrailDistance(f32);
+
+#[allow(clippy::type_complexity)]
+fn leave_trail(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Sprite,
+            &Velocity,
+            &Visible,
+            &Handle<ColorMaterial>,
+            Option<&mut TrailDistance>,
+        ),
+        With<Player>,
+    >,
+    time: Res<GameTime>,
+) {
+    for (entity, transform, sprite, velocity, visible, material, trail_distance) in
+        player_query.iter_mut()
+    {
+        let mut trail_distance = match trail_distance {
+            Some(trail_distance) => trail_distance,
+            None => {
+                commands.entity(entity).insert(TrailDistance::default());
+                continue;
+            }
+        };
+
+        // Dying players are hidden, they shouldn't leave anything behind
+        if !visible.is_visible {
+            trail_distance.0 = 0.0;
+            continue;
+        }
+
+        let speed = velocity.0.length();
+        trail_distance.0 += speed * time.delta_seconds();
+        if trail_distance.0 < TRAIL_SPACING {
+            continue;
+        }
+        trail_distance.0 = 0.0;
+
+        let alpha = TRAIL_ALPHA * (speed / FULL_TRAIL_SPEED).min(1.0);
+        spawn_after_image(
+            &mut commands,
+            &mut materials,
+            material,
+            transform.translation,
+            sprite.size,
+            Fade::new(TRAIL_SECONDS, alpha),
+        );
+    }
+}
+
+// Dashes leave this on top of the trail they make on the way
+fn leave_teleport_trail(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut teleport_event: EventReader<TeleportEvent>,
+    player_query: Query<(&Sprite, &Handle<ColorMaterial>), With<Player>>,
+) {
+    // Teleports don't say who did them, every player looks the same anyway
+    let (sprite, material) = match player_query.iter().next() {
+        Some(player) => player,
+        None => return,
+    };
+
+    for event in teleport_event.iter() {
+        for index in 0..TELEPORT_TRAIL_IMAGES {
+            let along = index as f32 / TELEPORT_TRAIL_IMAGES as f32;
+            spawn_after_image(
+                &mut commands,
+                &mut materials,
+                material,
+                event.from.lerp(event.to, along),
+                sprite.size,
+                // The ones nearer the end fade out later
+                Fade::new(
+                    TELEPORT_TRAIL_SECONDS * (0.5 + along / 2.0),
+                    TELEPORT_TRAIL_ALPHA,
+                ),
+            );
+        }
+    }
+}
+
+fn spawn_after_image(
+    commands: &mut Commands,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    player_material: &Handle<ColorMaterial>,
+    position: Vec3,
+    size: Vec2,
+    fade: Fade,
+) {
+    let texture = materials
+        .get(player_material)
+        .and_then(|material| material.texture.clone());
+    let mut color = Color::WHITE;
+    color.set_a(fade.start_alpha);
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                size,
+                resize_mode: SpriteResizeMode::Manual,
+                ..Default::default()
+            },
+            material: materials.add(ColorMaterial { color, texture }),
+            transform: Transform::from_xyz(position.x, position.y, position.z + TRAIL_DEPTH_OFFSET),
+            ..Default::default()
+        })
+        .insert(fade);
+}
+
+// NOTE
+// Runs on real time like the particles, so after-images still fade out
+// while the game is slowed down.
+fn fade_system(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut fade_query: Query<(Entity, &mut Fade, &Handle<ColorMaterial>)>,
+    time: Res<GameTime>,
+) {
+    for (entity, mut fade, material) in fade_query.iter_mut() {
+        fade.timer.tick(time.delta());
+        if fade.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(material) {
+            material
+                .color
+                .set_a(fade.start_alpha * (1.0 - fade.timer.percent()));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// TWEEN CODE
+
+use bevy::prelude::*;
+
+use crate::logic::game_time::GameTime;
+
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(scale_tween_system.system());
+    }
+}
+
+// Animates the scale of an entity from `start` up to `peak` and then
+// back down to its resting scale of one.
+//
+// NOTE
+// Only the transform scale is touched, the sprite size
+// is what the collision code uses so hitboxes stay the same.
+pub struct ScaleTween {
+    pub start: Vec3,
+    pub peak: Vec3,
+    pub timer: Timer,
+}
+
+impl ScaleTween {
+    // A quick pop from nothing to slightly too big, then settling
+    pub fn pop() -> Self {
+        ScaleTween {
+            start: Vec3::new(0.2, 0.2, 1.0),
+            peak: Vec3::new(1.2, 1.2, 1.0),
+            timer: Timer::from_seconds(0.25, false),
+        }
+    }
+
+    // Flattened against the side it bounced off, then stretching back out
+    // along it before settling. The side is the normal it bounced off.
+    pub fn squash(side: Vec2) -> Self {
+        let squashed = if side.x.abs() > side.y.abs() {
+            Vec3::new(0.7, 1.3, 1.0)
+        } else {
+            Vec3::new(1.3, 0.7, 1.0)
+        };
+        let stretched = Vec3::new(2.0 - squashed.x, 2.0 - squashed.y, 1.0);
+
+        ScaleTween {
+            start: squashed,
+            peak: Vec3::ONE.lerp(stretched, 0.4),
+            timer: Timer::from_seconds(0.2, false),
+        }
+    }
+}
+
+fn scale_tween_system(
+    mut commands: Commands,
+    mut tween_query: Query<(Entity, &mut ScaleTween, &mut Transform)>,
+    time: Res<GameTime>,
+) {
+    for (entity, mut tween, mut transform) in tween_query.iter_mut() {
+        tween.timer.tick(time.delta());
+
+        if tween.timer.finished() {
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<ScaleTween>();
+            continue;
+        }
+
+        // First half goes to the peak, second half settles back to one
+        let progress = tween.timer.percent();
+        transform.scale = if progress < 0.5 {
+            tween.start.lerp(tween.peak, ease_out(progress * 2.0))
+        } else {
+            tween.peak.lerp(Vec3::ONE, ease_out((progress - 0.5) * 2.0))
+        };
+    }
+}
+
+fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t) * (1.0 - t)
+}
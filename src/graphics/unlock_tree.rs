@@ -0,0 +1,140 @@
+// UNLOCK TREE CODE
+
+use bevy::prelude::*;
+
+use crate::logic::input_map::take_just_pressed;
+use crate::logic::profile::{Profile, Unlock};
+use crate::AppState;
+
+pub struct UnlockTreePlugin;
+
+impl Plugin for UnlockTreePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<UnlockCursor>()
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu).with_system(open_unlock_tree.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Unlocks).with_system(spawn_unlock_tree.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Unlocks)
+                    .with_system(navigate_unlock_tree.system())
+                    .with_system(update_unlock_tree_text.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Unlocks).with_system(clear_unlock_tree.system()),
+            );
+    }
+}
+
+// Which unlock is selected
+#[derive(Default)]
+struct UnlockCursor(usize);
+
+struct UnlockTreeText;
+
+// U both opens and closes the tree
+fn open_unlock_tree(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if take_just_pressed(&mut keyboard_input, KeyCode::U) {
+        let _ = app_state.set(AppState::Unlocks);
+    }
+}
+
+fn spawn_unlock_tree(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 36.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(UnlockTreeText);
+        })
+        .insert(UnlockTreeText);
+}
+
+// Up and down pick an unlock, Enter buys it, U goes back
+fn navigate_unlock_tree(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut cursor: ResMut<UnlockCursor>,
+    mut profile: ResMut<Profile>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    let unlock_count = Unlock::ALL.len();
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        cursor.0 = (cursor.0 + 1) % unlock_count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        cursor.0 = (cursor.0 + unlock_count - 1) % unlock_count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) && profile.buy(Unlock::ALL[cursor.0]) {
+        profile.save();
+    }
+
+    if take_just_pressed(&mut keyboard_input, KeyCode::U) {
+        let _ = app_state.set(AppState::MainMenu);
+    }
+}
+
+fn update_unlock_tree_text(
+    cursor: Res<UnlockCursor>,
+    profile: Res<Profile>,
+    mut text_query: Query<&mut Text, With<UnlockTreeText>>,
+) {
+    let mut lines = vec![
+        format!("Coins: {}", profile.coins),
+        "Enter to buy, U to go back".to_string(),
+        String::new(),
+    ];
+
+    lines.extend(Unlock::ALL.iter().enumerate().map(|(index, unlock)| {
+        let cursor_mark = if index == cursor.0 { "> " } else { "  " };
+        let indent = "    ".repeat(unlock.depth());
+        let status = if profile.has(*unlock) {
+            "owned".to_string()
+        } else {
+            format!("{} coins", unlock.cost())
+        };
+
+        format!("{}{}{} ({})", cursor_mark, indent, unlock, status)
+    }));
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn clear_unlock_tree(mut commands: Commands, text_query: Query<Entity, With<UnlockTreeText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
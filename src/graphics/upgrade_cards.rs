@@ -0,0 +1,109 @@
+// UPGRADE CARD CODE
+
+use bevy::prelude::*;
+
+use crate::logic::upgrades::{PickUpgradeEvent, UpgradeOffer};
+use crate::AppState;
+
+// Number keys that pick each card, left to right
+const CARD_KEYS: [KeyCode; 3] = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3];
+
+pub struct UpgradeCardsPlugin;
+
+impl Plugin for UpgradeCardsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::ChoosingUpgrade).with_system(spawn_cards.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::ChoosingUpgrade).with_system(choose_card.system()),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::ChoosingUpgrade).with_system(clear_cards.system()),
+        );
+    }
+}
+
+struct UpgradeCards;
+
+fn spawn_cards(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    upgrade_offer: Res<UpgradeOffer>,
+) {
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/Roboto-Thin.ttf"),
+        font_size: 30.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let card_material = materials.add(Color::rgba(0.1, 0.1, 0.2, 0.9).into());
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            // Dim the paused game behind the cards
+            material: materials.add(Color::rgba(0.0, 0.0, 0.0, 0.5).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (index, upgrade) in upgrade_offer.0.iter().enumerate() {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(260.0), Val::Px(340.0)),
+                            margin: Rect::all(Val::Px(20.0)),
+                            padding: Rect::all(Val::Px(20.0)),
+                            flex_direction: FlexDirection::ColumnReverse,
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..Default::default()
+                        },
+                        material: card_material.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|card| {
+                        card.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                upgrade.to_string(),
+                                text_style.clone(),
+                                Default::default(),
+                            ),
+                            ..Default::default()
+                        });
+
+                        card.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                format!("Press {}", index + 1),
+                                text_style.clone(),
+                                Default::default(),
+                            ),
+                            ..Default::default()
+                        });
+                    });
+            }
+        })
+        .insert(UpgradeCards);
+}
+
+fn choose_card(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut pick_upgrade_event: EventWriter<PickUpgradeEvent>,
+) {
+    for (index, key) in CARD_KEYS.iter().enumerate() {
+        if keyboard_input.just_pressed(*key) {
+            pick_upgrade_event.send(PickUpgradeEvent(index));
+        }
+    }
+}
+
+fn clear_cards(mut commands: Commands, cards_query: Query<Entity, With<UpgradeCards>>) {
+    for entity in cards_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
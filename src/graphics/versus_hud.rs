@@ -0,0 +1,61 @@
+// VERSUS HUD CODE
+
+use bevy::prelude::*;
+
+use crate::logic::versus::VersusMatch;
+
+pub struct VersusHudPlugin;
+
+impl Plugin for VersusHudPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(render_versus_hud.system())
+            .add_system(update_versus_hud.system());
+    }
+}
+
+struct VersusHudText;
+
+// Top middle, empty outside of versus
+fn render_versus_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(20.0),
+                    left: Val::Percent(40.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                    font_size: 32.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(VersusHudText);
+}
+
+// Who is dodging and how much the other player has left to launch with
+fn update_versus_hud(
+    versus: Res<VersusMatch>,
+    mut text_query: Query<&mut Text, With<VersusHudText>>,
+) {
+    if let Ok(mut text) = text_query.single_mut() {
+        text.sections[0].value = if versus.active {
+            format!(
+                "Player {} dodging  Budget: {}",
+                versus.round + 1,
+                versus.budget.floor() as u32
+            )
+        } else {
+            String::new()
+        };
+    }
+}
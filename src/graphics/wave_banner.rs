@@ -0,0 +1,72 @@
+// WAVE BANNER CODE
+
+use bevy::prelude::*;
+
+use crate::logic::waves::WaveManager;
+use crate::AppState;
+
+pub struct WaveBannerPlugin;
+
+impl Plugin for WaveBannerPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(render_wave_banner.system())
+            .add_system(update_wave_banner.system());
+    }
+}
+
+struct WaveBannerText;
+
+fn render_wave_banner(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // Centered across the top, empty while a wave is running
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(200.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Roboto-Thin.ttf"),
+                            font_size: 80.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(WaveBannerText);
+        });
+}
+
+fn update_wave_banner(
+    waves: Res<WaveManager>,
+    app_state: Res<State<AppState>>,
+    mut text_query: Query<&mut Text, With<WaveBannerText>>,
+) {
+    if let Ok(mut text) = text_query.single_mut() {
+        let between_waves = *app_state.current() == AppState::InGame && !waves.is_active();
+
+        text.sections[0].value = if between_waves {
+            format!("Wave {}", waves.wave)
+        } else {
+            String::new()
+        };
+    }
+}
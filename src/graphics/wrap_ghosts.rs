@@ -0,0 +1,200 @@
+// WRAP GHOSTS CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+use bevy::transform::TransformSystem;
+
+use crate::logic::spatial_grid::wrapped_offsets;
+use crate::WrapMargin;
+
+const SYNC_WRAP_GHOSTS: &str = "sync_wrap_ghosts";
+
+pub struct WrapGhostPlugin;
+
+impl Plugin for WrapGhostPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // After everything has moved, before the transforms are used to draw
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            sync_wrap_ghosts
+                .system()
+                .label(SYNC_WRAP_GHOSTS)
+                .before(TransformSystem::TransformPropagate),
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            sync_ghost_sheets
+                .system()
+                .after(SYNC_WRAP_GHOSTS)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+// A copy of something hanging off an edge, drawn on the opposite edge so
+// it never pops in or out. Collisions already count it through
+// collide_wrapped, so it is only ever drawn.
+struct WrapGhost {
+    owner: Entity,
+    offset: Vec2,
+}
+
+// A ghost's copy of a sprite sheet child of its owner, like the player's
+// animation. It is a child of the ghost so it sits the same way on it.
+struct WrapGhostSheet {
+    source: Entity,
+}
+
+// Ghosts aren't children of their owner, blocks are despawned on their own
+// and would leave them behind. They follow every frame instead, and go once
+// their owner is gone or fully back on screen.
+fn sync_wrap_ghosts(
+    mut commands: Commands,
+    owner_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Sprite,
+            &Handle<ColorMaterial>,
+            &Visible,
+        ),
+        (With<WrapMargin>, Without<WrapGhost>),
+    >,
+    mut ghost_query: Query<(
+        Entity,
+        &WrapGhost,
+        &mut Transform,
+        &mut Sprite,
+        &mut Handle<ColorMaterial>,
+        &mut Visible,
+    )>,
+) {
+    let mut kept = Vec::new();
+
+    for (ghost_entity, ghost, mut transform, mut sprite, mut material, mut visible) in
+        ghost_query.iter_mut()
+    {
+        let owner = match owner_query.get(ghost.owner) {
+            Ok(owner) => owner,
+            Err(_) => {
+                commands.entity(ghost_entity).despawn_recursive();
+                continue;
+            }
+        };
+        let (_entity, owner_transform, owner_sprite, owner_material, owner_visible) = owner;
+
+        let still_needed =
+            wrapped_offsets(owner_transform.translation.truncate(), owner_sprite.size)
+                .contains(&ghost.offset);
+        if !still_needed {
+            commands.entity(ghost_entity).despawn_recursive();
+            continue;
+        }
+
+        *transform = *owner_transform;
+        transform.translation += ghost.offset.extend(0.0);
+        sprite.size = owner_sprite.size;
+        *material = owner_material.clone();
+        visible.is_visible = owner_visible.is_visible;
+        kept.push((ghost.owner, ghost.offset));
+    }
+
+    for (entity, transform, sprite, material, visible) in owner_query.iter() {
+        // The first offset is always the owner itself
+        for offset in wrapped_offsets(transform.translation.truncate(), sprite.size)
+            .into_iter()
+            .skip(1)
+        {
+            if kept.contains(&(entity, offset)) {
+                continue;
+            }
+
+            let mut ghost_transform = *transform;
+            ghost_transform.translation += offset.extend(0.0);
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        size: sprite.size,
+                        resize_mode: SpriteResizeMode::Manual,
+                        ..Default::default()
+                    },
+                    material: material.clone(),
+                    transform: ghost_transform,
+                    visible: visible.clone(),
+                    ..Default::default()
+                })
+                .insert(WrapGhost {
+                    owner: entity,
+                    offset,
+                });
+        }
+    }
+}
+
+// The ghost's sheet children are spawned the frame after the ghost itself,
+// then follow the frame and visibility of the children they copy
+#[allow(clippy::type_complexity)]
+fn sync_ghost_sheets(
+    mut commands: Commands,
+    ghost_query: Query<(Entity, &WrapGhost, Option<&Children>)>,
+    owner_query: Query<&Children, Without<WrapGhost>>,
+    source_query: Query<
+        (
+            &TextureAtlasSprite,
+            &Handle<TextureAtlas>,
+            &Transform,
+            &Visible,
+        ),
+        Without<WrapGhostSheet>,
+    >,
+    mut sheet_query: Query<(
+        &WrapGhostSheet,
+        &mut TextureAtlasSprite,
+        &mut Transform,
+        &mut Visible,
+    )>,
+) {
+    for (ghost_entity, ghost, ghost_children) in ghost_query.iter() {
+        let owner_children = match owner_query.get(ghost.owner) {
+            Ok(children) => children,
+            Err(_) => continue,
+        };
+
+        for source in owner_children.iter() {
+            let (source_sprite, atlas, source_transform, source_visible) =
+                match source_query.get(*source) {
+                    Ok(sheet) => sheet,
+                    Err(_) => continue,
+                };
+
+            let mut synced = false;
+            for child in ghost_children.iter().flat_map(|children| children.iter()) {
+                if let Ok((sheet, mut sprite, mut transform, mut visible)) =
+                    sheet_query.get_mut(*child)
+                {
+                    if sheet.source == *source {
+                        *sprite = source_sprite.clone();
+                        *transform = *source_transform;
+                        visible.is_visible = source_visible.is_visible;
+                        synced = true;
+                    }
+                }
+            }
+            if synced {
+                continue;
+            }
+
+            commands.entity(ghost_entity).with_children(|parent| {
+                parent
+                    .spawn_bundle(SpriteSheetBundle {
+                        sprite: source_sprite.clone(),
+                        texture_atlas: atlas.clone(),
+                        transform: *source_transform,
+                        visible: source_visible.clone(),
+                        ..Default::default()
+                    })
+                    .insert(WrapGhostSheet { source: *source });
+            });
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// HEADLESS CODE
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::asset::AssetPlugin;
+use bevy::core::{DefaultTaskPoolOptions, FixedTimesteps};
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::transform::TransformPlugin;
+use bevy::window::WindowPlugin;
+use bevy_kira_audio::{Audio, AudioSource};
+use std::time::Duration;
+
+use crate::graphics::score::Score;
+use crate::logic::blocks::Block;
+use crate::logic::game_time::GameTime;
+use crate::logic::player::PlayerHitEvent;
+use crate::settings::GameSettings;
+use crate::{AppState, GamePlugin};
+
+// Seconds per simulated frame
+const FIXED_TIMESTEP: f64 = 1.0 / 60.0;
+
+// What happened during a simulated run, for tests to check
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct SimulationReport {
+    pub frames: u32,
+    pub points: u32,
+    pub deaths: u32,
+    pub blocks: usize,
+}
+
+// The game without a window, rendering or a sound device. Only the parts
+// of DefaultPlugins the game logic needs are added, the assets that would
+// be drawn or played are registered so loading them still works.
+//
+// NOTE
+// MinimalPlugins is left out, its CorePlugin sets Time from the real clock
+// every frame. The game runs on GameTime, which is stepped by whoever runs
+// the app instead, see advance. Time is only there for the frame time
+// diagnostics. Everything runs on one thread so systems that share the rng
+// always take their turns in the same order.
+pub fn headless_app(settings: GameSettings) -> App {
+    let mut app = App::build();
+    DefaultTaskPoolOptions::with_num_threads(1).create_default_pools(app.world_mut());
+    app.insert_resource(settings)
+        .insert_resource(GameTime::stepped())
+        .init_resource::<Time>()
+        .init_resource::<FixedTimesteps>()
+        .add_plugin(ScheduleRunnerPlugin::default())
+        .add_plugin(TransformPlugin)
+        .add_plugin(AssetPlugin)
+        .add_plugin(InputPlugin)
+        .add_plugin(WindowPlugin::default())
+        .add_asset::<ColorMaterial>()
+        .add_asset::<Texture>()
+        .add_asset::<TextureAtlas>()
+        .add_asset::<Font>()
+        .add_asset::<AudioSource>()
+        .init_resource::<Audio>()
+        .add_plugin(GamePlugin)
+        .init_resource::<SimulationReport>()
+        .add_system(record_simulation.system());
+    app.app
+}
+
+// Starts a run and plays it for the given number of seconds with nobody
+// at the controls, as fast as it can. The same seed always plays out the
+// same way.
+pub fn simulate(seconds: f32, seed: Option<u64>) -> SimulationReport {
+    let mut app = headless_app(GameSettings {
+        seed,
+        ..Default::default()
+    });
+
+    // Startup systems, then into the game
+    advance(&mut app);
+    if let Some(mut app_state) = app.world.get_resource_mut::<State<AppState>>() {
+        let _ = app_state.set(AppState::InGame);
+    }
+
+    let frames = (seconds as f64 / FIXED_TIMESTEP) as u32;
+    for _ in 0..frames {
+        advance(&mut app);
+    }
+
+    app.world
+        .get_resource::<SimulationReport>()
+        .cloned()
+        .unwrap_or_default()
+}
+
+// Moves GameTime on by one fixed timestep and runs a frame
+pub fn advance(app: &mut App) {
+    if let Some(mut game_time) = app.world.get_resource_mut::<GameTime>() {
+        game_time.step(Duration::from_secs_f64(FIXED_TIMESTEP));
+    }
+    app.update();
+}
+
+fn record_simulation(
+    mut report: ResMut<SimulationReport>,
+    score: Res<Score>,
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    block_query: Query<&Block>,
+) {
+    report.frames += 1;
+    report.points = score.points();
+    report.deaths += player_hit_event.iter().count() as u32;
+    report.blocks = block_query.iter().count();
+}
@@ -0,0 +1,332 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::sprite::collide_aabb::Collision;
+
+mod audio;
+pub mod graphics;
+pub mod headless;
+pub mod logic;
+pub mod settings;
+
+pub use graphics::score::ScorePlugin;
+pub use logic::blocks::BlocksPlugin;
+pub use logic::player::PlayerPlugin;
+pub use logic::spawning::SpawningPlugin;
+
+use settings::GameSettings;
+
+pub const WINDOWHEIGHT: f32 = 1200.0;
+pub const WINDOWWIDTH: f32 = 1500.0;
+pub const BLOCKSIZEX: f32 = 40.0;
+pub const BLOCKSIZEY: f32 = 40.0;
+
+// The whole game. Bevy's own plugins and the audio plugin are left to the app,
+// and the default settings are used unless the app inserts its own first.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<GameSettings>()
+            .add_state(AppState::Loading)
+            .add_startup_system(render_background.system())
+            .add_plugin(graphics::camera::CameraPlugin)
+            .add_plugin(audio::GameAudioPlugin)
+            // .add_startup_system(play_music.system())
+            .add_plugin(logic::game_time::GameTimePlugin)
+            .add_plugin(logic::game_rng::GameRngPlugin)
+            .add_plugin(logic::spawning::SpawningPlugin)
+            .add_plugin(logic::spectator::SpectatorPlugin)
+            .add_plugin(logic::gamepad::GamepadPlugin)
+            .add_plugin(logic::player::PlayerPlugin)
+            .add_plugin(logic::blocks::BlocksPlugin)
+            .add_plugin(logic::waves::WavePlugin)
+            .add_plugin(logic::block_kinds::BlockKindPlugin)
+            .add_plugin(logic::spatial_grid::SpatialGridPlugin)
+            .add_plugin(logic::factions::FactionPlugin)
+            .add_plugin(logic::triggers::TriggerPlugin)
+            .add_plugin(logic::block_collisions::BlockCollisionPlugin)
+            .add_plugin(logic::reset_game::ResetGamePlugin)
+            .add_plugin(logic::lifetime::LifetimePlugin)
+            .add_plugin(logic::time_scale::TimeScalePlugin)
+            .add_plugin(logic::beat::BeatPlugin)
+            .add_plugin(logic::snapshots::SnapshotPlugin)
+            .add_plugin(logic::stats::StatsPlugin)
+            .add_plugin(logic::graze::GrazePlugin)
+            .add_plugin(logic::combo::ComboPlugin)
+            .add_plugin(logic::rules::RulesPlugin)
+            .add_plugin(logic::adaptive::AdaptiveDifficultyPlugin)
+            .add_plugin(logic::difficulty::DifficultyPlugin)
+            .add_plugin(logic::mutators::MutatorPlugin)
+            .add_plugin(logic::upgrades::UpgradePlugin)
+            .add_plugin(logic::lives::LivesPlugin)
+            .add_plugin(logic::powerups::PowerUpPlugin)
+            .add_plugin(logic::coop::CoopPlugin)
+            .add_plugin(logic::bot::BotPlugin)
+            .add_plugin(logic::projectiles::ProjectilePlugin)
+            .add_plugin(logic::turrets::TurretPlugin)
+            .add_plugin(logic::splitting::SplittingPlugin)
+            .add_plugin(logic::health::HealthPlugin)
+            .add_plugin(logic::bounty::BountyPlugin)
+            .add_plugin(logic::profile::ProfilePlugin)
+            .add_plugin(logic::challenges::ChallengePlugin)
+            .add_plugin(logic::daily::DailyPlugin)
+            .add_plugin(logic::weekly::WeeklyPlugin)
+            .add_plugin(logic::versus::VersusPlugin)
+            .add_plugin(logic::idle::IdlePlugin)
+            .add_plugin(logic::high_scores::HighScorePlugin)
+            .add_plugin(logic::leaderboard::LeaderboardPlugin)
+            .add_plugin(logic::input_map::InputMapPlugin)
+            .add_plugin(graphics::score::ScorePlugin)
+            .add_plugin(logic::locale::LocalePlugin)
+            .add_plugin(graphics::fonts::FontPlugin)
+            .add_plugin(graphics::rtl_layout::RtlLayoutPlugin)
+            .add_plugin(graphics::loading_screen::LoadingScreenPlugin)
+            .add_plugin(graphics::text::TextPlugin)
+            .add_plugin(graphics::game_over::GameOverPlugin)
+            .add_plugin(graphics::menu::MenuPlugin)
+            .add_plugin(graphics::tween::TweenPlugin)
+            .add_plugin(graphics::death::DeathAnimationPlugin)
+            .add_plugin(graphics::particles::ParticlePlugin)
+            .add_plugin(graphics::flash::FlashPlugin)
+            .add_plugin(graphics::floating_text::FloatingTextPlugin)
+            .add_plugin(graphics::starfield::StarfieldPlugin)
+            .add_plugin(graphics::shadows::ShadowPlugin)
+            .add_plugin(graphics::trail::TrailPlugin)
+            .add_plugin(graphics::display::DisplayPlugin)
+            .add_plugin(graphics::pixel_art::PixelArtPlugin)
+            .add_plugin(graphics::block_style::BlockStylePlugin)
+            // After the passes it draws on top of
+            .add_plugin(graphics::crt::CrtPlugin)
+            .add_plugin(graphics::sprites::SpritePlugin)
+            .add_plugin(graphics::player_animation::PlayerAnimationPlugin)
+            .add_plugin(graphics::kill_cam::KillCamPlugin)
+            .add_plugin(graphics::camera_shake::CameraShakePlugin)
+            .add_plugin(graphics::difficulty_menu::DifficultyMenuPlugin)
+            .add_plugin(graphics::mutator_menu::MutatorMenuPlugin)
+            .add_plugin(graphics::upgrade_cards::UpgradeCardsPlugin)
+            .add_plugin(graphics::lives::LivesTextPlugin)
+            .add_plugin(graphics::hud::HudPlugin)
+            .add_plugin(graphics::versus_hud::VersusHudPlugin)
+            .add_plugin(graphics::unlock_tree::UnlockTreePlugin)
+            .add_plugin(graphics::challenge_menu::ChallengeMenuPlugin)
+            .add_plugin(graphics::seed_menu::SeedMenuPlugin)
+            .add_plugin(graphics::toast::ToastPlugin)
+            .add_plugin(graphics::controls_menu::ControlsMenuPlugin)
+            .add_plugin(graphics::settings_menu::SettingsMenuPlugin)
+            .add_plugin(graphics::pause_menu::PauseMenuPlugin)
+            .add_plugin(graphics::bot_menu::BotMenuPlugin)
+            .add_plugin(graphics::stick_menu::StickMenuPlugin)
+            .add_plugin(graphics::high_score_table::HighScoreTablePlugin)
+            .add_plugin(graphics::feedback_form::FeedbackFormPlugin)
+            .add_plugin(graphics::wave_banner::WaveBannerPlugin)
+            .add_plugin(graphics::wrap_ghosts::WrapGhostPlugin)
+            .add_plugin(graphics::debug_overlay::DebugOverlayPlugin)
+            .add_system(exit_on_esc.system());
+
+        // Chat voting is only built in with the twitch feature
+        #[cfg(feature = "twitch")]
+        app.add_plugin(logic::twitch::TwitchPlugin)
+            .add_plugin(graphics::chat_votes::ChatVoteTextPlugin);
+
+        #[cfg(feature = "overlay")]
+        app.add_plugin(logic::overlay::OverlayPlugin);
+
+        // Last, its reset has to run after everything else's
+        #[cfg(debug_assertions)]
+        app.add_plugin(logic::custom_start::CustomStartPlugin);
+    }
+}
+
+fn render_background(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let background_image: Handle<Texture> = asset_server.load("textures/bg.png");
+
+    // Width of standard image in pixels is 272
+    let background_width = WINDOWWIDTH / 272.0;
+    // Width of standard image in pixels is 160
+    let background_height = WINDOWHEIGHT / 160.0;
+
+    commands.spawn_bundle(SpriteBundle {
+        material: materials.add(background_image.into()),
+        transform: Transform::from_scale(Vec3::new(background_width, background_height, 0.0)),
+
+        ..Default::default()
+    });
+}
+
+// This is called by the system
+#[allow(dead_code)]
+fn exit_system(mut exit: EventWriter<AppExit>) {
+    exit.send(AppExit);
+}
+
+// Escape pauses during a run, can be bound on the controls screen and
+// backs out of typing a seed code, everywhere else it quits
+fn exit_on_esc(
+    keyboard_input: Res<Input<KeyCode>>,
+    app_state: Res<State<AppState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let ignores_escape = matches!(
+        app_state.current(),
+        AppState::InGame
+            | AppState::Paused
+            | AppState::ChoosingUpgrade
+            | AppState::Controls
+            | AppState::SeedEntry
+            | AppState::Settings
+            | AppState::HighScores
+            | AppState::Feedback
+    );
+
+    if keyboard_input.just_pressed(KeyCode::Escape) && !ignores_escape {
+        exit.send(AppExit);
+    }
+}
+
+// The top level flow of the game
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AppState {
+    // Waiting on the fonts and textures before the main menu shows
+    Loading,
+    MainMenu,
+    InGame,
+    // The permanent unlock tree, reached from the main menu
+    Unlocks,
+    // The list of challenge missions, reached from the main menu
+    Challenges,
+    // The key bindings, reached from the main menu
+    Controls,
+    // Typing in a seed code someone shared, reached from the main menu
+    SeedEntry,
+    // The options that are saved to the settings file, reached from the main menu
+    Settings,
+    // The best survival times, reached from the main menu
+    HighScores,
+    // Typing up a bug report or feedback, reached from the main menu
+    Feedback,
+    // Pushed on top of InGame while picking an upgrade card
+    ChoosingUpgrade,
+    // Pushed on top of InGame while the pause menu is open
+    Paused,
+    GameOver,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    // A unit vector pointing the same way
+    pub fn vector(self) -> Vec2 {
+        match self {
+            Direction::Left => Vec2::new(-1.0, 0.0),
+            Direction::Right => Vec2::new(1.0, 0.0),
+            Direction::Up => Vec2::new(0.0, 1.0),
+            Direction::Down => Vec2::new(0.0, -1.0),
+        }
+    }
+
+    // The side of the second box in a collide() the first one hit
+    pub fn hit_side(collision: Collision) -> Self {
+        match collision {
+            Collision::Left => Direction::Left,
+            Collision::Right => Direction::Right,
+            Collision::Top => Direction::Up,
+            Collision::Bottom => Direction::Down,
+        }
+    }
+}
+
+// Anything in the spatial grid. The layer is what it is, the mask is
+// which layers it checks itself against, so pickups only look for players
+// and a wall would look for everything.
+#[derive(Clone, Copy)]
+struct Collidable {
+    layer: u32,
+    mask: u32,
+}
+
+impl Collidable {
+    const PLAYER: u32 = 1 << 0;
+    const BLOCK: u32 = 1 << 1;
+    const BULLET: u32 = 1 << 2;
+    const TURRET: u32 = 1 << 3;
+    const PICKUP: u32 = 1 << 4;
+    // Nothing is a wall yet, one would check against every layer
+    const WALL: u32 = 1 << 5;
+    // Nothing checks against sensors, they only check against others
+    const SENSOR: u32 = 1 << 6;
+
+    fn new(layer: u32, mask: u32) -> Self {
+        Collidable { layer, mask }
+    }
+
+    fn player() -> Self {
+        Collidable::new(
+            Collidable::PLAYER,
+            Collidable::BLOCK | Collidable::BULLET | Collidable::PICKUP | Collidable::WALL,
+        )
+    }
+
+    fn block() -> Self {
+        Collidable::new(
+            Collidable::BLOCK,
+            Collidable::PLAYER | Collidable::BLOCK | Collidable::WALL,
+        )
+    }
+
+    fn bullet() -> Self {
+        Collidable::new(
+            Collidable::BULLET,
+            Collidable::PLAYER | Collidable::BLOCK | Collidable::TURRET | Collidable::WALL,
+        )
+    }
+
+    fn turret() -> Self {
+        Collidable::new(Collidable::TURRET, Collidable::PLAYER | Collidable::BULLET)
+    }
+
+    fn pickup() -> Self {
+        Collidable::new(Collidable::PICKUP, Collidable::PLAYER)
+    }
+
+    fn sensor(mask: u32) -> Self {
+        Collidable::new(Collidable::SENSOR, mask)
+    }
+}
+
+// How fast and which way something moves in 'pixels/second'
+pub struct Velocity(pub Vec2);
+
+// How far past the edge of the screen something keeps going before it wraps,
+// on top of its own half size. Anything without one wraps right away.
+// Anything with one also gets a ghost on the other edge while it hangs off.
+pub struct WrapMargin(pub f32);
+
+// Once something is fully off one edge, plus its margin, it moves across
+// by the size of the playfield. That is where its ghost was being drawn
+// and where collisions already had it, so nothing jumps.
+pub fn wrap_position(translation: &mut Vec3, half_extents: Vec2, margin: f32) {
+    let extents = Vec2::new(WINDOWWIDTH, WINDOWHEIGHT);
+    let bounds = extents / 2.0 + half_extents + Vec2::splat(margin);
+
+    if translation.x > bounds.x {
+        translation.x -= extents.x;
+    } else if translation.x < -bounds.x {
+        translation.x += extents.x;
+    }
+
+    if translation.y > bounds.y {
+        translation.y -= extents.y;
+    } else if translation.y < -bounds.y {
+        translation.y += extents.y;
+    }
+}
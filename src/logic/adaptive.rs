@@ -0,0 +1,104 @@
+// ADAPTIVE DIFFICULTY CODE
+
+use bevy::prelude::*;
+
+use super::player::PlayerDeathEvent;
+use super::reset_game::Resettable;
+use super::rules::GameRules;
+use super::stats::RunStats;
+
+// Dying before this many seconds counts as an early death
+const EARLY_DEATH_SECONDS: f32 = 20.0;
+// Early deaths in a row before spawning eases off
+const EARLY_DEATHS_TO_EASE: u32 = 2;
+// Every this many seconds survived in a run, spawning tightens
+const STREAK_SECONDS: f32 = 30.0;
+const EASE_STEP: f32 = 0.1;
+const TIGHTEN_STEP: f32 = 0.05;
+
+pub struct AdaptiveDifficultyPlugin;
+
+impl Plugin for AdaptiveDifficultyPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<AdaptiveDifficulty>()
+            .reset_with(|adaptive: &mut AdaptiveDifficulty| adaptive.streak_steps = 0)
+            .add_system(ease_after_early_deaths.system())
+            .add_system(tighten_during_streaks.system());
+    }
+}
+
+// Rubber bands the spawn interval around the one from the rules
+pub struct AdaptiveDifficulty {
+    // Multiplies the spawn interval, above one is easier
+    spawn_scale: f32,
+    early_deaths: u32,
+    streak_steps: u32,
+}
+
+impl Default for AdaptiveDifficulty {
+    fn default() -> Self {
+        AdaptiveDifficulty {
+            spawn_scale: 1.0,
+            early_deaths: 0,
+            streak_steps: 0,
+        }
+    }
+}
+
+impl AdaptiveDifficulty {
+    // The spawn interval to actually use, the rules one when adaptive is off
+    pub fn spawn_interval(&self, rules: &GameRules) -> f32 {
+        if !rules.adaptive {
+            return rules.spawn_interval;
+        }
+
+        let (min, max) = rules.spawn_interval_bounds();
+        (rules.spawn_interval * self.spawn_scale).clamp(min, max)
+    }
+
+    fn adjust(&mut self, rules: &GameRules, change: f32) {
+        // Keep the scale inside the bounds so it can't wind up forever
+        let (min, max) = rules.spawn_interval_bounds();
+        self.spawn_scale = (self.spawn_scale + change)
+            .clamp(min / rules.spawn_interval, max / rules.spawn_interval);
+    }
+}
+
+fn ease_after_early_deaths(
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+    rules: Res<GameRules>,
+    run_stats: Res<RunStats>,
+) {
+    for _event in player_death_event.iter() {
+        if !rules.adaptive {
+            continue;
+        }
+
+        if run_stats.survival_seconds >= EARLY_DEATH_SECONDS {
+            adaptive.early_deaths = 0;
+            continue;
+        }
+
+        adaptive.early_deaths += 1;
+        if adaptive.early_deaths >= EARLY_DEATHS_TO_EASE {
+            adaptive.adjust(&rules, EASE_STEP);
+        }
+    }
+}
+
+fn tighten_during_streaks(
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+    rules: Res<GameRules>,
+    run_stats: Res<RunStats>,
+) {
+    if !rules.adaptive {
+        return;
+    }
+
+    let streak_steps = (run_stats.survival_seconds / STREAK_SECONDS) as u32;
+    if streak_steps > adaptive.streak_steps {
+        adaptive.streak_steps = streak_steps;
+        adaptive.adjust(&rules, -TIGHTEN_STEP);
+    }
+}
@@ -0,0 +1,70 @@
+// BEAT CODE
+
+use bevy::prelude::*;
+
+use super::game_time::GameTime;
+
+pub struct BeatPlugin;
+
+impl Plugin for BeatPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<BeatEvent>()
+            .init_resource::<Beat>()
+            .add_system(beat_system.system());
+    }
+}
+
+// Tempo of the music that is playing.
+// Beats are counted from when the music started.
+pub struct Beat {
+    pub bpm: f32,
+    // Seconds since startup when the music started playing
+    pub started_at: f64,
+    // Snap block spawns to the beat instead of a flat timestep
+    pub sync_spawns: bool,
+    last_beat: u64,
+}
+
+impl Default for Beat {
+    fn default() -> Self {
+        Beat {
+            bpm: 120.0,
+            started_at: 0.0,
+            sync_spawns: false,
+            last_beat: 0,
+        }
+    }
+}
+
+impl Beat {
+    // How many beats have gone by after the given number of seconds
+    pub fn beats_at(&self, seconds: f64) -> f64 {
+        seconds * self.bpm as f64 / 60.0
+    }
+
+    // How many beats the music has played so far
+    pub fn beats_since_start(&self, time: &GameTime) -> f64 {
+        self.beats_at((time.seconds_since_startup() - self.started_at).max(0.0))
+    }
+
+    // The number of whole beats closest to the given number of seconds
+    pub fn beats_in(&self, seconds: f64) -> u64 {
+        (self.beats_at(seconds).round() as u64).max(1)
+    }
+}
+
+// Sent on every beat, the value is the number of the beat
+pub struct BeatEvent(pub u64);
+
+fn beat_system(
+    mut beat: ResMut<Beat>,
+    mut beat_event: EventWriter<BeatEvent>,
+    time: Res<GameTime>,
+) {
+    let current_beat = beat.beats_since_start(&time) as u64;
+
+    if current_beat > beat.last_beat {
+        beat.last_beat = current_beat;
+        beat_event.send(BeatEvent(current_beat));
+    }
+}
@@ -0,0 +1,96 @@
+// BLOCK COLLISION CODE
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::blocks::Block;
+use super::spatial_grid::{collide_wrapped, SpatialGrid, BUILD_SPATIAL_GRID};
+use crate::graphics::tween::ScaleTween;
+use crate::settings::GameSettings;
+use crate::{AppState, Collidable, Direction, Velocity};
+
+pub struct BlockCollisionPlugin;
+
+impl Plugin for BlockCollisionPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(bounce_blocks.system().after(BUILD_SPATIAL_GRID)),
+        );
+    }
+}
+
+// Blocks that touch bounce off each other like debris. All blocks weigh
+// the same, so they just trade their speed along the side that was hit.
+// A block that turns around squashes against the side it hit.
+fn bounce_blocks(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    grid: Res<SpatialGrid>,
+    mut block_query: Query<(Entity, &Transform, &Sprite, &mut Velocity), With<Block>>,
+) {
+    if !settings.block_bounce {
+        return;
+    }
+
+    let blocks: HashMap<Entity, (Vec3, Vec2, Vec2)> = block_query
+        .iter_mut()
+        .map(|(entity, transform, sprite, velocity)| {
+            (entity, (transform.translation, sprite.size, velocity.0))
+        })
+        .collect();
+    let mut new_velocities: HashMap<Entity, Vec2> = HashMap::new();
+    // The side each block was last hit on
+    let mut hit_sides: HashMap<Entity, Vec2> = HashMap::new();
+
+    for (entity, (position, size, velocity)) in blocks.iter() {
+        for other in grid.nearby(position.truncate(), *size, Collidable::BLOCK) {
+            // Each pair only once
+            if other.id() <= entity.id() {
+                continue;
+            }
+
+            let (other_position, other_size, other_velocity) = match blocks.get(&other) {
+                Some(other_block) => other_block,
+                None => continue,
+            };
+
+            let collision = match collide_wrapped(*position, *size, *other_position, *other_size) {
+                Some(collision) => collision,
+                None => continue,
+            };
+
+            // Pointing from the other block to this one
+            let normal = Direction::hit_side(collision).vector();
+            let velocity = new_velocities.get(entity).copied().unwrap_or(*velocity);
+            let other_velocity = new_velocities
+                .get(&other)
+                .copied()
+                .unwrap_or(*other_velocity);
+
+            // Already moving apart, leave them be so they don't stick together
+            let closing = (velocity - other_velocity).dot(normal);
+            if closing >= 0.0 {
+                continue;
+            }
+
+            new_velocities.insert(*entity, velocity - closing * normal);
+            new_velocities.insert(other, other_velocity + closing * normal);
+            hit_sides.insert(*entity, normal);
+            hit_sides.insert(other, -normal);
+        }
+    }
+
+    for (entity, new_velocity) in new_velocities {
+        if let Ok((_entity, _transform, _sprite, mut velocity)) = block_query.get_mut(entity) {
+            // Only the ones now going the other way, not those given a nudge
+            if let Some(side) = hit_sides.get(&entity) {
+                if velocity.0.dot(*side) * new_velocity.dot(*side) < 0.0 {
+                    commands.entity(entity).insert(ScaleTween::squash(*side));
+                }
+            }
+
+            velocity.0 = new_velocity;
+        }
+    }
+}
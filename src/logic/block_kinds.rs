@@ -0,0 +1,330 @@
+// BLOCK KIND CODE
+
+use bevy::prelude::*;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+
+use super::blocks::{rotate, Block};
+use super::game_time::GameTime;
+use super::player::{Dying, Player};
+use super::powerups::SlowMotion;
+use super::reset_game::RunScoped;
+use super::time_scale::TimeScale;
+use crate::{AppState, Direction, Velocity};
+
+// How many times a second a zigzag block swings side to side
+const ZIGZAG_FREQUENCY: f32 = 2.0;
+// How far a zigzag block swings to either side in pixels
+const ZIGZAG_AMPLITUDE: f32 = 60.0;
+// How fast a homing block can turn towards the player in 'radians/second'
+const HOMING_TURN_RATE: f32 = 1.0;
+// Any other kind of block has this chance of homing too,
+// with a turn rate somewhere in the range
+const HOMING_CHANCE: f64 = 0.05;
+const MIN_HOMING_TURN_RATE: f32 = 0.4;
+const MAX_HOMING_TURN_RATE: f32 = 0.8;
+// How thick the plate on an armored block is in pixels
+const ARMOR_THICKNESS: f32 = 6.0;
+
+pub struct BlockKindPlugin;
+
+impl Plugin for BlockKindPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(zigzag_blocks.system())
+                .with_system(homing_blocks.system())
+                .with_system(plate_armored_blocks.system())
+                .with_system(move_armor_plates.system()),
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BlockKind {
+    Normal,
+    // Small and fast
+    Small,
+    // Large and slow
+    Large,
+    // Swings side to side as it moves
+    Zigzag,
+    // Slowly turns towards the player
+    Homing,
+    // Bullets bounce off its front
+    Armored,
+    // Bullets bounce off every side, straight back at players
+    Mirror,
+}
+
+impl BlockKind {
+    pub const ALL: [BlockKind; 7] = [
+        BlockKind::Normal,
+        BlockKind::Small,
+        BlockKind::Large,
+        BlockKind::Zigzag,
+        BlockKind::Homing,
+        BlockKind::Armored,
+        BlockKind::Mirror,
+    ];
+
+    // How likely the kind is to be picked relative to the others
+    fn weight(self) -> u32 {
+        match self {
+            BlockKind::Normal => 10,
+            BlockKind::Small => 4,
+            BlockKind::Large => 3,
+            BlockKind::Zigzag => 3,
+            BlockKind::Homing => 1,
+            BlockKind::Armored => 2,
+            BlockKind::Mirror => 1,
+        }
+    }
+
+    pub fn size(self) -> BlockSize {
+        match self {
+            BlockKind::Small => BlockSize::Small,
+            BlockKind::Large => BlockSize::Large,
+            BlockKind::Normal
+            | BlockKind::Zigzag
+            | BlockKind::Homing
+            | BlockKind::Armored
+            | BlockKind::Mirror => BlockSize::Medium,
+        }
+    }
+
+    // Multiplies the base block speed
+    pub fn speed_multiplier(self) -> f32 {
+        match self {
+            BlockKind::Small => 1.5,
+            BlockKind::Large => 0.6,
+            BlockKind::Homing | BlockKind::Armored => 0.8,
+            BlockKind::Normal | BlockKind::Zigzag | BlockKind::Mirror => 1.0,
+        }
+    }
+
+    // Points for destroying a block of this kind, harder targets are worth more
+    pub fn score_value(self) -> u32 {
+        match self {
+            BlockKind::Normal => 10,
+            BlockKind::Small => 20,
+            BlockKind::Large => 5,
+            BlockKind::Zigzag => 15,
+            BlockKind::Homing => 25,
+            BlockKind::Armored => 30,
+            // Can't be shot, so only other ways of destroying it count
+            BlockKind::Mirror => 30,
+        }
+    }
+
+    pub fn random(rng: &mut impl Rng) -> Self {
+        let weights = BlockKind::ALL.iter().map(|kind| kind.weight());
+        let index = WeightedIndex::new(weights)
+            .expect("Block kind weights should not all be zero.")
+            .sample(rng);
+
+        BlockKind::ALL[index]
+    }
+
+    // Adds whatever the kind needs to move the way it does
+    pub fn insert_behavior(
+        self,
+        entity: &mut bevy::ecs::system::EntityCommands,
+        rng: &mut impl Rng,
+    ) {
+        match self {
+            BlockKind::Zigzag => {
+                entity.insert(Zigzag { elapsed: 0.0 });
+            }
+            BlockKind::Homing => {
+                entity.insert(Homing {
+                    turn_rate: HOMING_TURN_RATE,
+                });
+                return;
+            }
+            BlockKind::Armored => {
+                entity.insert(Armored);
+            }
+            BlockKind::Mirror => {
+                entity.insert(Mirror);
+            }
+            BlockKind::Normal | BlockKind::Small | BlockKind::Large => (),
+        }
+
+        if rng.gen_bool(HOMING_CHANCE) {
+            entity.insert(Homing {
+                turn_rate: rng.gen_range(MIN_HOMING_TURN_RATE..MAX_HOMING_TURN_RATE),
+            });
+        }
+    }
+}
+
+// Destroyed blocks split into blocks one size down, small ones are just gone
+#[derive(Clone, Copy, PartialEq)]
+pub enum BlockSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl BlockSize {
+    // Multiplies the base block size
+    pub fn scale(self) -> f32 {
+        match self {
+            BlockSize::Small => 0.6,
+            BlockSize::Medium => 1.0,
+            BlockSize::Large => 1.8,
+        }
+    }
+
+    // Bullets it takes to destroy, so big blocks need a few hits
+    pub fn hit_points(self) -> u32 {
+        match self {
+            BlockSize::Small | BlockSize::Medium => 1,
+            BlockSize::Large => 3,
+        }
+    }
+
+    // What the block splits into when destroyed
+    pub fn smaller(self) -> Option<BlockSize> {
+        match self {
+            BlockSize::Small => None,
+            BlockSize::Medium => Some(BlockSize::Small),
+            BlockSize::Large => Some(BlockSize::Medium),
+        }
+    }
+}
+
+// The swing is added on top of the velocity so the
+// block still travels the way it was aimed
+pub struct Zigzag {
+    elapsed: f32,
+}
+
+// Steers the block towards the closest player
+pub struct Homing {
+    // The fastest the block can turn in 'radians/second'
+    pub turn_rate: f32,
+}
+
+// The side of the block that faces the way it is going is armored
+pub struct Armored;
+
+// Reflects bullets back as hazards instead of being destroyed by them
+pub struct Mirror;
+
+// The plate drawn on the armored side, it follows its block around
+struct ArmorPlate {
+    block: Entity,
+}
+
+// The side of a block moving this way that is out in front
+pub fn leading_side(velocity: Vec2) -> Direction {
+    if velocity.x.abs() > velocity.y.abs() {
+        if velocity.x > 0.0 {
+            Direction::Right
+        } else {
+            Direction::Left
+        }
+    } else if velocity.y > 0.0 {
+        Direction::Up
+    } else {
+        Direction::Down
+    }
+}
+
+fn zigzag_blocks(
+    mut block_query: Query<(&mut Zigzag, &Velocity, &mut Transform), With<Block>>,
+    time: Res<GameTime>,
+    time_scale: Res<TimeScale>,
+    slow_motion: Res<SlowMotion>,
+) {
+    let delta_time = time.delta_seconds() * time_scale.0 * slow_motion.multiplier();
+
+    for (mut zigzag, velocity, mut transform) in block_query.iter_mut() {
+        zigzag.elapsed += delta_time;
+
+        // Move along the derivative of a sine wave across the direction of travel
+        let across = velocity.0.perp() / velocity.0.length().max(f32::EPSILON);
+        let phase = zigzag.elapsed * ZIGZAG_FREQUENCY * std::f32::consts::TAU;
+        let swing = phase.cos() * ZIGZAG_AMPLITUDE * ZIGZAG_FREQUENCY * std::f32::consts::TAU;
+        transform.translation += (across * swing * delta_time).extend(0.0);
+    }
+}
+
+// Turn towards the closest player, but only so fast, so they can be dodged
+fn homing_blocks(
+    mut block_query: Query<(&Homing, &mut Velocity, &Transform), With<Block>>,
+    player_query: Query<&Transform, (With<Player>, Without<Dying>)>,
+    time: Res<GameTime>,
+    time_scale: Res<TimeScale>,
+) {
+    let delta_time = time.delta_seconds() * time_scale.0;
+
+    for (homing, mut velocity, transform) in block_query.iter_mut() {
+        let max_turn = homing.turn_rate * delta_time;
+        let position = transform.translation.truncate();
+        let closest = player_query
+            .iter()
+            .map(|player_transform| player_transform.translation.truncate() - position)
+            .min_by(|a, b| a.length().total_cmp(&b.length()));
+
+        if let Some(to_player) = closest {
+            let angle = velocity.0.angle_between(to_player);
+            if angle.is_nan() {
+                continue;
+            }
+
+            velocity.0 = rotate(velocity.0, angle.clamp(-max_turn, max_turn));
+        }
+    }
+}
+
+fn plate_armored_blocks(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    block_query: Query<Entity, Added<Armored>>,
+) {
+    for entity in block_query.iter() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                material: materials.add(Color::rgb(0.6, 0.65, 0.7).into()),
+                ..Default::default()
+            })
+            .insert(ArmorPlate { block: entity })
+            .insert(RunScoped);
+    }
+}
+
+// Keeps the plate on the leading side even as homing blocks turn,
+// and gets rid of it once the block is gone
+fn move_armor_plates(
+    mut commands: Commands,
+    block_query: Query<(&Transform, &Sprite, &Velocity), With<Armored>>,
+    mut plate_query: Query<(Entity, &ArmorPlate, &mut Transform, &mut Sprite), Without<Armored>>,
+) {
+    for (entity, plate, mut transform, mut sprite) in plate_query.iter_mut() {
+        let (block_transform, block_sprite, velocity) = match block_query.get(plate.block) {
+            Ok(block) => block,
+            Err(_) => {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        };
+
+        let side = leading_side(velocity.0).vector();
+        let half_size = block_sprite.size / 2.0;
+
+        sprite.size = if side.x != 0.0 {
+            Vec2::new(ARMOR_THICKNESS, block_sprite.size.y)
+        } else {
+            Vec2::new(block_sprite.size.x, ARMOR_THICKNESS)
+        };
+        transform.scale = block_transform.scale;
+        transform.translation = block_transform.translation
+            + (side
+                * (half_size - Vec2::splat(ARMOR_THICKNESS / 2.0))
+                * block_transform.scale.truncate())
+            .extend(0.1);
+    }
+}
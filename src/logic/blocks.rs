@@ -1,34 +1,69 @@
 // BLOCKS CODE
 
 // use crate::logic::spawning::{SpawnInfo};
-use crate::{Collidable, Direction, BLOCKSIZEX, BLOCKSIZEY, WINDOWHEIGHT, WINDOWWIDTH};
+use crate::{
+    wrap_position, AppState, Collidable, Direction, Velocity, WrapMargin, BLOCKSIZEX, BLOCKSIZEY,
+    WINDOWHEIGHT, WINDOWWIDTH,
+};
 
-use bevy::core::FixedTimestep;
 use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
 use rand::distributions::{Distribution, Standard};
 use rand::seq::IteratorRandom;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
+use super::adaptive::AdaptiveDifficulty;
+use super::beat::{Beat, BeatEvent};
+use super::block_kinds::{BlockKind, BlockSize};
+use super::difficulty::Difficulty;
+use super::factions::{Damage, Faction};
+use super::game_rng::GameRng;
+use super::game_time::GameTime;
+use super::health::Health;
+use super::player::{Controls, Player};
+use super::powerups::SlowMotion;
+use super::reset_game::RunScoped;
+use super::rules::GameRules;
 use super::spawning::SpawnInfo;
+use super::time_scale::TimeScale;
+use super::upgrades::RunUpgrades;
+use super::waves::WaveManager;
+use crate::graphics::sprites::block_texture;
+use crate::graphics::tween::ScaleTween;
+use std::time::Duration;
 
-// For BLOCK_SPAWN_TIMESTEP, it's once every two seconds
-const BLOCK_SPAWN_TIMESTEP: f64 = 120.0 / 60.0;
+// Base block speed in 'pixels/second', scaled by the rules
+const BLOCK_VELOCITY: f32 = 300.0;
+// How far off of straight across the screen a block can be aimed, in radians
+const MAX_SPAWN_ANGLE: f32 = 0.5;
+// How far past the edge blocks go before moving across to their ghost
+pub const BLOCK_WRAP_MARGIN: f32 = 40.0;
+// How long the warning marker shows before a runtime block comes in
+const SPAWN_WARNING_SECONDS: f32 = 0.75;
+// Seconds between the warning marker blinking on and off
+const SPAWN_WARNING_BLINK: f32 = 0.125;
+const SPAWN_WARNING_SIZE: f32 = 24.0;
 
 pub struct BlocksPlugin;
 
 impl Plugin for BlocksPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app
-            // Needs to be run after spawning logic
-            .add_event::<SpawnBlockEvent>()
-            .add_startup_system_to_stage(StartupStage::PostStartup, spawn_starting_block.system())
+        // NOTE
+        // Each wave spawns its blocks at once, see the wave logic
+        app.add_event::<SpawnBlockEvent>()
+            .add_event::<WarnSpawnEvent>()
+            .add_event::<BlockDestroyedEvent>()
+            .insert_resource(SpawnTimer(Timer::from_seconds(2.0, true)))
             .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(BLOCK_SPAWN_TIMESTEP))
-                    .with_system(spawn_runtime_blocks.system()),
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(spawn_runtime_blocks.system())
+                    .with_system(spawn_blocks_on_beat.system())
+                    .with_system(show_spawn_warnings.system())
+                    .with_system(tick_spawn_warnings.system())
+                    .with_system(move_blocks.system()),
             )
-            .add_system(move_blocks.system())
-            .add_system(spawn_block.system());
+            .add_system(spawn_block.system())
+            .add_system(free_spawn_positions.system());
     }
 }
 
@@ -43,78 +78,298 @@ impl Distribution<Direction> for Standard {
     }
 }
 
-pub struct Block {
-    velocity: f32,
-    direction: Direction,
-}
+pub struct Block;
 
 pub struct SpawnBlockEvent(pub Entity);
 
-// Spawns starting blocks for the game
-fn spawn_starting_block(
-    mut spawn_positions_query: Query<(Entity, With<SpawnInfo>)>,
-    mut spawn_event: EventWriter<SpawnBlockEvent>,
+// Puts a warning marker up at a spawn position, the block follows shortly
+pub struct WarnSpawnEvent(pub Entity);
+
+// A runtime block that is about to come in
+pub struct SpawnWarning {
+    pub spawn_position: Entity,
+    timer: Timer,
+}
+
+// The spawn position a block came in from
+pub struct SpawnedFrom(pub Entity);
+
+// Sent when something destroys a block, the block is already despawned
+pub struct BlockDestroyedEvent {
+    pub block: Entity,
+    pub position: Vec3,
+    pub velocity: Vec2,
+    pub kind: BlockKind,
+    pub size: BlockSize,
+    pub spawned_from: Option<Entity>,
+    // Who destroyed it, if it was a player
+    pub destroyed_by: Option<Controls>,
+}
+
+// Counts down to the next runtime block spawn
+struct SpawnTimer(Timer);
+
+// spawns blocks as a way to make the game harder during a wave
+// this will only spawn once every spawn interval from the rules
+#[allow(clippy::too_many_arguments)]
+fn spawn_runtime_blocks(
+    mut spawn_timer: ResMut<SpawnTimer>,
+    rules: Res<GameRules>,
+    adaptive: Res<AdaptiveDifficulty>,
+    beat: Res<Beat>,
+    waves: Res<WaveManager>,
+    difficulty: Res<Difficulty>,
+    time: Res<GameTime>,
+    spawn_positions_query: Query<(Entity, &SpawnInfo)>,
+    warning_query: Query<&SpawnWarning>,
+    mut warn_event: EventWriter<WarnSpawnEvent>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    let block_number = 6;
-    for (entity, _boolean) in spawn_positions_query.iter_mut().take(block_number) {
-        spawn_event.send(SpawnBlockEvent(entity))
+    // The rules can change the interval at any point,
+    // and the longer the run goes the faster blocks come in
+    let spawn_interval = adaptive.spawn_interval(&rules) / difficulty.rate();
+    spawn_timer
+        .0
+        .set_duration(Duration::from_secs_f32(spawn_interval));
+    spawn_timer.0.tick(time.delta());
+
+    // The beat decides when to spawn instead, and nothing spawns between waves
+    if !spawn_timer.0.just_finished() || beat.sync_spawns || !waves.is_active() {
+        return;
     }
+
+    spawn_random_block(
+        &spawn_positions_query,
+        &warning_query,
+        &mut warn_event,
+        &mut game_rng,
+    );
 }
 
-// spawns blocks as a way to make the game harder during runtime
-// this will only run every spawn block timestep
-fn spawn_runtime_blocks(
+// In beat mode the spawn timestep is rounded to a whole number of beats,
+// and blocks spawn right on those beats
+#[allow(clippy::too_many_arguments)]
+fn spawn_blocks_on_beat(
+    beat: Res<Beat>,
+    rules: Res<GameRules>,
+    adaptive: Res<AdaptiveDifficulty>,
+    waves: Res<WaveManager>,
+    difficulty: Res<Difficulty>,
+    mut beat_event: EventReader<BeatEvent>,
     spawn_positions_query: Query<(Entity, &SpawnInfo)>,
-    mut spawn_event: EventWriter<SpawnBlockEvent>,
+    warning_query: Query<&SpawnWarning>,
+    mut warn_event: EventWriter<WarnSpawnEvent>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    let mut rng = thread_rng();
+    let beats_per_spawn =
+        beat.beats_in((adaptive.spawn_interval(&rules) / difficulty.rate()) as f64);
+
+    for event in beat_event.iter() {
+        if beat.sync_spawns && waves.is_active() && event.0 % beats_per_spawn == 0 {
+            spawn_random_block(
+                &spawn_positions_query,
+                &warning_query,
+                &mut warn_event,
+                &mut game_rng,
+            );
+        }
+    }
+}
 
+// Runtime blocks are warned about first so they don't come out of nowhere
+fn spawn_random_block(
+    spawn_positions_query: &Query<(Entity, &SpawnInfo)>,
+    warning_query: &Query<&SpawnWarning>,
+    warn_event: &mut EventWriter<WarnSpawnEvent>,
+    rng: &mut GameRng,
+) {
+    // A position with a warning up is already taken
     let spawn_entity: Option<Entity> = spawn_positions_query
         .iter()
-        .filter(|(_entity, spawn_position)| !spawn_position.spawned)
+        .filter(|(entity, spawn_position)| {
+            !spawn_position.spawned
+                && !warning_query
+                    .iter()
+                    .any(|warning| warning.spawn_position == *entity)
+        })
         .map(|tuple| tuple.0)
-        .choose(&mut rng);
+        .choose(rng);
 
     match spawn_entity {
-        Some(entity) => spawn_event.send(SpawnBlockEvent(entity)),
+        Some(entity) => warn_event.send(WarnSpawnEvent(entity)),
         // NOTE
         // You could do the swap around stuff here at some point
         None => println!("empty"),
     }
 }
 
+fn show_spawn_warnings(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut warn_event: EventReader<WarnSpawnEvent>,
+    spawn_query: Query<&SpawnInfo>,
+) {
+    for event in warn_event.iter() {
+        if let Ok(spawn_position) = spawn_query.get(event.0) {
+            // Spawn positions sit right on the edge, keep the marker on screen
+            let margin = SPAWN_WARNING_SIZE;
+            let x = (spawn_position.spawn_location.0 as f32)
+                .clamp(-WINDOWWIDTH / 2.0 + margin, WINDOWWIDTH / 2.0 - margin);
+            let y = (spawn_position.spawn_location.1 as f32)
+                .clamp(-WINDOWHEIGHT / 2.0 + margin, WINDOWHEIGHT / 2.0 - margin);
+
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite::new(Vec2::new(SPAWN_WARNING_SIZE, SPAWN_WARNING_SIZE)),
+                    material: materials.add(Color::rgba(1.0, 0.2, 0.2, 0.8).into()),
+                    transform: Transform::from_xyz(x, y, 2.0),
+                    ..Default::default()
+                })
+                .insert(SpawnWarning {
+                    spawn_position: event.0,
+                    timer: Timer::from_seconds(SPAWN_WARNING_SECONDS, false),
+                })
+                .insert(RunScoped);
+        }
+    }
+}
+
+// Blink the markers, then swap them for the real blocks. A player can walk
+// into the lane while it blinks, then the warning moves to a safe lane and
+// starts over so a block never comes in without one.
+#[allow(clippy::too_many_arguments)]
+fn tick_spawn_warnings(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut warning_query: Query<(Entity, &mut SpawnWarning, &mut Visible)>,
+    mut spawn_query: Query<(Entity, &mut SpawnInfo)>,
+    player_query: Query<&Transform, With<Player>>,
+    rules: Res<GameRules>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_event: EventWriter<SpawnBlockEvent>,
+    mut warn_event: EventWriter<WarnSpawnEvent>,
+) {
+    let mut queued: Vec<Entity> = warning_query
+        .iter_mut()
+        .map(|(_entity, warning, _visible)| warning.spawn_position)
+        .collect();
+
+    for (entity, mut warning, mut visible) in warning_query.iter_mut() {
+        warning.timer.tick(time.delta());
+        visible.is_visible = (warning.timer.elapsed_secs() / SPAWN_WARNING_BLINK) as u32 % 2 == 0;
+
+        if !warning.timer.finished() {
+            continue;
+        }
+        commands.entity(entity).despawn();
+
+        let lane = safe_spawn_position(
+            warning.spawn_position,
+            &mut spawn_query,
+            &player_query,
+            &queued,
+            &rules,
+            &mut game_rng,
+        );
+        match lane {
+            Some(lane) if lane == warning.spawn_position => {
+                spawn_event.send(SpawnBlockEvent(lane));
+            }
+            Some(lane) => {
+                queued.push(lane);
+                warn_event.send(WarnSpawnEvent(lane));
+            }
+            None => {}
+        }
+    }
+}
+
 // This is called by an event
+#[allow(clippy::too_many_arguments)]
 fn spawn_block(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut spawn_event: EventReader<SpawnBlockEvent>,
-    mut spawn_query: Query<&mut SpawnInfo>,
+    mut spawn_query: Query<(Entity, &mut SpawnInfo)>,
+    player_query: Query<&Transform, With<Player>>,
+    warning_query: Query<&SpawnWarning>,
+    rules: Res<GameRules>,
+    upgrades: Res<RunUpgrades>,
+    waves: Res<WaveManager>,
+    difficulty: Res<Difficulty>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    for event in spawn_event.iter() {
-        let entity: Entity = event.0;
+    let queued: Vec<Entity> = warning_query
+        .iter()
+        .map(|warning| warning.spawn_position)
+        .collect();
 
-        if let Ok(mut spawn_position) = spawn_query.get_mut(entity) {
-            let texture_handle = asset_server.load("textures/block_1.png");
+    for event in spawn_event.iter() {
+        // Spawning right on top of a player would be an unavoidable death
+        // and with nowhere safe the block is skipped
+        let entity = match safe_spawn_position(
+            event.0,
+            &mut spawn_query,
+            &player_query,
+            &queued,
+            &rules,
+            &mut game_rng,
+        ) {
+            Some(entity) => entity,
+            None => continue,
+        };
 
+        if let Ok((_entity, mut spawn_position)) = spawn_query.get_mut(entity) {
             let location = spawn_position.spawn_location;
-            let direction = spawn_position.spawn_direction;
+
+            // Aim roughly across the screen from the spawn edge
+            let angle = game_rng.gen_range(-MAX_SPAWN_ANGLE..=MAX_SPAWN_ANGLE);
+            let direction = rotate(spawn_position.spawn_direction.vector(), angle);
+            let kind = BlockKind::random(&mut *game_rng);
+            let texture_handle = block_texture(&asset_server, kind);
+            let speed = BLOCK_VELOCITY
+                * kind.speed_multiplier()
+                * rules.speed_multiplier
+                * upgrades.block_speed_multiplier()
+                * waves.speed_multiplier()
+                * difficulty.rate();
+            let block_size = kind.size();
+            let size = Vec2::new(BLOCKSIZEX, BLOCKSIZEY) * block_size.scale();
 
             // set the positions spawned value to true
             spawn_position.spawned = true;
 
-            commands
-                .spawn_bundle(SpriteBundle {
-                    sprite: Sprite::new(Vec2::new(BLOCKSIZEX, BLOCKSIZEY)),
-                    material: materials.add(texture_handle.into()),
-                    transform: Transform::from_xyz(location.0 as f32, location.1 as f32, 1.0),
+            // Start small, the tween pops the block up to full size
+            let pop = ScaleTween::pop();
+            let mut transform = Transform::from_xyz(location.0 as f32, location.1 as f32, 1.0);
+            transform.scale = pop.start;
+
+            let mut block = commands.spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    size,
+                    // Keep the texture from resizing the sprite to its own size
+                    resize_mode: SpriteResizeMode::Manual,
                     ..Default::default()
-                })
-                .insert(Block {
-                    velocity: 300.0,
-                    direction,
-                })
-                .insert(Collidable);
+                },
+                material: materials.add(texture_handle.into()),
+                transform,
+                ..Default::default()
+            });
+            block
+                .insert(Block)
+                .insert(SpawnedFrom(entity))
+                .insert(kind)
+                .insert(block_size)
+                .insert(Health::new(block_size.hit_points()))
+                .insert(Faction::Hostile)
+                .insert(Damage(1))
+                .insert(Velocity(direction * speed))
+                .insert(Collidable::block())
+                .insert(WrapMargin(BLOCK_WRAP_MARGIN))
+                .insert(RunScoped)
+                .insert(pop);
+            kind.insert_behavior(&mut block, &mut *game_rng);
         } else {
             // the entity does not have the components from the query
             println!("not here")
@@ -122,32 +377,76 @@ fn spawn_block(
     }
 }
 
-// move the block by its own velocity
-fn move_blocks(mut block_query: Query<(&Block, &mut Transform, &Sprite)>, time: Res<Time>) {
-    for (block, mut transform, sprite) in block_query.iter_mut() {
-        let block_speed = block.velocity * time.delta_seconds();
-        match &block.direction {
-            Direction::Left => transform.translation.x -= block_speed,
-            Direction::Right => transform.translation.x += block_speed,
-            Direction::Up => transform.translation.y += block_speed,
-            Direction::Down => transform.translation.y -= block_speed,
-        };
+// The requested spawn position if it is free and far enough from every
+// player, otherwise a random one that is. Queued positions have a warning
+// up and are left for the block that is coming there.
+fn safe_spawn_position(
+    requested: Entity,
+    spawn_query: &mut Query<(Entity, &mut SpawnInfo)>,
+    player_query: &Query<&Transform, With<Player>>,
+    queued: &[Entity],
+    rules: &GameRules,
+    rng: &mut GameRng,
+) -> Option<Entity> {
+    let is_safe = |spawn_location: (i16, i16)| {
+        let location = Vec2::new(spawn_location.0 as f32, spawn_location.1 as f32);
+        player_query.iter().all(|transform| {
+            transform.translation.truncate().distance(location) >= rules.safe_spawn_radius
+        })
+    };
 
-        // Wrap the block if they go off screen
-        if transform.translation.x > WINDOWWIDTH / 2.0 + sprite.size.x {
-            transform.translation.x = -WINDOWWIDTH / 2.0;
+    if let Ok((_entity, spawn_position)) = spawn_query.get_mut(requested) {
+        if !spawn_position.spawned && is_safe(spawn_position.spawn_location) {
+            return Some(requested);
         }
+    }
 
-        if transform.translation.x < -WINDOWWIDTH / 2.0 - sprite.size.x {
-            transform.translation.x = WINDOWWIDTH / 2.0;
-        }
+    spawn_query
+        .iter_mut()
+        .filter(|(entity, spawn_position)| {
+            !spawn_position.spawned
+                && is_safe(spawn_position.spawn_location)
+                && !queued.contains(entity)
+        })
+        .map(|(entity, _spawn_position)| entity)
+        .choose(rng)
+}
 
-        if transform.translation.y > WINDOWHEIGHT / 2.0 + sprite.size.y {
-            transform.translation.y = -WINDOWHEIGHT / 2.0;
+// A destroyed block frees up the spot it came in from
+fn free_spawn_positions(
+    mut block_destroyed_event: EventReader<BlockDestroyedEvent>,
+    mut spawn_query: Query<&mut SpawnInfo>,
+) {
+    for event in block_destroyed_event.iter() {
+        if let Some(spawned_from) = event.spawned_from {
+            if let Ok(mut spawn_position) = spawn_query.get_mut(spawned_from) {
+                spawn_position.spawned = false;
+            }
         }
+    }
+}
 
-        if transform.translation.y < -WINDOWHEIGHT / 2.0 - sprite.size.y {
-            transform.translation.y = WINDOWHEIGHT / 2.0;
-        }
+pub fn rotate(vector: Vec2, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    Vec2::new(
+        vector.x * cos - vector.y * sin,
+        vector.x * sin + vector.y * cos,
+    )
+}
+
+// move the block by its own velocity
+fn move_blocks(
+    mut block_query: Query<(&Velocity, &mut Transform, &Sprite, Option<&WrapMargin>), With<Block>>,
+    time: Res<GameTime>,
+    time_scale: Res<TimeScale>,
+    slow_motion: Res<SlowMotion>,
+) {
+    for (velocity, mut transform, sprite, wrap_margin) in block_query.iter_mut() {
+        let scale = time.delta_seconds() * time_scale.0 * slow_motion.multiplier();
+        transform.translation += (velocity.0 * scale).extend(0.0);
+
+        // Wrap the block once it is fully off screen
+        let margin = wrap_margin.map_or(0.0, |margin| margin.0);
+        wrap_position(&mut transform.translation, sprite.size / 2.0, margin);
     }
 }
@@ -0,0 +1,175 @@
+// BOT CODE
+
+use bevy::prelude::*;
+use std::fmt;
+
+use super::blocks::Block;
+use super::game_time::GameTime;
+use super::player::{Controls, Dying, Player};
+use crate::{AppState, Velocity, WINDOWWIDTH};
+
+// Blocks closer than this are worth dodging
+const DANGER_RADIUS: f32 = 150.0;
+// Blocks closer than this are worth teleporting away from
+const PANIC_RADIUS: f32 = 60.0;
+// Blocks closer than this are worth shooting at
+const SHOOT_RADIUS: f32 = 400.0;
+// How hard the bot drifts back towards the middle of the screen
+const CENTER_PULL: f32 = 0.3;
+
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<BotSettings>()
+            .add_system(give_bots_brains.system())
+            .add_system_set(SystemSet::on_update(AppState::InGame).with_system(bot_think.system()));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BotDifficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl BotDifficulty {
+    pub fn next(self) -> Self {
+        match self {
+            BotDifficulty::Easy => BotDifficulty::Normal,
+            BotDifficulty::Normal => BotDifficulty::Hard,
+            BotDifficulty::Hard => BotDifficulty::Easy,
+        }
+    }
+
+    // Seconds between decisions, slower bots react later
+    fn think_interval(self) -> f32 {
+        match self {
+            BotDifficulty::Easy => 0.3,
+            BotDifficulty::Normal => 0.15,
+            BotDifficulty::Hard => 0.05,
+        }
+    }
+
+    // How many seconds ahead the bot predicts where blocks will be
+    fn lookahead(self) -> f32 {
+        match self {
+            BotDifficulty::Easy => 0.3,
+            BotDifficulty::Normal => 0.6,
+            BotDifficulty::Hard => 1.0,
+        }
+    }
+
+    fn can_teleport(self) -> bool {
+        self != BotDifficulty::Easy
+    }
+}
+
+impl fmt::Display for BotDifficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BotDifficulty::Easy => write!(f, "Easy"),
+            BotDifficulty::Normal => write!(f, "Normal"),
+            BotDifficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+// Whether solo runs get a bot teammate, picked on the main menu
+pub struct BotSettings {
+    pub enabled: bool,
+    pub difficulty: BotDifficulty,
+}
+
+impl Default for BotSettings {
+    fn default() -> Self {
+        BotSettings {
+            enabled: false,
+            difficulty: BotDifficulty::Normal,
+        }
+    }
+}
+
+// What the bot has decided to do, read by the player movement code
+pub struct BotBrain {
+    pub move_delta: Vec2,
+    pub teleport: bool,
+    // Which way to shoot, if at all
+    pub aim: Option<Vec2>,
+    think_timer: Timer,
+}
+
+// Every bot player, including respawned ones, needs a brain
+fn give_bots_brains(
+    mut commands: Commands,
+    bot_settings: Res<BotSettings>,
+    player_query: Query<(Entity, &Controls), Added<Controls>>,
+) {
+    for (entity, controls) in player_query.iter() {
+        if *controls == Controls::Bot {
+            commands.entity(entity).insert(BotBrain {
+                move_delta: Vec2::ZERO,
+                teleport: false,
+                aim: None,
+                think_timer: Timer::from_seconds(bot_settings.difficulty.think_interval(), true),
+            });
+        }
+    }
+}
+
+// Dodge by moving away from where nearby blocks are about to be,
+// and shoot at whichever block is closest
+fn bot_think(
+    bot_settings: Res<BotSettings>,
+    mut bot_query: Query<(&mut BotBrain, &Transform), (With<Player>, Without<Dying>)>,
+    block_query: Query<(&Transform, &Velocity), With<Block>>,
+    time: Res<GameTime>,
+) {
+    let difficulty = bot_settings.difficulty;
+    let lookahead = difficulty.lookahead();
+
+    for (mut brain, transform) in bot_query.iter_mut() {
+        if !brain.think_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let mut push = -position / (WINDOWWIDTH / 2.0) * CENTER_PULL;
+        let mut closest = f32::MAX;
+        let mut closest_offset = Vec2::ZERO;
+
+        for (block_transform, block_velocity) in block_query.iter() {
+            let block_position = block_transform.translation.truncate();
+            let distance = block_position.distance(position);
+            if distance < closest {
+                closest = distance;
+                closest_offset = block_position - position;
+            }
+
+            // Check now, halfway, and at the end of the lookahead
+            for step in 0..=2 {
+                let predicted = block_position + block_velocity.0 * lookahead * step as f32 / 2.0;
+                let away = position - predicted;
+                let distance = away.length().max(1.0);
+
+                if distance < DANGER_RADIUS {
+                    push += away / distance * (DANGER_RADIUS - distance) / DANGER_RADIUS;
+                }
+            }
+        }
+
+        brain.move_delta = if push.length() > 0.05 {
+            push.normalize()
+        } else {
+            Vec2::ZERO
+        };
+        brain.teleport =
+            difficulty.can_teleport() && closest < PANIC_RADIUS && brain.move_delta != Vec2::ZERO;
+        brain.aim = if closest < SHOOT_RADIUS && closest_offset != Vec2::ZERO {
+            Some(closest_offset.normalize())
+        } else {
+            None
+        };
+    }
+}
@@ -0,0 +1,290 @@
+// CHALLENGE CODE
+
+use bevy::prelude::*;
+
+use super::mutators::Mutator;
+use super::player::{Controls, Dying, Player, PlayerDeathEvent};
+use super::profile::Profile;
+use super::reset_game::Resettable;
+use super::rules::DifficultyPreset;
+use super::stats::RunStats;
+use crate::AppState;
+
+pub struct ChallengePlugin;
+
+impl Plugin for ChallengePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<ChallengeProgress>()
+            // The picked challenge stays for the next attempt
+            .reset_with(|progress: &mut ChallengeProgress| {
+                progress.outcome = None;
+                progress.last_position = None;
+            })
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(clear_challenge.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(check_challenge.system()),
+            )
+            .add_system(fail_challenge_on_death.system());
+    }
+}
+
+// Extra rules a challenge can put on the player, breaking one fails it
+#[derive(Clone, Copy, PartialEq)]
+pub enum Restriction {
+    NoVerticalMovement,
+    NoHorizontalMovement,
+    MaxTeleports(u32),
+}
+
+// What has to be done to win a challenge
+#[derive(Clone, Copy, PartialEq)]
+pub enum Goal {
+    // Stay alive this long
+    Survive(f32),
+    // Destroy this many blocks before the time is up
+    DestroyBlocks { count: u32, seconds: f32 },
+    // See this many waves through to the end
+    FinishWaves(u32),
+}
+
+// A handcrafted run with its own rules, won by reaching its goal
+// without breaking any restriction
+pub struct Challenge {
+    // Saved in the profile, so never rename one
+    pub id: &'static str,
+    pub description: &'static str,
+    pub preset: DifficultyPreset,
+    pub mutators: &'static [Mutator],
+    pub restrictions: &'static [Restriction],
+    pub goal: Goal,
+    // Coins paid out the first time the challenge is completed
+    pub reward: u32,
+}
+
+pub const CHALLENGES: [Challenge; 13] = [
+    Challenge {
+        id: "warm_up",
+        description: "Survive 30s on easy",
+        preset: DifficultyPreset::Easy,
+        mutators: &[],
+        restrictions: &[],
+        goal: Goal::Survive(30.0),
+        reward: 20,
+    },
+    Challenge {
+        id: "grounded",
+        description: "Survive 60s with no dashing",
+        preset: DifficultyPreset::Normal,
+        mutators: &[Mutator::NoDash],
+        restrictions: &[],
+        goal: Goal::Survive(60.0),
+        reward: 40,
+    },
+    Challenge {
+        id: "flatland",
+        description: "Survive 45s without moving vertically",
+        preset: DifficultyPreset::Normal,
+        mutators: &[],
+        restrictions: &[Restriction::NoVerticalMovement],
+        goal: Goal::Survive(45.0),
+        reward: 50,
+    },
+    Challenge {
+        id: "elevator",
+        description: "Survive 45s without moving horizontally",
+        preset: DifficultyPreset::Normal,
+        mutators: &[],
+        restrictions: &[Restriction::NoHorizontalMovement],
+        goal: Goal::Survive(45.0),
+        reward: 50,
+    },
+    Challenge {
+        id: "minimalist",
+        description: "Survive 60s teleporting at most 5 times",
+        preset: DifficultyPreset::Normal,
+        mutators: &[],
+        restrictions: &[Restriction::MaxTeleports(5)],
+        goal: Goal::Survive(60.0),
+        reward: 50,
+    },
+    Challenge {
+        id: "small_target",
+        description: "Survive 60s as a tiny player on hard",
+        preset: DifficultyPreset::Hard,
+        mutators: &[Mutator::TinyPlayer],
+        restrictions: &[],
+        goal: Goal::Survive(60.0),
+        reward: 40,
+    },
+    Challenge {
+        id: "looking_glass",
+        description: "Survive 60s with mirrored controls",
+        preset: DifficultyPreset::Normal,
+        mutators: &[Mutator::MirrorControls],
+        restrictions: &[],
+        goal: Goal::Survive(60.0),
+        reward: 60,
+    },
+    Challenge {
+        id: "lights_out",
+        description: "Survive 45s with hidden blocks",
+        preset: DifficultyPreset::Normal,
+        mutators: &[Mutator::HiddenBlocks],
+        restrictions: &[],
+        goal: Goal::Survive(45.0),
+        reward: 60,
+    },
+    Challenge {
+        id: "rush_hour",
+        description: "Survive 40s at double speed with no dashing",
+        preset: DifficultyPreset::Normal,
+        mutators: &[Mutator::DoubleSpeed, Mutator::NoDash],
+        restrictions: &[],
+        goal: Goal::Survive(40.0),
+        reward: 80,
+    },
+    Challenge {
+        id: "demolition",
+        description: "Destroy 30 blocks in 45s",
+        preset: DifficultyPreset::Normal,
+        mutators: &[],
+        restrictions: &[],
+        goal: Goal::DestroyBlocks {
+            count: 30,
+            seconds: 45.0,
+        },
+        reward: 60,
+    },
+    Challenge {
+        id: "level_headed",
+        description: "Finish a wave without moving vertically",
+        preset: DifficultyPreset::Normal,
+        mutators: &[],
+        restrictions: &[Restriction::NoVerticalMovement],
+        goal: Goal::FinishWaves(1),
+        reward: 60,
+    },
+    Challenge {
+        id: "long_haul",
+        description: "Finish 3 waves on hard",
+        preset: DifficultyPreset::Hard,
+        mutators: &[],
+        restrictions: &[],
+        goal: Goal::FinishWaves(3),
+        reward: 100,
+    },
+    Challenge {
+        id: "nightmare",
+        description: "Survive 90s on nightmare",
+        preset: DifficultyPreset::Nightmare,
+        mutators: &[],
+        restrictions: &[],
+        goal: Goal::Survive(90.0),
+        reward: 120,
+    },
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChallengeOutcome {
+    Completed,
+    Failed,
+}
+
+// The challenge being played, if any, and how it is going
+#[derive(Default)]
+pub struct ChallengeProgress {
+    // Index into CHALLENGES
+    pub selected: Option<usize>,
+    pub outcome: Option<ChallengeOutcome>,
+    // The player and where they were last frame, for the movement restrictions
+    last_position: Option<(Entity, Vec3)>,
+}
+
+impl ChallengeProgress {
+    pub fn challenge(&self) -> Option<&'static Challenge> {
+        self.selected.map(|index| &CHALLENGES[index])
+    }
+}
+
+// Going back to the main menu means normal runs again
+fn clear_challenge(mut progress: ResMut<ChallengeProgress>) {
+    *progress = ChallengeProgress::default();
+}
+
+fn check_challenge(
+    mut progress: ResMut<ChallengeProgress>,
+    mut profile: ResMut<Profile>,
+    mut app_state: ResMut<State<AppState>>,
+    run_stats: Res<RunStats>,
+    player_query: Query<(Entity, &Controls, &Transform), (With<Player>, Without<Dying>)>,
+) {
+    let challenge = match progress.challenge() {
+        Some(challenge) if progress.outcome.is_none() => challenge,
+        _ => return,
+    };
+
+    // A respawned player is a new entity, so it starts over from where it is.
+    // Only the primary player is held to the movement restrictions.
+    let mut moved = Vec3::ZERO;
+    let primary = player_query
+        .iter()
+        .find(|(_entity, controls, _transform)| **controls == Controls::Primary);
+    if let Some((entity, _controls, transform)) = primary {
+        if let Some((last_entity, last_position)) = progress.last_position {
+            if last_entity == entity {
+                moved = transform.translation - last_position;
+            }
+        }
+        progress.last_position = Some((entity, transform.translation));
+    }
+
+    let broken = challenge
+        .restrictions
+        .iter()
+        .any(|restriction| match restriction {
+            Restriction::NoVerticalMovement => moved.y != 0.0,
+            Restriction::NoHorizontalMovement => moved.x != 0.0,
+            Restriction::MaxTeleports(max) => run_stats.teleports > *max,
+        });
+
+    let (reached, out_of_time) = match challenge.goal {
+        Goal::Survive(seconds) => (run_stats.survival_seconds >= seconds, false),
+        Goal::DestroyBlocks { count, seconds } => (
+            run_stats.blocks_destroyed >= count,
+            run_stats.survival_seconds > seconds,
+        ),
+        Goal::FinishWaves(waves) => (run_stats.waves_finished >= waves, false),
+    };
+
+    if broken || (out_of_time && !reached) {
+        progress.outcome = Some(ChallengeOutcome::Failed);
+        let _ = app_state.set(AppState::GameOver);
+    } else if reached {
+        progress.outcome = Some(ChallengeOutcome::Completed);
+
+        if !profile
+            .completed_challenges
+            .iter()
+            .any(|id| id == challenge.id)
+        {
+            profile.completed_challenges.push(challenge.id.to_string());
+            profile.coins += challenge.reward;
+            profile.save();
+        }
+
+        let _ = app_state.set(AppState::GameOver);
+    }
+}
+
+fn fail_challenge_on_death(
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    mut progress: ResMut<ChallengeProgress>,
+) {
+    for _event in player_death_event.iter() {
+        if progress.selected.is_some() && progress.outcome.is_none() {
+            progress.outcome = Some(ChallengeOutcome::Failed);
+        }
+    }
+}
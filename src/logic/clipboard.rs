@@ -0,0 +1,67 @@
+// CLIPBOARD CODE
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Bevy doesn't have clipboard access, so this goes through whatever the
+// system has for it. The first tool that works is used.
+#[cfg(target_os = "windows")]
+const COPY_COMMANDS: &[&[&str]] = &[&["clip"]];
+#[cfg(target_os = "windows")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]];
+
+#[cfg(target_os = "macos")]
+const COPY_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(target_os = "macos")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["pbpaste"]];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const COPY_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PASTE_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+// Returns whether it made it onto the clipboard
+pub fn copy(text: &str) -> bool {
+    COPY_COMMANDS.iter().any(|command| {
+        let child = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        let mut child = match child {
+            Ok(child) => child,
+            Err(_) => return false,
+        };
+
+        let written = child
+            .stdin
+            .take()
+            .map_or(false, |mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        child.wait().map_or(false, |status| status.success()) && written
+    })
+}
+
+pub fn paste() -> Option<String> {
+    PASTE_COMMANDS.iter().find_map(|command| {
+        let output = Command::new(command[0])
+            .args(&command[1..])
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    })
+}
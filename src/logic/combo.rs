@@ -0,0 +1,96 @@
+// COMBO CODE
+
+use bevy::prelude::*;
+
+use super::blocks::BlockDestroyedEvent;
+use super::game_time::GameTime;
+use super::player::PlayerHitEvent;
+use super::reset_game::Resettable;
+use super::stats::NearMissEvent;
+use crate::graphics::score::AWARD_POINTS;
+use crate::AppState;
+
+// Seconds there are to keep the combo going after each graze or block
+const COMBO_WINDOW: f32 = 3.0;
+// The multiplier goes up by one every this many in a row
+const LINKS_PER_STEP: u32 = 4;
+const MAX_MULTIPLIER: u32 = 8;
+
+pub struct ComboPlugin;
+
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Combo>()
+            .reset_on_restart::<Combo>()
+            .add_system_set(
+                // Points earned this frame get this frame's multiplier
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(update_combo.system().before(AWARD_POINTS)),
+            );
+    }
+}
+
+// Grazes and destroyed blocks in a row, each one has to come before the
+// window runs out. Every point earned is multiplied while it lasts,
+// surviving included.
+pub struct Combo {
+    pub count: u32,
+    window: Timer,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Combo {
+            count: 0,
+            window: Timer::from_seconds(COMBO_WINDOW, false),
+        }
+    }
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.count / LINKS_PER_STEP).min(MAX_MULTIPLIER)
+    }
+
+    // How much of the window is left, from one down to zero
+    pub fn time_left(&self) -> f32 {
+        if self.count == 0 {
+            0.0
+        } else {
+            1.0 - self.window.percent()
+        }
+    }
+
+    fn add_link(&mut self) {
+        self.count += 1;
+        self.window.reset();
+    }
+}
+
+// Getting hit loses the combo straight away, even with lives to spare
+fn update_combo(
+    mut combo: ResMut<Combo>,
+    mut near_miss_event: EventReader<NearMissEvent>,
+    mut block_destroyed_event: EventReader<BlockDestroyedEvent>,
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    time: Res<GameTime>,
+) {
+    if combo.count > 0 && combo.window.tick(time.delta()).finished() {
+        combo.count = 0;
+    }
+
+    for _event in near_miss_event.iter() {
+        combo.add_link();
+    }
+
+    // Only blocks a player destroyed, not ones that crashed into each other
+    for event in block_destroyed_event.iter() {
+        if event.destroyed_by.is_some() {
+            combo.add_link();
+        }
+    }
+
+    if player_hit_event.iter().next().is_some() {
+        *combo = Combo::default();
+    }
+}
@@ -0,0 +1,149 @@
+// CO-OP CODE
+
+use bevy::prelude::*;
+
+use super::gamepad::ConnectedGamepads;
+use super::lives::{Invulnerable, Lives, Respawn};
+use super::player::{spawn_player, Controls, Dying, Player, PlayerId};
+use super::rules::GameRules;
+use crate::settings::GameSettings;
+use crate::AppState;
+
+pub struct CoopPlugin;
+
+impl Plugin for CoopPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(join_on_start.system())
+                .with_system(leave_on_select.system())
+                .with_system(push_players_apart.system()),
+        );
+    }
+}
+
+// Pressing Start on a gamepad that isn't playing yet, or U for the second
+// keyboard player, drops a new player into the run with the shared lives,
+// or lives of their own if the rules say so. A player that is out of
+// lives stays out until the next run.
+#[allow(clippy::too_many_arguments)]
+fn join_on_start(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<ConnectedGamepads>,
+    respawn: Res<Respawn>,
+    lives: Res<Lives>,
+    player_query: Query<&Controls, With<Player>>,
+) {
+    // Nobody can join once the run is over
+    if player_query.iter().count() == 0 && respawn.pending.is_empty() {
+        return;
+    }
+
+    let already_in = |controls: Controls| {
+        player_query.iter().any(|player| *player == controls)
+            || respawn
+                .pending
+                .iter()
+                .any(|pending| pending.controls == controls)
+            || lives.left(PlayerId::from(controls)) == 0
+    };
+
+    let joining = gamepads
+        .0
+        .iter()
+        .filter(|gamepad| {
+            gamepad_buttons.just_pressed(GamepadButton(**gamepad, GamepadButtonType::Start))
+        })
+        .map(|gamepad| Controls::Gamepad(*gamepad))
+        .chain(Some(Controls::SecondKeyboard).filter(|_| keyboard_input.just_pressed(KeyCode::U)))
+        .filter(|controls| !already_in(*controls))
+        .collect::<Vec<Controls>>();
+
+    for controls in joining {
+        let player = spawn_player(
+            &mut commands,
+            &asset_server,
+            &mut materials,
+            &settings,
+            controls,
+        );
+        commands.entity(player).insert(Invulnerable::after_spawn());
+    }
+}
+
+// Pressing Select, or pulling the gamepad out, takes that player out of
+// the run without ending it. The second keyboard player leaves with P.
+fn leave_on_select(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<ConnectedGamepads>,
+    mut respawn: ResMut<Respawn>,
+    player_query: Query<(Entity, &Controls), With<Player>>,
+) {
+    let leaving = |controls: &Controls| match controls {
+        Controls::Primary | Controls::Bot => false,
+        Controls::SecondKeyboard => keyboard_input.just_pressed(KeyCode::P),
+        Controls::Gamepad(gamepad) => {
+            !gamepads.0.contains(gamepad)
+                || gamepad_buttons.just_pressed(GamepadButton(*gamepad, GamepadButtonType::Select))
+        }
+    };
+
+    for (entity, controls) in player_query.iter() {
+        if leaving(controls) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    respawn
+        .pending
+        .retain(|pending| !leaving(&pending.controls));
+}
+
+// With player collision on, overlapping players are pushed
+// apart evenly along whichever axis they overlap the least
+fn push_players_apart(
+    rules: Res<GameRules>,
+    mut player_query: Query<(Entity, &Sprite, &mut Transform), (With<Player>, Without<Dying>)>,
+) {
+    if !rules.player_collision {
+        return;
+    }
+
+    let players: Vec<(Entity, Vec2, Vec3)> = player_query
+        .iter_mut()
+        .map(|(entity, sprite, transform)| (entity, sprite.size, transform.translation))
+        .collect();
+
+    for (index, (entity_a, size_a, position_a)) in players.iter().enumerate() {
+        for (entity_b, size_b, position_b) in players.iter().skip(index + 1) {
+            let offset = *position_b - *position_a;
+            let overlap_x = (size_a.x + size_b.x) / 2.0 - offset.x.abs();
+            let overlap_y = (size_a.y + size_b.y) / 2.0 - offset.y.abs();
+
+            if overlap_x <= 0.0 || overlap_y <= 0.0 {
+                continue;
+            }
+
+            let push = if overlap_x < overlap_y {
+                Vec3::new(overlap_x / 2.0 * offset.x.signum(), 0.0, 0.0)
+            } else {
+                Vec3::new(0.0, overlap_y / 2.0 * offset.y.signum(), 0.0)
+            };
+
+            if let Ok((_entity, _sprite, mut transform)) = player_query.get_mut(*entity_a) {
+                transform.translation -= push;
+            }
+
+            if let Ok((_entity, _sprite, mut transform)) = player_query.get_mut(*entity_b) {
+                transform.translation += push;
+            }
+        }
+    }
+}
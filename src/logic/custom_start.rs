@@ -0,0 +1,167 @@
+// CUSTOM START CODE
+
+use bevy::prelude::*;
+use rand::seq::IteratorRandom;
+
+use super::blocks::SpawnBlockEvent;
+use super::game_rng::GameRng;
+use super::player::{Controls, Player};
+use super::powerups::{PowerUp, PowerUpPickedEvent};
+use super::reset_game::Resettable;
+use super::rules::{DifficultyPreset, GameRules};
+use super::spawning::SpawnInfo;
+use super::waves::WaveManager;
+use crate::settings::GameSettings;
+use crate::AppState;
+
+// Debug builds only. Added after every other plugin so its reset
+// comes after the waves and the rest have been reset.
+pub struct CustomStartPlugin;
+
+impl Plugin for CustomStartPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.reset_with(|custom_start: &mut CustomStart| custom_start.pending = true)
+            .add_startup_system(apply_custom_difficulty.system())
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(apply_custom_start.system()),
+            );
+    }
+}
+
+// For testing late game situations without playing up to them.
+// Set from the command line, every run starts this way while it is there.
+pub struct CustomStart {
+    pub wave: Option<u32>,
+    pub preset: Option<DifficultyPreset>,
+    pub powerups: Vec<PowerUp>,
+    pub blocks: usize,
+    // Set by the reset, the run is changed the frame after
+    pending: bool,
+}
+
+impl CustomStart {
+    // --start-wave 5 --difficulty hard --powerup shield --powerup bomb --blocks 10,
+    // None when none of them were given
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let value = |flag: &str| {
+            args.iter()
+                .position(|arg| arg == flag)
+                .and_then(|index| args.get(index + 1))
+        };
+
+        let wave = value("--start-wave").and_then(|wave| match wave.parse::<u32>() {
+            Ok(wave) if wave >= 1 => Some(wave),
+            _ => {
+                println!("--start-wave needs a wave number, starting at wave 1");
+                None
+            }
+        });
+
+        let preset = value("--difficulty").and_then(|name| {
+            let preset = DifficultyPreset::ALL
+                .iter()
+                .copied()
+                .find(|preset| preset.to_string().eq_ignore_ascii_case(name));
+            if preset.is_none() {
+                println!("unknown difficulty {}, starting on normal", name);
+            }
+            preset
+        });
+
+        let powerups = args
+            .windows(2)
+            .filter(|pair| pair[0] == "--powerup")
+            .filter_map(|pair| match pair[1].to_lowercase().as_str() {
+                "shield" => Some(PowerUp::Shield),
+                "slowmotion" | "slow_motion" => Some(PowerUp::SlowMotion),
+                "bomb" => Some(PowerUp::Bomb),
+                name => {
+                    println!("unknown power up {}", name);
+                    None
+                }
+            })
+            .collect::<Vec<PowerUp>>();
+
+        let blocks = value("--blocks").map_or(0, |blocks| {
+            blocks.parse().unwrap_or_else(|_| {
+                println!("--blocks needs a number, starting without any");
+                0
+            })
+        });
+
+        if wave.is_none() && preset.is_none() && powerups.is_empty() && blocks == 0 {
+            return None;
+        }
+
+        Some(CustomStart {
+            wave,
+            preset,
+            powerups,
+            blocks,
+            pending: false,
+        })
+    }
+}
+
+// Picked before the first run so its lives come from the preset,
+// the difficulty menu can still change it afterwards
+fn apply_custom_difficulty(
+    custom_start: Option<Res<CustomStart>>,
+    settings: Res<GameSettings>,
+    mut rules: ResMut<GameRules>,
+) {
+    if let Some(preset) = custom_start.and_then(|custom_start| custom_start.preset) {
+        *rules = GameRules::from_preset(preset, &settings);
+    }
+}
+
+// Runs once the player from the reset is in the world.
+//
+// NOTE
+// There is no power up inventory, so they are picked up the moment the run
+// starts. A bomb goes off right away, before the pre-spawned blocks come in.
+fn apply_custom_start(
+    custom_start: Option<ResMut<CustomStart>>,
+    mut waves: ResMut<WaveManager>,
+    mut game_rng: ResMut<GameRng>,
+    player_query: Query<(Entity, &Controls), With<Player>>,
+    spawn_positions_query: Query<(Entity, &SpawnInfo)>,
+    mut powerup_picked_event: EventWriter<PowerUpPickedEvent>,
+    mut spawn_event: EventWriter<SpawnBlockEvent>,
+) {
+    let mut custom_start = match custom_start {
+        Some(custom_start) if custom_start.pending => custom_start,
+        _ => return,
+    };
+
+    let player = match player_query
+        .iter()
+        .find(|(_entity, controls)| **controls == Controls::Primary)
+    {
+        Some((entity, _controls)) => entity,
+        None => return,
+    };
+
+    custom_start.pending = false;
+
+    if let Some(wave) = custom_start.wave {
+        waves.wave = wave;
+    }
+
+    for powerup in custom_start.powerups.iter() {
+        powerup_picked_event.send(PowerUpPickedEvent {
+            player,
+            powerup: *powerup,
+        });
+    }
+
+    let free_positions = spawn_positions_query
+        .iter()
+        .filter(|(_entity, spawn_position)| !spawn_position.spawned)
+        .map(|(entity, _spawn_position)| entity)
+        .choose_multiple(&mut *game_rng, custom_start.blocks);
+
+    for entity in free_positions {
+        spawn_event.send(SpawnBlockEvent(entity));
+    }
+}
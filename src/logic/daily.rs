@@ -0,0 +1,93 @@
+// DAILY CHALLENGE CODE
+
+use bevy::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::game_rng::GameRng;
+use super::mutators::ActiveMutators;
+use super::rules::{DifficultyPreset, GameRules, PlayerSetup};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<DailyChallenge>().add_system_set(
+            SystemSet::on_enter(AppState::MainMenu).with_system(clear_daily.system()),
+        );
+    }
+}
+
+// One seeded run a day on fixed rules, so everyone playing it on the
+// same day faces the same blocks. Restarting replays the same day.
+// Unlocks from the profile and upgrade cards are off for it too, so
+// everyone's run is the same and not just the blocks.
+#[derive(Default)]
+pub struct DailyChallenge {
+    // Days since 1970-01-01 in UTC, while a daily run is being played
+    pub day: Option<u64>,
+}
+
+impl DailyChallenge {
+    pub fn is_active(&self) -> bool {
+        self.day.is_some()
+    }
+
+    // Normal difficulty with no mutators, whatever was picked in the menus.
+    // That is put aside until the main menu.
+    pub fn start(
+        &mut self,
+        game_rng: &mut GameRng,
+        rules: &mut GameRules,
+        mutators: &mut ActiveMutators,
+        setup: &mut PlayerSetup,
+        settings: &GameSettings,
+    ) {
+        setup.set_aside(rules, mutators);
+        let day = today();
+        self.day = Some(day);
+        game_rng.set_fixed_seed(Some(daily_seed(day)));
+        *rules = GameRules::from_preset(DifficultyPreset::Normal, settings);
+        mutators.0.clear();
+    }
+}
+
+// The current day in UTC, a clock set before 1970 is just day zero
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / SECONDS_PER_DAY)
+}
+
+// The day itself is the seed, the rng spreads it out
+pub fn daily_seed(day: u64) -> u64 {
+    day
+}
+
+// The day as YYYY-MM-DD, from Howard Hinnant's civil_from_days
+pub fn date_string(day: u64) -> String {
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!("{}-{:02}-{:02}", year, month, day_of_month)
+}
+
+// Going back to the main menu means normal runs again
+fn clear_daily(mut daily: ResMut<DailyChallenge>) {
+    daily.day = None;
+}
@@ -0,0 +1,64 @@
+// DIFFICULTY CODE
+
+use bevy::prelude::*;
+
+use super::reset_game::Resettable;
+use super::stats::RunStats;
+use crate::settings::GameSettings;
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Difficulty>()
+            .reset_with(|difficulty: &mut Difficulty| difficulty.rate = difficulty.initial_rate)
+            .add_system(ramp_difficulty.system());
+    }
+}
+
+// Ramps block speed and spawn frequency up the longer a run goes on,
+// so a long run gets harder instead of just more crowded
+pub struct Difficulty {
+    // The rate a run starts at
+    pub initial_rate: f32,
+    // The rate the ramp tops out at
+    pub max_rate: f32,
+    // Seconds of survival it takes to go from the initial to the max rate
+    pub ramp_seconds: f32,
+    rate: f32,
+}
+
+// The ramp comes from the settings so it can be tuned without a rebuild
+impl FromWorld for Difficulty {
+    fn from_world(world: &mut World) -> Self {
+        let settings = world
+            .get_resource::<GameSettings>()
+            .expect("The settings are inserted before the difficulty.");
+        Difficulty {
+            initial_rate: settings.difficulty_initial_rate,
+            max_rate: settings.difficulty_max_rate,
+            ramp_seconds: settings.difficulty_ramp_seconds,
+            rate: settings.difficulty_initial_rate,
+        }
+    }
+}
+
+impl Difficulty {
+    // Multiplies the block speed and divides the spawn interval
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    fn rate_at(&self, survival_seconds: f32) -> f32 {
+        let progress = (survival_seconds / self.ramp_seconds.max(f32::EPSILON)).clamp(0.0, 1.0);
+        self.initial_rate + (self.max_rate - self.initial_rate) * progress
+    }
+}
+
+fn ramp_difficulty(mut difficulty: ResMut<Difficulty>, run_stats: Res<RunStats>) {
+    if !run_stats.is_changed() {
+        return;
+    }
+
+    difficulty.rate = difficulty.rate_at(run_stats.survival_seconds);
+}
@@ -0,0 +1,126 @@
+// FACTION CODE
+
+use bevy::prelude::*;
+
+use super::player::MOVE_PLAYER;
+use super::spatial_grid::{collide_wrapped, SpatialGrid, BUILD_SPATIAL_GRID};
+use crate::{AppState, Collidable, Direction};
+
+// Label for the system that finds everything hitting something,
+// anything reacting to a CollisionEvent the same frame goes after it
+pub const DETECT_COLLISIONS: &str = "detect_collisions";
+
+pub struct FactionPlugin;
+
+impl Plugin for FactionPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<CollisionEvent>()
+            .init_resource::<CollisionRules>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(
+                    detect_collisions
+                        .system()
+                        .label(DETECT_COLLISIONS)
+                        .after(MOVE_PLAYER)
+                        .after(BUILD_SPATIAL_GRID),
+                ),
+            );
+    }
+}
+
+// Which side something is on, the collision rules decide who hurts who
+#[derive(Clone, Copy, PartialEq)]
+pub enum Faction {
+    // The players and their bullets
+    Player,
+    // Blocks, turrets and their bullets
+    Hostile,
+    // Hits nothing and nothing hits it, like pickups
+    Neutral,
+}
+
+// How much something hurts what it hits. Without it something can
+// still be hit but never hits anything, like the player's own body.
+pub struct Damage(pub u32);
+
+// Who damages who. Anything not in the table passes straight through,
+// so hostile bullets fly through blocks and blocks through turrets.
+pub struct CollisionRules {
+    // Attacker faction, then target faction
+    pub rules: Vec<(Faction, Faction)>,
+}
+
+impl Default for CollisionRules {
+    fn default() -> Self {
+        CollisionRules {
+            rules: vec![
+                (Faction::Hostile, Faction::Player),
+                (Faction::Player, Faction::Hostile),
+            ],
+        }
+    }
+}
+
+impl CollisionRules {
+    pub fn damages(&self, attacker: Faction, target: Faction) -> bool {
+        self.rules.contains(&(attacker, target))
+    }
+}
+
+// Sent when something that does damage touches something its faction hurts.
+// Detection only finds these, each kind of target reacts in its own system.
+pub struct CollisionEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    // The side of the target that was hit
+    pub side: Direction,
+    pub damage: u32,
+}
+
+// Everything that does damage against everything nearby in the spatial grid
+// on the layers it checks
+#[allow(clippy::type_complexity)]
+fn detect_collisions(
+    rules: Res<CollisionRules>,
+    grid: Res<SpatialGrid>,
+    attacker_query: Query<(Entity, &Faction, &Damage, &Collidable, &Transform, &Sprite)>,
+    target_query: Query<(&Faction, &Transform, &Sprite)>,
+    mut collision_event: EventWriter<CollisionEvent>,
+) {
+    for (attacker, attacker_faction, damage, collidable, attacker_transform, attacker_sprite) in
+        attacker_query.iter()
+    {
+        let nearby = grid.nearby(
+            attacker_transform.translation.truncate(),
+            attacker_sprite.size,
+            collidable.mask,
+        );
+
+        for target in nearby {
+            let (target_faction, target_transform, target_sprite) = match target_query.get(target) {
+                Ok(target) => target,
+                Err(_) => continue,
+            };
+
+            if target == attacker || !rules.damages(*attacker_faction, *target_faction) {
+                continue;
+            }
+
+            let collision = collide_wrapped(
+                attacker_transform.translation,
+                attacker_sprite.size,
+                target_transform.translation,
+                target_sprite.size,
+            );
+
+            if let Some(collision) = collision {
+                collision_event.send(CollisionEvent {
+                    attacker,
+                    target,
+                    side: Direction::hit_side(collision),
+                    damage: damage.0,
+                });
+            }
+        }
+    }
+}
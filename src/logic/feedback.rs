@@ -0,0 +1,121 @@
+// FEEDBACK CODE
+
+use bevy::diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin};
+use std::fs;
+use std::io::Write;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::profile::save_directory;
+use super::stats::RunStats;
+use crate::settings::GameSettings;
+
+const FEEDBACK_DIRECTORY: &str = "feedback";
+
+// Details about the game and this machine sent along with every report,
+// so a report can be looked into without asking for them
+pub fn diagnostic_bundle(
+    settings: &GameSettings,
+    run_stats: &RunStats,
+    diagnostics: &Diagnostics,
+) -> String {
+    let fps = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.average())
+        .unwrap_or(0.0);
+
+    let lines = [
+        format!("Version: {}", env!("CARGO_PKG_VERSION")),
+        format!(
+            "Platform: {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ),
+        format!(
+            "Window: {}x{}, vsync {}",
+            settings.window_width, settings.window_height, settings.vsync
+        ),
+        format!("FPS: {:.0}", fps),
+        format!("Block bounce: {}", settings.block_bounce),
+        format!("Seed: {:?}", settings.seed),
+        format!(
+            "Last run: {:.1}s, {} mutators",
+            run_stats.survival_seconds,
+            run_stats.mutators.len()
+        ),
+    ];
+
+    lines.join("\n")
+}
+
+// Writes the report into its own file in the save directory,
+// returns where it went
+pub fn save_report(report: &str) -> Option<PathBuf> {
+    let directory = save_directory().join(FEEDBACK_DIRECTORY);
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let path = directory.join(format!("feedback_{}.txt", seconds));
+
+    match fs::create_dir_all(&directory).and_then(|_| fs::write(&path, report)) {
+        Ok(_) => Some(path),
+        Err(error) => {
+            println!("Could not save the feedback: {}", error);
+            None
+        }
+    }
+}
+
+// Sends the report off in the background so a slow server can't freeze
+// the game. It is already saved, so a failed send only gets printed.
+//
+// NOTE
+// Only plain 'http://host:port/path' endpoints, https would need a TLS crate.
+pub fn post_report(endpoint: &str, report: String) {
+    let (host, path) = match parse_endpoint(endpoint) {
+        Some(parts) => parts,
+        None => {
+            println!("The feedback endpoint has to be http://host:port/path");
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            path,
+            host,
+            report.len(),
+            report
+        );
+
+        if let Err(error) =
+            TcpStream::connect(&host).and_then(|mut stream| stream.write_all(request.as_bytes()))
+        {
+            println!("Could not send the feedback: {}", error);
+        }
+    });
+}
+
+// Splits out the 'host:port' to connect to and the path to post to
+fn parse_endpoint(endpoint: &str) -> Option<(String, String)> {
+    let rest = endpoint.strip_prefix("http://")?;
+    let (host, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+
+    if host.is_empty() {
+        return None;
+    }
+
+    let host = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+    Some((host, path.to_string()))
+}
@@ -0,0 +1,101 @@
+// GAME RNG CODE
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Error, Rng, RngCore, SeedableRng};
+
+use super::reset_game::Resettable;
+use super::spawning::reroll_spawn_directions;
+use crate::settings::GameSettings;
+use crate::AppState;
+
+pub struct GameRngPlugin;
+
+impl Plugin for GameRngPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<GameRng>()
+            .reset_world(reset_game_rng)
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(restore_fixed_seed.system()),
+            );
+    }
+}
+
+// The randomness that decides what spawns where. Every run starts from the
+// seed again, so a fixed seed always plays out the same spawn sequence.
+// Without one every run gets a fresh seed.
+pub struct GameRng {
+    fixed_seed: Option<u64>,
+    seed: u64,
+    rng: StdRng,
+}
+
+impl FromWorld for GameRng {
+    fn from_world(world: &mut World) -> Self {
+        let fixed_seed = world
+            .get_resource::<GameSettings>()
+            .and_then(|settings| settings.seed);
+        let seed = fixed_seed.unwrap_or_else(|| thread_rng().gen());
+
+        GameRng {
+            fixed_seed,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl GameRng {
+    // The seed the current run was started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // The seed every run is started from, None when each run gets its own
+    pub fn fixed_seed(&self) -> Option<u64> {
+        self.fixed_seed
+    }
+
+    // Takes effect from the next run
+    pub fn set_fixed_seed(&mut self, fixed_seed: Option<u64>) {
+        self.fixed_seed = fixed_seed;
+    }
+
+    fn reseed(&mut self) {
+        self.seed = self.fixed_seed.unwrap_or_else(|| thread_rng().gen());
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+// The lanes are rolled again from the new seed as well, so a seed set after
+// launch plays out the same as it would from --seed
+fn reset_game_rng(world: &mut World) {
+    world.resource_scope(|world, mut game_rng: Mut<GameRng>| {
+        game_rng.reseed();
+        reroll_spawn_directions(world, &mut game_rng);
+    });
+}
+
+// The daily, the weekly and shared seed codes only last until the main
+// menu, then runs are unseeded again unless --seed was given
+fn restore_fixed_seed(mut game_rng: ResMut<GameRng>, settings: Res<GameSettings>) {
+    game_rng.set_fixed_seed(settings.seed);
+}
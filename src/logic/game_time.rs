@@ -0,0 +1,72 @@
+// GAME TIME CODE
+
+use bevy::core::CoreSystem;
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub struct GameTimePlugin;
+
+impl Plugin for GameTimePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<GameTime>().add_system_to_stage(
+            CoreStage::First,
+            follow_time.system().after(CoreSystem::Time),
+        );
+    }
+}
+
+// The clock everything in the game runs on. Normally it just follows Bevy's
+// Time, but Time can only be moved on from the real clock. A headless app
+// steps this by hand instead so a run doesn't take as long as it plays for,
+// see headless::advance.
+pub struct GameTime {
+    delta: Duration,
+    seconds_since_startup: f64,
+    stepped: bool,
+}
+
+impl Default for GameTime {
+    fn default() -> Self {
+        GameTime {
+            delta: Duration::default(),
+            seconds_since_startup: 0.0,
+            stepped: false,
+        }
+    }
+}
+
+impl GameTime {
+    // A clock that leaves Time alone and only moves on through step
+    pub fn stepped() -> Self {
+        GameTime {
+            stepped: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn step(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.seconds_since_startup += delta.as_secs_f64();
+    }
+
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn seconds_since_startup(&self) -> f64 {
+        self.seconds_since_startup
+    }
+}
+
+fn follow_time(time: Res<Time>, mut game_time: ResMut<GameTime>) {
+    if game_time.stepped {
+        return;
+    }
+
+    game_time.delta = time.delta();
+    game_time.seconds_since_startup = time.seconds_since_startup();
+}
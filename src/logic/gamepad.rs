@@ -0,0 +1,122 @@
+// GAMEPAD CODE
+
+use bevy::prelude::*;
+use std::fmt;
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<ConnectedGamepads>()
+            .init_resource::<StickSettings>()
+            .add_system(track_gamepads.system());
+    }
+}
+
+// Every controller that is plugged in, oldest first.
+// The oldest one is the one that plays.
+#[derive(Default)]
+pub struct ConnectedGamepads(pub Vec<Gamepad>);
+
+impl ConnectedGamepads {
+    pub fn active(&self) -> Option<Gamepad> {
+        self.0.first().copied()
+    }
+}
+
+// How the left stick turns into movement, tuned from the main menu
+pub struct StickSettings {
+    // How far the stick has to move before it counts. Worn sticks
+    // rest slightly off center and would make the player drift.
+    pub inner_deadzone: f32,
+    // Past this the stick counts as all the way over. Some sticks
+    // never quite reach the edge.
+    pub outer_deadzone: f32,
+    pub curve: StickCurve,
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
+        StickSettings {
+            inner_deadzone: 0.2,
+            outer_deadzone: 0.95,
+            curve: StickCurve::Linear,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum StickCurve {
+    Linear,
+    // Small movements are finer, the full speed is still at the edge
+    Squared,
+}
+
+impl StickCurve {
+    pub fn next(self) -> Self {
+        match self {
+            StickCurve::Linear => StickCurve::Squared,
+            StickCurve::Squared => StickCurve::Linear,
+        }
+    }
+}
+
+impl fmt::Display for StickCurve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StickCurve::Linear => write!(f, "Linear"),
+            StickCurve::Squared => write!(f, "Squared"),
+        }
+    }
+}
+
+// Controllers can be plugged in and pulled out at any time
+fn track_gamepads(
+    mut gamepad_event: EventReader<GamepadEvent>,
+    mut gamepads: ResMut<ConnectedGamepads>,
+) {
+    for GamepadEvent(gamepad, event_type) in gamepad_event.iter() {
+        match event_type {
+            GamepadEventType::Connected => {
+                gamepads.0.push(*gamepad);
+            }
+            GamepadEventType::Disconnected => {
+                gamepads.0.retain(|connected| connected != gamepad);
+            }
+            _ => (),
+        }
+    }
+}
+
+// The left stick exactly as the controller reports it
+pub fn raw_left_stick(axes: &Axis<GamepadAxis>, gamepad: Gamepad) -> Vec2 {
+    let x = axes
+        .get(GamepadAxis(gamepad, GamepadAxisType::LeftStickX))
+        .unwrap_or(0.0);
+    let y = axes
+        .get(GamepadAxis(gamepad, GamepadAxisType::LeftStickY))
+        .unwrap_or(0.0);
+    Vec2::new(x, y)
+}
+
+// The left stick with the deadzones cut out and the curve applied
+pub fn left_stick(axes: &Axis<GamepadAxis>, gamepad: Gamepad, settings: &StickSettings) -> Vec2 {
+    apply_stick_settings(raw_left_stick(axes, gamepad), settings)
+}
+
+// Rescaled so movement still starts from zero at the edge of the
+// inner deadzone and reaches full speed at the outer one
+pub fn apply_stick_settings(stick: Vec2, settings: &StickSettings) -> Vec2 {
+    let length = stick.length();
+    if length < settings.inner_deadzone {
+        return Vec2::ZERO;
+    }
+
+    let range = (settings.outer_deadzone - settings.inner_deadzone).max(f32::EPSILON);
+    let scaled_length = ((length - settings.inner_deadzone) / range).min(1.0);
+    let curved_length = match settings.curve {
+        StickCurve::Linear => scaled_length,
+        StickCurve::Squared => scaled_length * scaled_length,
+    };
+    stick / length * curved_length
+}
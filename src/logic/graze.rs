@@ -0,0 +1,41 @@
+// GRAZE CODE
+
+use bevy::prelude::*;
+
+use super::combo::Combo;
+use super::stats::NearMissEvent;
+use crate::graphics::score::{Score, ScoreEvent, AWARD_POINTS};
+use crate::AppState;
+
+const GRAZE_POINTS: u32 = 25;
+
+pub struct GrazePlugin;
+
+impl Plugin for GrazePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(reward_grazes.system().label(AWARD_POINTS)),
+        );
+    }
+}
+
+// Every near miss is a graze and is worth points. The player's near miss
+// sensor decides what counts, so the stats, the camera shake and the
+// combo all agree with the score.
+fn reward_grazes(
+    score: Res<Score>,
+    combo: Res<Combo>,
+    mut near_miss_event: EventReader<NearMissEvent>,
+    mut score_event: EventWriter<ScoreEvent>,
+) {
+    for event in near_miss_event.iter() {
+        if score.active {
+            score_event.send(ScoreEvent {
+                points: GRAZE_POINTS * combo.multiplier(),
+                position: event.position,
+                player: Some(event.player),
+            });
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// HEALTH CODE
+
+use bevy::prelude::*;
+
+use super::blocks::Block;
+use super::reset_game::RunScoped;
+use crate::AppState;
+
+// How see-through the cracks are on the first hit, they get
+// darker with every hit after
+const MIN_CRACK_ALPHA: f32 = 0.5;
+// Worse cracks left to right, one frame per stage of damage
+const CRACK_SPRITES: &str = "sprites/block_cracks.png";
+const CRACK_FRAME_SIZE: f32 = 40.0;
+const CRACK_FRAMES: usize = 3;
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(crack_damaged.system())
+                .with_system(update_cracks.system()),
+        );
+    }
+}
+
+// Hits something can take before it is destroyed
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Health { current: max, max }
+    }
+
+    // True once there is no health left
+    pub fn damage(&mut self, amount: u32) -> bool {
+        self.current = self.current.saturating_sub(amount);
+        self.current == 0
+    }
+
+    // How hurt it is, 0 untouched to 1 dead
+    pub fn damage_fraction(&self) -> f32 {
+        1.0 - self.current as f32 / self.max.max(1) as f32
+    }
+}
+
+// On a damaged block, the overlay that shows the cracks
+struct Cracked;
+
+// Sits on top of a damaged block and follows it around.
+// Each hit cracks it further and darkens the cracks.
+struct CrackOverlay {
+    block: Entity,
+}
+
+// Which frame of the crack sprites shows this much damage
+fn crack_frame(health: &Health) -> u32 {
+    let stage = (health.damage_fraction() * CRACK_FRAMES as f32).ceil() as usize;
+    stage.clamp(1, CRACK_FRAMES) as u32 - 1
+}
+
+fn crack_color(health: &Health) -> Color {
+    let alpha = MIN_CRACK_ALPHA + (1.0 - MIN_CRACK_ALPHA) * health.damage_fraction();
+    Color::rgba(1.0, 1.0, 1.0, alpha)
+}
+
+fn crack_damaged(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    // The sheet is the same for every block, so it is only made once
+    mut crack_atlas: Local<Option<Handle<TextureAtlas>>>,
+    block_query: Query<(Entity, &Health, &Transform, &Sprite), (With<Block>, Without<Cracked>)>,
+) {
+    for (entity, health, transform, sprite) in block_query.iter() {
+        if health.current == health.max {
+            continue;
+        }
+
+        let texture_atlas = crack_atlas
+            .get_or_insert_with(|| {
+                texture_atlases.add(TextureAtlas::from_grid(
+                    asset_server.load(CRACK_SPRITES),
+                    Vec2::new(CRACK_FRAME_SIZE, CRACK_FRAME_SIZE),
+                    CRACK_FRAMES,
+                    1,
+                ))
+            })
+            .clone();
+
+        // Atlas sprites are always drawn at the frame size, so the
+        // transform scales the cracks to the block instead
+        let mut overlay_transform = *transform;
+        overlay_transform.translation.z += 0.1;
+        overlay_transform.scale *= (sprite.size / CRACK_FRAME_SIZE).extend(1.0);
+
+        commands
+            .spawn_bundle(SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    index: crack_frame(health),
+                    color: crack_color(health),
+                    ..Default::default()
+                },
+                texture_atlas,
+                transform: overlay_transform,
+                ..Default::default()
+            })
+            .insert(CrackOverlay { block: entity })
+            .insert(RunScoped);
+        commands.entity(entity).insert(Cracked);
+    }
+}
+
+// The overlay cracks further with every hit and goes away with its block
+fn update_cracks(
+    mut commands: Commands,
+    block_query: Query<(&Health, &Transform, &Sprite), With<Block>>,
+    mut overlay_query: Query<
+        (
+            Entity,
+            &CrackOverlay,
+            &mut Transform,
+            &mut TextureAtlasSprite,
+        ),
+        Without<Block>,
+    >,
+) {
+    for (entity, overlay, mut transform, mut crack_sprite) in overlay_query.iter_mut() {
+        let (health, block_transform, sprite) = match block_query.get(overlay.block) {
+            Ok(block) => block,
+            Err(_) => {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        };
+
+        transform.translation = block_transform.translation;
+        transform.translation.z += 0.1;
+        // Blocks tumble as they go, the cracks turn with them
+        transform.rotation = block_transform.rotation;
+        transform.scale = block_transform.scale * (sprite.size / CRACK_FRAME_SIZE).extend(1.0);
+
+        crack_sprite.index = crack_frame(health);
+        crack_sprite.color = crack_color(health);
+    }
+}
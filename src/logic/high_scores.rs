@@ -0,0 +1,228 @@
+// HIGH SCORES CODE
+
+use bevy::prelude::*;
+use bevy::window::ReceivedCharacter;
+use serde::{Deserialize, Serialize};
+
+use super::gamepad::ConnectedGamepads;
+use super::mutators::ActiveMutators;
+use super::player::PlayerDeathEvent;
+use super::rules::GameRules;
+use super::sealed_save::{load_sealed, save_sealed};
+use super::stats::RunStats;
+use super::upgrades::RunUpgrades;
+use crate::graphics::score::Score;
+use crate::AppState;
+
+// Sealed so it can't just be edited, see sealed_save
+const HIGH_SCORES_FILE: &str = "high_scores.sav";
+const LEGACY_HIGH_SCORES_FILE: &str = "high_scores.ron";
+const MAX_HIGH_SCORES: usize = 10;
+const INITIALS_LENGTH: usize = 3;
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(HighScores::load())
+            .init_resource::<PendingHighScore>()
+            .add_system(check_for_high_score.system())
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver).with_system(enter_initials.system()),
+            );
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub initials: String,
+    pub survival_seconds: f32,
+    // The player's own points, only kept for runs with split co-op scores
+    #[serde(default)]
+    pub points: Option<u32>,
+}
+
+// The longest runs on this machine, longest first
+#[derive(Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    // A missing or broken file starts an empty table
+    pub fn load() -> Self {
+        load_sealed(HIGH_SCORES_FILE, LEGACY_HIGH_SCORES_FILE, "high scores").unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_sealed(self, HIGH_SCORES_FILE, "high scores");
+    }
+
+    pub fn qualifies(&self, survival_seconds: f32) -> bool {
+        self.entries.len() < MAX_HIGH_SCORES
+            || self
+                .entries
+                .iter()
+                .any(|entry| survival_seconds > entry.survival_seconds)
+    }
+
+    // Players from the same split run that survived as long are ranked
+    // by their points
+    fn insert(&mut self, entry: HighScore) {
+        self.entries.push(entry);
+        self.entries.sort_by(|a, b| {
+            b.survival_seconds
+                .partial_cmp(&a.survival_seconds)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.points.cmp(&a.points))
+        });
+        self.entries.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+// An entry that made the table, with split co-op scores one per player
+pub struct PendingEntry {
+    // Who it is for, None when the whole run shares one entry
+    pub player: Option<String>,
+    pub survival_seconds: f32,
+    pub points: Option<u32>,
+}
+
+// Entries that made the table and are waiting on initials. With split
+// co-op scores the players fill theirs in one after another.
+pub struct PendingHighScore {
+    pub entries: Vec<PendingEntry>,
+    pub initials: [char; INITIALS_LENGTH],
+    // The letter being changed
+    pub cursor: usize,
+}
+
+impl Default for PendingHighScore {
+    fn default() -> Self {
+        PendingHighScore {
+            entries: Vec::new(),
+            initials: ['A'; INITIALS_LENGTH],
+            cursor: 0,
+        }
+    }
+}
+
+impl PendingHighScore {
+    pub fn is_pending(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    // The entry the initials are being entered for
+    pub fn current(&self) -> Option<&PendingEntry> {
+        self.entries.first()
+    }
+
+    // Wraps around from Z back to A
+    fn cycle_letter(&mut self, step: i8) {
+        let letter = &mut self.initials[self.cursor];
+        let index = (*letter as u8 - b'A') as i8;
+        *letter = (b'A' + (index + step).rem_euclid(26) as u8) as char;
+    }
+}
+
+// Split co-op scores give every player a shot at the table with their own
+// time and points, otherwise the run gets one entry
+#[allow(clippy::too_many_arguments)]
+fn check_for_high_score(
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    high_scores: Res<HighScores>,
+    run_stats: Res<RunStats>,
+    score: Res<Score>,
+    rules: Res<GameRules>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+    mut pending: ResMut<PendingHighScore>,
+) {
+    for _event in player_death_event.iter() {
+        let entries = if score.is_split(&rules) {
+            score
+                .players
+                .iter()
+                .map(|player| PendingEntry {
+                    player: Some(player.controls.to_string()),
+                    survival_seconds: player.survival_seconds,
+                    points: Some(score.player_total(player, &mutators, &upgrades)),
+                })
+                .collect()
+        } else {
+            vec![PendingEntry {
+                player: None,
+                survival_seconds: run_stats.survival_seconds,
+                points: None,
+            }]
+        };
+
+        *pending = PendingHighScore {
+            entries: entries
+                .into_iter()
+                .filter(|entry| high_scores.qualifies(entry.survival_seconds))
+                .collect(),
+            ..Default::default()
+        };
+    }
+}
+
+// Arcade style, up and down change the letter and left and right move between
+// them, on the arrow keys or any controller's d-pad. Enter or A saves.
+// Typing a letter also sets it and moves on, Backspace goes back.
+fn enter_initials(
+    mut received_characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<ConnectedGamepads>,
+    mut pending: ResMut<PendingHighScore>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    if !pending.is_pending() {
+        return;
+    }
+
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keyboard_input.just_pressed(key)
+            || gamepads
+                .0
+                .iter()
+                .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton(*gamepad, button)))
+    };
+
+    if pressed(KeyCode::Up, GamepadButtonType::DPadUp) {
+        pending.cycle_letter(1);
+    }
+    if pressed(KeyCode::Down, GamepadButtonType::DPadDown) {
+        pending.cycle_letter(-1);
+    }
+    if pressed(KeyCode::Right, GamepadButtonType::DPadRight) {
+        pending.cursor = (pending.cursor + 1).min(INITIALS_LENGTH - 1);
+    }
+    if pressed(KeyCode::Left, GamepadButtonType::DPadLeft)
+        || keyboard_input.just_pressed(KeyCode::Back)
+    {
+        pending.cursor = pending.cursor.saturating_sub(1);
+    }
+
+    for event in received_characters.iter() {
+        if event.char.is_ascii_alphabetic() {
+            let cursor = pending.cursor;
+            pending.initials[cursor] = event.char.to_ascii_uppercase();
+            pending.cursor = (cursor + 1).min(INITIALS_LENGTH - 1);
+        }
+    }
+
+    // The next player starts from fresh initials
+    if pressed(KeyCode::Return, GamepadButtonType::South) {
+        let entry = pending.entries.remove(0);
+        high_scores.insert(HighScore {
+            initials: pending.initials.iter().collect(),
+            survival_seconds: entry.survival_seconds,
+            points: entry.points,
+        });
+        high_scores.save();
+        pending.initials = ['A'; INITIALS_LENGTH];
+        pending.cursor = 0;
+    }
+}
@@ -0,0 +1,41 @@
+// HINT CODE
+
+use super::stats::{DeathHistory, RunStats};
+
+// Runs shorter than this don't say much about how someone plays
+const MIN_SECONDS_FOR_HINTS: f32 = 10.0;
+// The share of a run spent in corners before it counts as hugging them
+const CORNER_HUGGING_SHARE: f32 = 0.4;
+// How many deaths in a row in one zone before it counts as a pattern
+const REPEATED_DEATHS: usize = 3;
+
+// Looks at how the last run went and picks a tip for the game over
+// screen. The checks are in priority order, the first match wins.
+pub fn choose_hint(run_stats: &RunStats, death_history: &DeathHistory) -> Option<&'static str> {
+    let last_zone = death_history.zones.last();
+    let same_zone = death_history.zones.len() >= REPEATED_DEATHS
+        && death_history
+            .zones
+            .iter()
+            .rev()
+            .take(REPEATED_DEATHS)
+            .all(|zone| Some(zone) == last_zone);
+
+    if same_zone {
+        return Some("Tip: You keep dying in the same spot, watch the edge the blocks come from.");
+    }
+
+    if run_stats.survival_seconds < MIN_SECONDS_FOR_HINTS {
+        return None;
+    }
+
+    if run_stats.teleports == 0 {
+        return Some("Tip: Hold a direction and press Space to teleport out of trouble.");
+    }
+
+    if run_stats.corner_seconds > run_stats.survival_seconds * CORNER_HUGGING_SHARE {
+        return Some("Tip: Blocks wrap around the screen, corners aren't as safe as they look.");
+    }
+
+    None
+}
@@ -0,0 +1,93 @@
+// IDLE CODE
+
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+
+use super::game_time::GameTime;
+use super::gamepad::{raw_left_stick, ConnectedGamepads, StickSettings};
+use crate::AppState;
+
+pub struct IdlePlugin;
+
+impl Plugin for IdlePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<IdleSettings>()
+            .init_resource::<IdleTime>()
+            .init_resource::<AutoPaused>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::InGame).with_system(reset_idle_time.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(pause_when_idle.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Paused).with_system(clear_auto_pause.system()),
+            );
+    }
+}
+
+// Seconds without any input before the game pauses itself,
+// so someone who walked away doesn't lose all of their lives
+pub struct IdleSettings {
+    pub seconds: f32,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        IdleSettings { seconds: 30.0 }
+    }
+}
+
+// Seconds since the last input during a run
+#[derive(Default)]
+struct IdleTime(f32);
+
+// Set when the pause came from being idle instead of the player
+#[derive(Default)]
+pub struct AutoPaused(pub bool);
+
+#[allow(clippy::too_many_arguments)]
+fn pause_when_idle(
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<ConnectedGamepads>,
+    stick_settings: Res<StickSettings>,
+    idle_settings: Res<IdleSettings>,
+    time: Res<GameTime>,
+    mut idle_time: ResMut<IdleTime>,
+    mut auto_paused: ResMut<AutoPaused>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    // Holding a key or a stick counts, not just pressing
+    let stick_moved = gamepads.0.iter().any(|gamepad| {
+        raw_left_stick(&gamepad_axes, *gamepad).length() > stick_settings.inner_deadzone
+    });
+    let any_input = keyboard_input.get_pressed().next().is_some()
+        || mouse_input.get_pressed().next().is_some()
+        || mouse_motion.iter().count() > 0
+        || gamepad_buttons.get_pressed().next().is_some()
+        || stick_moved;
+
+    if any_input {
+        idle_time.0 = 0.0;
+        return;
+    }
+
+    idle_time.0 += time.delta_seconds();
+    if idle_time.0 >= idle_settings.seconds {
+        idle_time.0 = 0.0;
+        auto_paused.0 = true;
+        let _ = app_state.push(AppState::Paused);
+    }
+}
+
+fn reset_idle_time(mut idle_time: ResMut<IdleTime>) {
+    idle_time.0 = 0.0;
+}
+
+fn clear_auto_pause(mut auto_paused: ResMut<AutoPaused>) {
+    auto_paused.0 = false;
+}
@@ -0,0 +1,204 @@
+// INPUT MAP CODE
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use super::player::ActivationMode;
+use super::profile::save_directory;
+use crate::settings::GameSettings;
+
+const INPUT_MAP_FILE: &str = "input_map.ron";
+
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // The modes are kept in the settings, the keys in their own file
+        let modes = app
+            .world()
+            .get_resource::<GameSettings>()
+            .map(|settings| settings.action_modes.clone())
+            .unwrap_or_default();
+        let mut input_map = InputMap::load();
+        input_map.modes = modes;
+        app.insert_resource(input_map);
+    }
+}
+
+// The things a key can be bound to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Teleport,
+    Fire,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Teleport,
+        Action::Fire,
+        Action::Pause,
+    ];
+
+    // Actions that can be held, so can be set to tap, hold or toggle.
+    // Teleporting is always a single press.
+    pub fn has_mode(self) -> bool {
+        self == Action::Fire
+    }
+
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            Action::MoveUp => vec![KeyCode::W, KeyCode::Up],
+            Action::MoveDown => vec![KeyCode::S, KeyCode::Down],
+            Action::MoveLeft => vec![KeyCode::A, KeyCode::Left],
+            Action::MoveRight => vec![KeyCode::D, KeyCode::Right],
+            Action::Teleport => vec![KeyCode::Space],
+            Action::Fire => vec![KeyCode::F],
+            Action::Pause => vec![KeyCode::Escape],
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Teleport => "Teleport",
+            Action::Fire => "Fire",
+            Action::Pause => "Pause",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Which keys do what, saved next to the profile.
+// An action missing from the file keeps its default keys.
+#[derive(Default, Serialize, Deserialize)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<KeyCode>>,
+    // How each held action is triggered, saved in the settings instead
+    // so they sit with the other accessibility options
+    #[serde(skip)]
+    modes: HashMap<Action, ActivationMode>,
+}
+
+impl InputMap {
+    // A missing or broken file starts with the default keys
+    pub fn load() -> Self {
+        fs::read_to_string(save_directory().join(INPUT_MAP_FILE))
+            .ok()
+            .and_then(|contents| ron::de::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let directory = save_directory();
+        let contents = match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()) {
+            Ok(contents) => contents,
+            Err(error) => {
+                println!("Could not serialize the key bindings: {}", error);
+                return;
+            }
+        };
+
+        if let Err(error) = fs::create_dir_all(&directory)
+            .and_then(|_| fs::write(directory.join(INPUT_MAP_FILE), contents))
+        {
+            println!("Could not save the key bindings: {}", error);
+        }
+    }
+
+    pub fn keys(&self, action: Action) -> Vec<KeyCode> {
+        self.bindings
+            .get(&action)
+            .cloned()
+            .unwrap_or_else(|| action.default_keys())
+    }
+
+    // Rebinding replaces every key the action had
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        self.bindings.insert(action, vec![key]);
+    }
+
+    pub fn reset(&mut self) {
+        self.bindings.clear();
+    }
+
+    pub fn mode(&self, action: Action) -> ActivationMode {
+        self.modes
+            .get(&action)
+            .copied()
+            .unwrap_or(ActivationMode::Tap)
+    }
+
+    // Saved to the settings straight away, like the keys are to their file
+    pub fn set_mode(&mut self, action: Action, mode: ActivationMode, settings: &mut GameSettings) {
+        self.modes.insert(action, mode);
+        settings.action_modes = self.modes.clone();
+        settings.save();
+    }
+
+    // Whether the action is on this frame with its mode, toggled is kept
+    // by whoever owns the action between frames
+    pub fn active(
+        &self,
+        keyboard_input: &Input<KeyCode>,
+        action: Action,
+        toggled: &mut bool,
+    ) -> bool {
+        self.mode(action).active(
+            self.pressed(keyboard_input, action),
+            self.just_pressed(keyboard_input, action),
+            toggled,
+        )
+    }
+
+    pub fn pressed(&self, keyboard_input: &Input<KeyCode>, action: Action) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|key| keyboard_input.pressed(*key))
+    }
+
+    pub fn just_pressed(&self, keyboard_input: &Input<KeyCode>, action: Action) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|key| keyboard_input.just_pressed(*key))
+    }
+
+    // take_just_pressed for any of the action's keys
+    pub fn take_just_pressed(&self, keyboard_input: &mut Input<KeyCode>, action: Action) -> bool {
+        let pressed = self.just_pressed(keyboard_input, action);
+        if pressed {
+            for key in self.keys(action).iter() {
+                keyboard_input.reset(*key);
+            }
+        }
+        pressed
+    }
+}
+
+// Whether the key was just pressed, using it up if it was. For keys that
+// switch to a state that also listens for them, like a menu opened and
+// closed with the same key, or the other check would see the key in the
+// same frame and switch straight back.
+pub fn take_just_pressed(keyboard_input: &mut Input<KeyCode>, key: KeyCode) -> bool {
+    let pressed = keyboard_input.just_pressed(key);
+    if pressed {
+        keyboard_input.reset(key);
+    }
+    pressed
+}
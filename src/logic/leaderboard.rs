@@ -0,0 +1,343 @@
+// LEADERBOARD CODE
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::player::PlayerDeathEvent;
+use super::sealed_save::{load_sealed, save_sealed};
+use super::stats::RunStats;
+use crate::settings::GameSettings;
+
+const LEADERBOARD_FILE: &str = "leaderboard.sav";
+// There was never a plain version of this file
+const LEGACY_LEADERBOARD_FILE: &str = "leaderboard.ron";
+const PAGE_SIZE: usize = 10;
+// The local board keeps this many runs, the rest could never be seen anyway
+const MAX_LOCAL_RUNS: usize = 1000;
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(Leaderboard::new(Box::new(LocalBackend::load())))
+            .add_system(submit_run.system());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LeaderboardView {
+    // Everyone, best first
+    Global,
+    // The page around the player's best run
+    NearMe,
+    // Only the player and the friends from the settings
+    Friends,
+}
+
+impl LeaderboardView {
+    pub const ALL: [LeaderboardView; 3] = [
+        LeaderboardView::Global,
+        LeaderboardView::NearMe,
+        LeaderboardView::Friends,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LeaderboardView::Global => "Global",
+            LeaderboardView::NearMe => "Near me",
+            LeaderboardView::Friends => "Friends",
+        }
+    }
+
+    pub fn next(&self) -> LeaderboardView {
+        let index = LeaderboardView::ALL
+            .iter()
+            .position(|view| view == self)
+            .unwrap_or(0);
+        LeaderboardView::ALL[(index + 1) % LeaderboardView::ALL.len()]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LeaderboardQuery {
+    pub view: LeaderboardView,
+    // Counted from the top of the view, or from the player's own page
+    // for the near me view
+    pub page: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct LeaderboardRun {
+    pub username: String,
+    pub survival_seconds: f32,
+}
+
+// A run with its place on the whole board, so the friends and near me
+// views still show the real rank
+#[derive(Clone, PartialEq, Debug)]
+pub struct RankedRun {
+    pub rank: usize,
+    pub run: LeaderboardRun,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LeaderboardPage {
+    pub runs: Vec<RankedRun>,
+    pub page: usize,
+    pub page_count: usize,
+}
+
+// Where the runs are kept. The game only has the local one, an online board
+// plugs in here and returns None from fetch when it can't be reached.
+pub trait LeaderboardBackend: Send + Sync {
+    fn submit(&mut self, run: LeaderboardRun);
+    fn fetch(
+        &self,
+        query: LeaderboardQuery,
+        username: &str,
+        friends: &[String],
+    ) -> Option<LeaderboardPage>;
+}
+
+// Every page fetched is kept, so flicking between pages and views doesn't
+// wait on the backend and still works once it goes offline
+pub struct Leaderboard {
+    backend: Box<dyn LeaderboardBackend>,
+    cache: HashMap<LeaderboardQuery, LeaderboardPage>,
+}
+
+impl Leaderboard {
+    pub fn new(backend: Box<dyn LeaderboardBackend>) -> Self {
+        Leaderboard {
+            backend,
+            cache: HashMap::new(),
+        }
+    }
+
+    // Anything cached could be missing the new run, so it all goes
+    pub fn submit(&mut self, run: LeaderboardRun) {
+        self.backend.submit(run);
+        self.cache.clear();
+    }
+
+    pub fn page(
+        &mut self,
+        query: LeaderboardQuery,
+        username: &str,
+        friends: &[String],
+    ) -> Option<&LeaderboardPage> {
+        if !self.cache.contains_key(&query) {
+            let page = self.backend.fetch(query, username, friends)?;
+            self.cache.insert(query, page);
+        }
+        self.cache.get(&query)
+    }
+}
+
+// The runs played on this machine, sealed like the high scores
+#[derive(Default, Serialize, Deserialize)]
+pub struct LocalBackend {
+    runs: Vec<LeaderboardRun>,
+    #[serde(skip)]
+    saved: bool,
+}
+
+impl LocalBackend {
+    pub fn load() -> Self {
+        let mut backend: LocalBackend =
+            load_sealed(LEADERBOARD_FILE, LEGACY_LEADERBOARD_FILE, "leaderboard")
+                .unwrap_or_default();
+        backend.saved = true;
+        backend
+    }
+}
+
+impl LeaderboardBackend for LocalBackend {
+    fn submit(&mut self, run: LeaderboardRun) {
+        self.runs.push(run);
+        self.runs.sort_by(|a, b| {
+            b.survival_seconds
+                .partial_cmp(&a.survival_seconds)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        self.runs.truncate(MAX_LOCAL_RUNS);
+        if self.saved {
+            save_sealed(self, LEADERBOARD_FILE, "leaderboard");
+        }
+    }
+
+    fn fetch(
+        &self,
+        query: LeaderboardQuery,
+        username: &str,
+        friends: &[String],
+    ) -> Option<LeaderboardPage> {
+        Some(page_of(&self.runs, query, username, friends))
+    }
+}
+
+// The runs have to be sorted best first already
+pub fn page_of(
+    runs: &[LeaderboardRun],
+    query: LeaderboardQuery,
+    username: &str,
+    friends: &[String],
+) -> LeaderboardPage {
+    let ranked = runs.iter().enumerate().map(|(index, run)| RankedRun {
+        rank: index + 1,
+        run: run.clone(),
+    });
+
+    let is_me = |name: &str| name.eq_ignore_ascii_case(username);
+    let shown: Vec<RankedRun> = match query.view {
+        LeaderboardView::Friends => ranked
+            .filter(|ranked| {
+                is_me(&ranked.run.username)
+                    || friends
+                        .iter()
+                        .any(|friend| friend.eq_ignore_ascii_case(&ranked.run.username))
+            })
+            .collect(),
+        _ => ranked.collect(),
+    };
+
+    let page_count = ((shown.len() + PAGE_SIZE - 1) / PAGE_SIZE).max(1);
+
+    // Starts half a page above the player's best run, and from the top
+    // if they haven't got one yet
+    let first = match query.view {
+        LeaderboardView::NearMe => {
+            let best = shown
+                .iter()
+                .position(|ranked| is_me(&ranked.run.username))
+                .unwrap_or(0);
+            best.saturating_sub(PAGE_SIZE / 2) + query.page * PAGE_SIZE
+        }
+        _ => query.page * PAGE_SIZE,
+    };
+
+    LeaderboardPage {
+        runs: shown.into_iter().skip(first).take(PAGE_SIZE).collect(),
+        page: first / PAGE_SIZE,
+        page_count,
+    }
+}
+
+// Every finished run goes on the board under the player's username
+fn submit_run(
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    run_stats: Res<RunStats>,
+    settings: Res<GameSettings>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    for _event in player_death_event.iter() {
+        leaderboard.submit(LeaderboardRun {
+            username: settings.username.clone(),
+            survival_seconds: run_stats.survival_seconds,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(count: usize) -> Vec<LeaderboardRun> {
+        (0..count)
+            .map(|index| LeaderboardRun {
+                username: format!("player{}", index),
+                survival_seconds: (count - index) as f32,
+            })
+            .collect()
+    }
+
+    fn query(view: LeaderboardView, page: usize) -> LeaderboardQuery {
+        LeaderboardQuery { view, page }
+    }
+
+    fn ranks(page: &LeaderboardPage) -> Vec<usize> {
+        page.runs.iter().map(|ranked| ranked.rank).collect()
+    }
+
+    #[test]
+    fn global_is_paged() {
+        let runs = runs(25);
+
+        let first = page_of(&runs, query(LeaderboardView::Global, 0), "", &[]);
+        assert_eq!(ranks(&first), (1..=10).collect::<Vec<_>>());
+        assert_eq!(first.page_count, 3);
+
+        let last = page_of(&runs, query(LeaderboardView::Global, 2), "", &[]);
+        assert_eq!(ranks(&last), (21..=25).collect::<Vec<_>>());
+
+        let past_the_end = page_of(&runs, query(LeaderboardView::Global, 3), "", &[]);
+        assert!(past_the_end.runs.is_empty());
+    }
+
+    #[test]
+    fn near_me_is_centred_on_the_player() {
+        let runs = runs(40);
+
+        let page = page_of(&runs, query(LeaderboardView::NearMe, 0), "PLAYER20", &[]);
+        assert_eq!(ranks(&page), (16..=25).collect::<Vec<_>>());
+
+        let below = page_of(&runs, query(LeaderboardView::NearMe, 1), "player20", &[]);
+        assert_eq!(ranks(&below), (26..=35).collect::<Vec<_>>());
+
+        // Near the top it can't go above first place
+        let top = page_of(&runs, query(LeaderboardView::NearMe, 0), "player2", &[]);
+        assert_eq!(ranks(&top), (1..=10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn friends_keep_their_global_rank() {
+        let runs = runs(30);
+        let friends = vec!["player3".to_string(), "Player17".to_string()];
+
+        let page = page_of(
+            &runs,
+            query(LeaderboardView::Friends, 0),
+            "player9",
+            &friends,
+        );
+        assert_eq!(ranks(&page), vec![4, 10, 18]);
+        assert_eq!(page.page_count, 1);
+    }
+
+    struct Offline;
+
+    impl LeaderboardBackend for Offline {
+        fn submit(&mut self, _run: LeaderboardRun) {}
+
+        fn fetch(&self, _: LeaderboardQuery, _: &str, _: &[String]) -> Option<LeaderboardPage> {
+            None
+        }
+    }
+
+    #[test]
+    fn pages_are_cached_until_a_run_is_submitted() {
+        let mut leaderboard = Leaderboard::new(Box::new(LocalBackend::default()));
+        let global = query(LeaderboardView::Global, 0);
+
+        leaderboard.submit(LeaderboardRun {
+            username: "me".to_string(),
+            survival_seconds: 10.0,
+        });
+        assert_eq!(leaderboard.page(global, "me", &[]).unwrap().runs.len(), 1);
+
+        // A cached page is still there with the backend gone
+        let cached = leaderboard.page(global, "me", &[]).cloned();
+        leaderboard.backend = Box::new(Offline);
+        assert_eq!(leaderboard.page(global, "me", &[]).cloned(), cached);
+        assert!(leaderboard
+            .page(query(LeaderboardView::Friends, 0), "me", &[])
+            .is_none());
+
+        leaderboard.submit(LeaderboardRun {
+            username: "me".to_string(),
+            survival_seconds: 20.0,
+        });
+        assert!(leaderboard.page(global, "me", &[]).is_none());
+    }
+}
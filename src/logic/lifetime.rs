@@ -0,0 +1,45 @@
+// LIFETIME CODE
+
+use bevy::prelude::*;
+
+use super::game_time::GameTime;
+
+pub struct LifetimePlugin;
+
+impl Plugin for LifetimePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(despawn_expired.system());
+    }
+}
+
+// Despawns the entity once the timer runs out, so short lived effects
+// don't each need their own cleanup. Effects that fade can read how far
+// along the timer is.
+pub struct DespawnAfter(pub Timer);
+
+impl DespawnAfter {
+    pub fn seconds(seconds: f32) -> Self {
+        DespawnAfter(Timer::from_seconds(seconds, false))
+    }
+
+    // 0 when just spawned to 1 when about to go
+    pub fn percent(&self) -> f32 {
+        self.0.percent()
+    }
+}
+
+// NOTE
+// Runs on real time in every state, so effects finish even while the game
+// is paused or slowed down. Spawn warnings keep their own timer since they
+// have to stop with the game and spawn a block when they are done.
+fn despawn_expired(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut lifetime_query: Query<(Entity, &mut DespawnAfter)>,
+) {
+    for (entity, mut despawn_after) in lifetime_query.iter_mut() {
+        if despawn_after.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
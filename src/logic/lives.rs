@@ -0,0 +1,171 @@
+// LIVES CODE
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::daily::DailyChallenge;
+use super::game_time::GameTime;
+use super::player::{spawn_player, Controls, Player, PlayerId};
+use super::profile::Profile;
+use super::reset_game::Resettable;
+use super::rules::{CoopLives, GameRules};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+// How long the player waits before coming back
+pub const RESPAWN_SECONDS: f32 = 2.0;
+// How long the player can't be hit after coming back
+const INVULNERABLE_SECONDS: f32 = 2.0;
+// How many times a second the player blinks while invulnerable
+const BLINK_RATE: f32 = 10.0;
+
+pub struct LivesPlugin;
+
+impl Plugin for LivesPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<LifeLostEvent>()
+            .insert_resource(Lives::new(3, CoopLives::Shared))
+            .reset_world(reset_lives)
+            .init_resource::<Respawn>()
+            .reset_on_restart::<Respawn>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(start_respawn.system())
+                    .with_system(respawn_countdown.system())
+                    .with_system(invulnerability_system.system()),
+            );
+    }
+}
+
+// How many lives each player has left, including the current one.
+// With shared lives that is the same for everyone.
+pub struct Lives {
+    starting: u32,
+    coop_lives: CoopLives,
+    lost: HashMap<PlayerId, u32>,
+}
+
+impl Lives {
+    pub fn new(starting: u32, coop_lives: CoopLives) -> Self {
+        Lives {
+            starting,
+            coop_lives,
+            lost: HashMap::new(),
+        }
+    }
+
+    pub fn left(&self, player: PlayerId) -> u32 {
+        let lost = match self.coop_lives {
+            CoopLives::Shared => self.lost.values().sum(),
+            CoopLives::PerPlayer => self.lost.get(&player).copied().unwrap_or(0),
+        };
+        self.starting.saturating_sub(lost)
+    }
+
+    // Returns how many that player has left afterwards
+    pub fn lose(&mut self, player: PlayerId) -> u32 {
+        *self.lost.entry(player).or_insert(0) += 1;
+        self.left(player)
+    }
+}
+
+// Sent when a player dies but there are still lives left
+pub struct LifeLostEvent(pub Controls);
+
+// A player waiting to come back
+pub struct PendingRespawn {
+    pub controls: Controls,
+    pub timer: Timer,
+}
+
+// Every player waiting to come back
+#[derive(Default)]
+pub struct Respawn {
+    pub pending: Vec<PendingRespawn>,
+}
+
+// The player can't be hit while this is on them
+pub struct Invulnerable {
+    pub timer: Timer,
+}
+
+impl Invulnerable {
+    // The grace period a player gets whenever they come into a run
+    pub fn after_spawn() -> Self {
+        Invulnerable {
+            timer: Timer::from_seconds(INVULNERABLE_SECONDS, false),
+        }
+    }
+}
+
+fn reset_lives(world: &mut World) {
+    // The daily is the same for everyone, unlocked lives included
+    let daily = world
+        .get_resource::<DailyChallenge>()
+        .map_or(false, |daily| daily.is_active());
+    let bonus_lives = match world.get_resource::<Profile>() {
+        Some(profile) if !daily => profile.bonus_lives(),
+        _ => 0,
+    };
+    let (starting, coop_lives) = world
+        .get_resource::<GameRules>()
+        .map_or((0, CoopLives::Shared), |rules| {
+            (rules.lives, rules.coop_lives)
+        });
+
+    world.insert_resource(Lives::new(starting + bonus_lives, coop_lives));
+}
+
+fn start_respawn(mut life_lost_event: EventReader<LifeLostEvent>, mut respawn: ResMut<Respawn>) {
+    for event in life_lost_event.iter() {
+        respawn.pending.push(PendingRespawn {
+            controls: event.0,
+            timer: Timer::from_seconds(RESPAWN_SECONDS, false),
+        });
+    }
+}
+
+fn respawn_countdown(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut respawn: ResMut<Respawn>,
+    time: Res<GameTime>,
+) {
+    for pending in respawn.pending.iter_mut() {
+        pending.timer.tick(time.delta());
+
+        if pending.timer.finished() {
+            let player = spawn_player(
+                &mut commands,
+                &asset_server,
+                &mut materials,
+                &settings,
+                pending.controls,
+            );
+            commands.entity(player).insert(Invulnerable::after_spawn());
+        }
+    }
+
+    respawn.pending.retain(|pending| !pending.timer.finished());
+}
+
+// Blink the player while they can't be hit
+fn invulnerability_system(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Invulnerable, &mut Visible), With<Player>>,
+    time: Res<GameTime>,
+) {
+    for (entity, mut invulnerable, mut visible) in player_query.iter_mut() {
+        invulnerable.timer.tick(time.delta());
+
+        if invulnerable.timer.finished() {
+            visible.is_visible = true;
+            commands.entity(entity).remove::<Invulnerable>();
+        } else {
+            let blinks = (invulnerable.timer.elapsed_secs() * BLINK_RATE) as u32;
+            visible.is_visible = blinks % 2 == 0;
+        }
+    }
+}
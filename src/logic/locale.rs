@@ -0,0 +1,83 @@
+// LOCALE CODE
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::settings::GameSettings;
+
+const LOCALES: &str = include_str!("../../assets/locales.ron");
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(Locales::load());
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Locale {
+    pub code: String,
+    // In its own language, so it can be found without reading the current one
+    pub name: String,
+    pub font: String,
+    // Mirrors the HUD and menus, see rtl_layout
+    #[serde(default)]
+    pub right_to_left: bool,
+}
+
+#[derive(Deserialize)]
+pub struct Locales {
+    pub locales: Vec<Locale>,
+}
+
+impl Default for Locales {
+    fn default() -> Self {
+        Locales {
+            locales: vec![Locale {
+                code: "en".to_string(),
+                name: "English".to_string(),
+                font: "fonts/Roboto-Thin.ttf".to_string(),
+                right_to_left: false,
+            }],
+        }
+    }
+}
+
+impl Locales {
+    // A broken or empty file leaves only English
+    fn load() -> Self {
+        match ron::de::from_str::<Locales>(LOCALES) {
+            Ok(locales) if !locales.locales.is_empty() => locales,
+            Ok(_) => Locales::default(),
+            Err(error) => {
+                println!("Could not read the locales: {}", error);
+                Locales::default()
+            }
+        }
+    }
+
+    pub fn default_locale(&self) -> &Locale {
+        &self.locales[0]
+    }
+
+    // An unknown language in the settings gets the default
+    pub fn current(&self, settings: &GameSettings) -> &Locale {
+        self.locales
+            .iter()
+            .find(|locale| locale.code == settings.language)
+            .unwrap_or_else(|| self.default_locale())
+    }
+
+    // The language step places away from the current one, wrapping around
+    pub fn next(&self, settings: &GameSettings, step: isize) -> &Locale {
+        let count = self.locales.len() as isize;
+        let index = self
+            .locales
+            .iter()
+            .position(|locale| locale.code == settings.language)
+            .unwrap_or(0) as isize;
+
+        &self.locales[(index + step).rem_euclid(count) as usize]
+    }
+}
@@ -1,5 +1,57 @@
+pub mod adaptive;
+pub mod beat;
+pub mod block_collisions;
+pub mod block_kinds;
 pub mod blocks;
+pub mod bot;
+pub mod bounty;
+pub mod challenges;
+pub mod clipboard;
+pub mod combo;
+pub mod coop;
+#[cfg(debug_assertions)]
+pub mod custom_start;
+pub mod daily;
+pub mod difficulty;
+pub mod factions;
+pub mod feedback;
+pub mod game_rng;
+pub mod game_time;
+pub mod gamepad;
+pub mod graze;
+pub mod health;
+pub mod high_scores;
+pub mod hints;
+pub mod idle;
+pub mod input_map;
+pub mod leaderboard;
+pub mod lifetime;
+pub mod lives;
+pub mod locale;
+pub mod mutators;
+#[cfg(feature = "overlay")]
+pub mod overlay;
 pub mod player;
+pub mod powerups;
+pub mod profile;
+pub mod projectiles;
 pub mod reset_game;
+pub mod rules;
+pub mod sealed_save;
+pub mod seed_codes;
 /// Block Game logic
+pub mod snapshots;
+pub mod spatial_grid;
 pub mod spawning;
+pub mod spectator;
+pub mod splitting;
+pub mod stats;
+pub mod time_scale;
+pub mod triggers;
+pub mod turrets;
+#[cfg(feature = "twitch")]
+pub mod twitch;
+pub mod upgrades;
+pub mod versus;
+pub mod waves;
+pub mod weekly;
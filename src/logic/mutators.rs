@@ -0,0 +1,147 @@
+// MUTATOR CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::blocks::Block;
+use super::player::Player;
+use crate::{AppState, Velocity};
+
+// How close a block has to be before it shows up with hidden blocks on
+const REVEAL_RADIUS: f32 = 250.0;
+
+pub struct MutatorPlugin;
+
+impl Plugin for MutatorPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<ActiveMutators>()
+            .add_system(apply_player_mutators.system())
+            .add_system(apply_block_mutators.system())
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(reveal_close_blocks.system()),
+            );
+    }
+}
+
+// Modifiers picked before a run that change how it plays.
+// Any number of them can be on at once.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Mutator {
+    TinyPlayer,
+    DoubleSpeed,
+    NoDash,
+    MirrorControls,
+    HiddenBlocks,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 5] = [
+        Mutator::TinyPlayer,
+        Mutator::DoubleSpeed,
+        Mutator::NoDash,
+        Mutator::MirrorControls,
+        Mutator::HiddenBlocks,
+    ];
+
+    // Harder mutators are worth more points
+    pub fn score_multiplier(self) -> f32 {
+        match self {
+            Mutator::TinyPlayer => 0.8,
+            Mutator::DoubleSpeed => 1.5,
+            Mutator::NoDash => 1.25,
+            Mutator::MirrorControls => 1.25,
+            Mutator::HiddenBlocks => 1.5,
+        }
+    }
+}
+
+impl fmt::Display for Mutator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mutator::TinyPlayer => write!(f, "Tiny player"),
+            Mutator::DoubleSpeed => write!(f, "Double speed"),
+            Mutator::NoDash => write!(f, "No teleport"),
+            Mutator::MirrorControls => write!(f, "Mirror controls"),
+            Mutator::HiddenBlocks => write!(f, "Hidden blocks"),
+        }
+    }
+}
+
+// The mutators that are on for the current run
+#[derive(Default)]
+pub struct ActiveMutators(pub Vec<Mutator>);
+
+impl ActiveMutators {
+    pub fn has(&self, mutator: Mutator) -> bool {
+        self.0.contains(&mutator)
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if self.has(mutator) {
+            self.0.retain(|active| *active != mutator);
+        } else {
+            self.0.push(mutator);
+        }
+    }
+
+    // Every mutator's multiplier stacks
+    pub fn score_multiplier(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|mutator| mutator.score_multiplier())
+            .product()
+    }
+}
+
+fn apply_player_mutators(
+    mutators: Res<ActiveMutators>,
+    mut player_query: Query<(&mut Player, &mut Sprite), Added<Player>>,
+) {
+    for (mut player, mut sprite) in player_query.iter_mut() {
+        if mutators.has(Mutator::TinyPlayer) {
+            sprite.size /= 2.0;
+            // Keep the texture from resizing the sprite back up
+            sprite.resize_mode = SpriteResizeMode::Manual;
+        }
+
+        if mutators.has(Mutator::DoubleSpeed) {
+            player.speed *= 2.0;
+        }
+    }
+}
+
+fn apply_block_mutators(
+    mutators: Res<ActiveMutators>,
+    mut block_query: Query<&mut Velocity, Added<Block>>,
+) {
+    if !mutators.has(Mutator::DoubleSpeed) {
+        return;
+    }
+
+    for mut velocity in block_query.iter_mut() {
+        velocity.0 *= 2.0;
+    }
+}
+
+fn reveal_close_blocks(
+    mutators: Res<ActiveMutators>,
+    player_query: Query<&Transform, With<Player>>,
+    mut block_query: Query<(&Transform, &mut Visible), With<Block>>,
+) {
+    if !mutators.has(Mutator::HiddenBlocks) {
+        return;
+    }
+
+    // Blocks show up when they are close to any player
+    for (transform, mut visible) in block_query.iter_mut() {
+        visible.is_visible = player_query.iter().any(|player_transform| {
+            transform
+                .translation
+                .truncate()
+                .distance(player_transform.translation.truncate())
+                < REVEAL_RADIUS
+        });
+    }
+}
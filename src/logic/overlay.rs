@@ -0,0 +1,99 @@
+// OVERLAY CODE
+
+use bevy::prelude::*;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::lives::Lives;
+use super::mutators::ActiveMutators;
+use super::player::{Controls, PlayerId};
+use super::upgrades::RunUpgrades;
+use super::waves::WaveManager;
+use crate::graphics::score::Score;
+
+// Browser sources can poll http://127.0.0.1:7878 for the latest state
+const OVERLAY_ADDRESS: &str = "127.0.0.1:7878";
+
+pub struct OverlayPlugin;
+
+impl Plugin for OverlayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(OverlayState::serve())
+            .add_system(update_overlay_state.system());
+    }
+}
+
+// The latest JSON, shared with the server thread
+struct OverlayState(Arc<Mutex<String>>);
+
+impl OverlayState {
+    fn serve() -> Self {
+        let state = Arc::new(Mutex::new("{}".to_string()));
+
+        let listener = match TcpListener::bind(OVERLAY_ADDRESS) {
+            Ok(listener) => listener,
+            Err(error) => {
+                println!("Could not start the overlay server: {}", error);
+                return OverlayState(state);
+            }
+        };
+
+        let server_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let json = server_state
+                    .lock()
+                    .expect("The overlay state lock is poisoned.")
+                    .clone();
+
+                if let Err(error) = respond(stream, &json) {
+                    println!("Overlay request failed: {}", error);
+                }
+            }
+        });
+
+        OverlayState(state)
+    }
+}
+
+// Every request gets the same answer, no matter the path
+fn respond(mut stream: TcpStream, json: &str) -> std::io::Result<()> {
+    // The request itself doesn't matter, but it has to be read
+    let mut request = [0; 1024];
+    let _ = stream.read(&mut request)?;
+
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        json.len(),
+        json
+    )
+}
+
+// Same resources the HUD reads, only rebuilt when they change
+fn update_overlay_state(
+    overlay_state: Res<OverlayState>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+    waves: Res<WaveManager>,
+) {
+    if !score.is_changed() && !lives.is_changed() && !waves.is_changed() {
+        return;
+    }
+
+    let json = format!(
+        "{{\"score\":{},\"lives\":{},\"wave\":{}}}",
+        score.total(&mutators, &upgrades),
+        lives.left(PlayerId::from(Controls::Primary)),
+        waves.wave
+    );
+
+    *overlay_state
+        .0
+        .lock()
+        .expect("The overlay state lock is poisoned.") = json;
+}
@@ -1,146 +1,644 @@
 // PLAYER CODE
 
-use crate::{Collidable, WINDOWHEIGHT, WINDOWWIDTH};
+use crate::graphics::camera::CameraRig;
+use crate::graphics::sprites::player_texture;
+use crate::settings::GameSettings;
+use crate::{wrap_position, AppState, Collidable, Velocity, WrapMargin};
 
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::ElementState;
 use bevy::prelude::*;
-use bevy::sprite::collide_aabb::collide;
+use bevy::sprite::SpriteResizeMode;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::bot::BotBrain;
+use super::factions::{CollisionEvent, Faction, DETECT_COLLISIONS};
+use super::game_time::GameTime;
+use super::gamepad::{left_stick, ConnectedGamepads, StickSettings};
+use super::input_map::{Action, InputMap};
+use super::lives::{Invulnerable, LifeLostEvent, Lives, Respawn};
+use super::mutators::{ActiveMutators, Mutator};
+use super::powerups::{absorb_hit, Shield};
+use super::reset_game::RunScoped;
+use super::spectator::{Spectator, StartSpectatingEvent};
+use super::time_scale::TimeScale;
+use super::upgrades::RunUpgrades;
+
+// How long the player takes to break apart before they are removed
+pub const DEATH_SEQUENCE_SECONDS: f32 = 1.0;
+// How slow the rest of the game runs while the player is dying
+const DEATH_TIME_SCALE: f32 = 0.3;
+
+// Seconds before the player can teleport again
+pub const TELEPORT_COOLDOWN: f32 = 0.75;
+
+// How long a dash takes to cover the teleport distance
+const DASH_SECONDS: f32 = 0.15;
+// Players come back the moment they are fully off screen
+const PLAYER_WRAP_MARGIN: f32 = 0.0;
+
+// Label for the system that turns input into movement
+pub const MOVE_PLAYER: &str = "move_player";
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_event::<PlayerDeathEvent>()
-            .add_startup_system(spawn_startup_player.system())
-            .add_system(move_player.system())
-            .add_system(player_collision_system.system());
+            .add_event::<PlayerHitEvent>()
+            .add_event::<TeleportEvent>()
+            .init_resource::<InputLatency>()
+            .init_resource::<ControlScheme>()
+            .init_resource::<MovementAbility>()
+            .add_system_to_stage(CoreStage::First, timestamp_input.system())
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    // Move first so collisions see this frame's input, not last frame's
+                    .with_system(move_player.system().label(MOVE_PLAYER))
+                    .with_system(damage_players.system().after(DETECT_COLLISIONS))
+                    .with_system(dying_system.system()),
+            );
     }
 }
 
-// The float value is the player movement speed in 'pixels/second'.
+// Speed is the player movement speed in 'pixels/second',
+// the Velocity component is the way they are moving right now
 pub struct Player {
-    pub velocity: f32,
+    pub speed: f32,
     pub teleport_distance: f32,
+    // Teleports are only allowed once this has finished
+    pub teleport_cooldown: Timer,
 }
 
-fn spawn_startup_player(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-) {
-    spawn_player(&mut commands, &asset_server, &mut materials);
+// What a player is controlled with. The primary player uses the keyboard
+// and any gamepad that hasn't dropped in as its own player.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Controls {
+    Primary,
+    Gamepad(Gamepad),
+    // A second player on the same keyboard, IJKL to move,
+    // U to teleport and O to fire. These can't be rebound.
+    SecondKeyboard,
+    // A computer controlled teammate
+    Bot,
+}
+
+// Which player this is, the same one every time they come back.
+// The primary player is always player 1.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PlayerId(pub usize);
+
+impl From<Controls> for PlayerId {
+    fn from(controls: Controls) -> Self {
+        match controls {
+            Controls::Primary => PlayerId(1),
+            Controls::SecondKeyboard => PlayerId(2),
+            Controls::Bot => PlayerId(3),
+            // After everyone that doesn't need a gamepad
+            Controls::Gamepad(gamepad) => PlayerId(4 + gamepad.0),
+        }
+    }
+}
+
+impl fmt::Display for Controls {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Controls::Primary => write!(f, "Player 1"),
+            Controls::Gamepad(gamepad) => write!(f, "Gamepad {}", gamepad.0 + 1),
+            Controls::SecondKeyboard => write!(f, "Player 2"),
+            Controls::Bot => write!(f, "Bot"),
+        }
+    }
 }
 
 pub fn spawn_player(
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
-) {
+    settings: &GameSettings,
+    controls: Controls,
+) -> Entity {
+    let mut teleport_cooldown = Timer::from_seconds(TELEPORT_COOLDOWN, false);
+    // Ready to teleport straight away
+    teleport_cooldown.tick(teleport_cooldown.duration());
+
     let sprite_size_x = 40.0;
     let sprite_size_y = 40.0;
-    let texture_handle = asset_server.load("textures/block_3.png");
+    let texture_handle = player_texture(asset_server);
 
     commands
         .spawn_bundle(SpriteBundle {
             material: materials.add(texture_handle.into()),
             transform: Transform::from_xyz(0.0, 0.0, 1.0),
-            sprite: Sprite::new(Vec2::new(sprite_size_x, sprite_size_y)),
+            sprite: Sprite {
+                size: Vec2::new(sprite_size_x, sprite_size_y),
+                // Sprites can be drawn at any size, the player stays this big
+                resize_mode: SpriteResizeMode::Manual,
+                ..Default::default()
+            },
             ..Default::default()
         })
         .insert(Player {
-            velocity: 300.0,
-            teleport_distance: 70.0,
+            speed: settings.player_speed,
+            teleport_distance: settings.teleport_distance,
+            teleport_cooldown,
         })
-        .insert(Collidable);
+        .insert(Velocity(Vec2::ZERO))
+        .insert(AnimationState::Idle)
+        .insert(WrapMargin(PLAYER_WRAP_MARGIN))
+        .insert(controls)
+        .insert(PlayerId::from(controls))
+        .insert(Faction::Player)
+        .insert(Collidable::player())
+        .insert(RunScoped)
+        .id()
 }
 
-fn move_player(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut player_query: Query<(&Player, &mut Transform, &Sprite)>,
-    time: Res<Time>,
-) {
-    if let Ok((player, mut transform, sprite)) = player_query.single_mut() {
-        // Get input from the keyboard (WASD)
-        let up: bool = keyboard_input.pressed(KeyCode::W) || keyboard_input.pressed(KeyCode::Up);
-        let down: bool =
-            keyboard_input.pressed(KeyCode::S) || keyboard_input.pressed(KeyCode::Down);
-        let left: bool =
-            keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left);
-        let right: bool =
-            keyboard_input.pressed(KeyCode::D) || keyboard_input.pressed(KeyCode::Right);
-
-        // If left is pressed than it will be -1, right 1, both they cancel out.
-        let x_axis: i8 = -(left as i8) + right as i8;
-        let y_axis: i8 = -(down as i8) + up as i8;
-        let move_delta: Vec2 = Vec2::new(x_axis as f32, y_axis as f32);
+// How the primary player moves, the keyboard or just the mouse
+#[derive(Clone, Copy, PartialEq)]
+pub enum ControlScheme {
+    Keyboard,
+    // Follow the cursor, left click dashes and right click fires
+    Mouse,
+}
 
-        // move the player
-        let delta_time = time.delta_seconds();
-        transform.translation.x += move_delta.x * player.velocity * delta_time;
-        transform.translation.y += move_delta.y * player.velocity * delta_time;
+impl Default for ControlScheme {
+    fn default() -> Self {
+        ControlScheme::Keyboard
+    }
+}
+
+// What the teleport key does, jump straight there or dash the whole way
+#[derive(Clone, Copy, PartialEq)]
+pub enum MovementAbility {
+    Teleport,
+    // Quick movement the player can't be hit during
+    Dash,
+}
+
+impl Default for MovementAbility {
+    fn default() -> Self {
+        MovementAbility::Teleport
+    }
+}
 
-        // Wrap the player if they go off screen
-        if transform.translation.x > WINDOWWIDTH / 2.0 + sprite.size.x {
-            transform.translation.x = -WINDOWWIDTH / 2.0;
+impl fmt::Display for MovementAbility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MovementAbility::Teleport => write!(f, "Teleport"),
+            MovementAbility::Dash => write!(f, "Dash"),
         }
+    }
+}
+
+// A dash in progress, move_player carries the player from one end to the
+// other and detect_player_collisions leaves them alone until it is over
+pub struct Dashing {
+    from: Vec3,
+    to: Vec3,
+    timer: Timer,
+}
+
+// How a held ability is triggered, for players who can't or
+// would rather not keep a button held down
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ActivationMode {
+    // Once per press
+    Tap,
+    // For as long as the button is held
+    Hold,
+    // One press starts it and the next press stops it
+    Toggle,
+}
 
-        if transform.translation.x < -WINDOWWIDTH / 2.0 - sprite.size.x {
-            transform.translation.x = WINDOWWIDTH / 2.0;
+impl ActivationMode {
+    pub fn next(self) -> Self {
+        match self {
+            ActivationMode::Tap => ActivationMode::Hold,
+            ActivationMode::Hold => ActivationMode::Toggle,
+            ActivationMode::Toggle => ActivationMode::Tap,
         }
+    }
 
-        if transform.translation.y > WINDOWHEIGHT / 2.0 + sprite.size.y {
-            transform.translation.y = -WINDOWHEIGHT / 2.0;
+    // Whether the ability is on this frame, toggled is kept
+    // by whoever owns the ability between frames
+    pub fn active(self, held: bool, pressed: bool, toggled: &mut bool) -> bool {
+        match self {
+            ActivationMode::Tap => pressed,
+            ActivationMode::Hold => held,
+            ActivationMode::Toggle => {
+                if pressed {
+                    *toggled = !*toggled;
+                }
+                *toggled
+            }
         }
+    }
+}
 
-        if transform.translation.y < -WINDOWHEIGHT / 2.0 - sprite.size.y {
-            transform.translation.y = WINDOWHEIGHT / 2.0;
+impl fmt::Display for ActivationMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ActivationMode::Tap => write!(f, "Tap"),
+            ActivationMode::Hold => write!(f, "Hold"),
+            ActivationMode::Toggle => write!(f, "Toggle"),
         }
+    }
+}
+
+// How long after a press first reached the game it turned into movement,
+// shown on the debug overlay
+#[derive(Default)]
+pub struct InputLatency {
+    pub last: Option<Duration>,
+    // When this frame's press was read, if there was one
+    pressed_at: Option<Instant>,
+}
 
-        // teleport the player if they press space
-        if keyboard_input.just_pressed(KeyCode::Space) {
-            if y_axis == -1 {
-                transform.translation.y -= player.teleport_distance;
+// NOTE
+// The window events carry no time of their own, so a press is stamped
+// at the very start of the frame it arrives in. That is as early as
+// anything in the game can see it.
+fn timestamp_input(
+    mut keyboard_input_event: EventReader<KeyboardInput>,
+    mut mouse_button_event: EventReader<MouseButtonInput>,
+    mut gamepad_event: EventReader<GamepadEvent>,
+    mut input_latency: ResMut<InputLatency>,
+) {
+    let keys = keyboard_input_event
+        .iter()
+        .any(|event| event.state == ElementState::Pressed);
+    let buttons = mouse_button_event
+        .iter()
+        .any(|event| event.state == ElementState::Pressed);
+    let gamepad_buttons = gamepad_event
+        .iter()
+        .any(|event| matches!(event.1, GamepadEventType::ButtonChanged(_, value) if value > 0.0));
+
+    // Presses are applied the frame they come in, or not at all
+    input_latency.pressed_at = (keys || buttons || gamepad_buttons).then(Instant::now);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn move_player(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mouse_input: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<ConnectedGamepads>,
+    stick_settings: Res<StickSettings>,
+    control_scheme: Res<ControlScheme>,
+    movement_ability: Res<MovementAbility>,
+    windows: Res<Windows>,
+    camera_rig: Res<CameraRig>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Player,
+            &Controls,
+            Option<&mut BotBrain>,
+            Option<&mut Dashing>,
+            &mut Velocity,
+            &mut Transform,
+            &Sprite,
+            Option<&WrapMargin>,
+            &mut AnimationState,
+        ),
+        Without<Dying>,
+    >,
+    spectator_query: Query<&Spectator>,
+    mut teleport_event: EventWriter<TeleportEvent>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+    time: Res<GameTime>,
+    mut input_latency: ResMut<InputLatency>,
+) {
+    // Measured here, where the press is applied
+    if let Some(pressed_at) = input_latency.pressed_at.take() {
+        input_latency.last = Some(pressed_at.elapsed());
+    }
+
+    // Gamepads that dropped in as their own player don't also move the primary
+    // player, even once that player is out and spectating
+    let claimed: Vec<Controls> = player_query
+        .iter_mut()
+        .map(
+            |(
+                _entity,
+                _player,
+                controls,
+                _bot,
+                _dashing,
+                _velocity,
+                _transform,
+                _sprite,
+                _wrap_margin,
+                _animation_state,
+            )| { *controls },
+        )
+        .chain(spectator_query.iter().map(|spectator| spectator.controls))
+        .collect();
+    let primary_gamepad = gamepads
+        .0
+        .iter()
+        .copied()
+        .find(|gamepad| !claimed.contains(&Controls::Gamepad(*gamepad)));
+
+    let cursor = camera_rig.cursor_world_position(&windows);
+
+    for (
+        entity,
+        mut player,
+        controls,
+        bot,
+        dashing,
+        mut velocity,
+        mut transform,
+        sprite,
+        wrap_margin,
+        mut animation_state,
+    ) in player_query.iter_mut()
+    {
+        player.teleport_cooldown.tick(time.delta());
+
+        let mouse_controlled =
+            *controls == Controls::Primary && *control_scheme == ControlScheme::Mouse;
+
+        let (keyboard_delta, keyboard_teleport, gamepad) = match controls {
+            Controls::Primary if mouse_controlled => {
+                // Head for the cursor as fast as the player can go,
+                // slowing down on the last frame so it doesn't overshoot
+                let to_cursor = cursor.map_or(Vec2::ZERO, |cursor| {
+                    cursor - transform.translation.truncate()
+                });
+                let step = player.speed * upgrades.move_speed_multiplier() * time.delta_seconds();
+                let cursor_delta = if to_cursor.length() > step {
+                    to_cursor / to_cursor.length()
+                } else {
+                    to_cursor / step.max(f32::EPSILON)
+                };
+                (
+                    cursor_delta,
+                    mouse_input.just_pressed(MouseButton::Left),
+                    primary_gamepad,
+                )
+            }
+            Controls::Primary => {
+                // Get input from the keyboard, WASD and the arrows unless rebound
+                let up: bool = input_map.pressed(&keyboard_input, Action::MoveUp);
+                let down: bool = input_map.pressed(&keyboard_input, Action::MoveDown);
+                let left: bool = input_map.pressed(&keyboard_input, Action::MoveLeft);
+                let right: bool = input_map.pressed(&keyboard_input, Action::MoveRight);
+
+                // If left is pressed than it will be -1, right 1, both they cancel out.
+                let x_axis: i8 = -(left as i8) + right as i8;
+                let y_axis: i8 = -(down as i8) + up as i8;
+                (
+                    Vec2::new(x_axis as f32, y_axis as f32),
+                    input_map.just_pressed(&keyboard_input, Action::Teleport),
+                    primary_gamepad,
+                )
             }
+            Controls::Gamepad(gamepad) => (Vec2::ZERO, false, Some(*gamepad)),
+            Controls::SecondKeyboard => {
+                let up = keyboard_input.pressed(KeyCode::I);
+                let down = keyboard_input.pressed(KeyCode::K);
+                let left = keyboard_input.pressed(KeyCode::J);
+                let right = keyboard_input.pressed(KeyCode::L);
 
-            if y_axis == 1 {
-                transform.translation.y += player.teleport_distance;
+                let x_axis: i8 = -(left as i8) + right as i8;
+                let y_axis: i8 = -(down as i8) + up as i8;
+                (
+                    Vec2::new(x_axis as f32, y_axis as f32),
+                    keyboard_input.just_pressed(KeyCode::U),
+                    None,
+                )
             }
+            // The bot "presses" its keys through its brain
+            Controls::Bot => match bot {
+                Some(mut bot) => (bot.move_delta, std::mem::take(&mut bot.teleport), None),
+                None => (Vec2::ZERO, false, None),
+            },
+        };
+
+        // The keyboard wins if both are being used
+        let mut move_delta = if keyboard_delta != Vec2::ZERO {
+            keyboard_delta
+        } else {
+            gamepad.map_or(Vec2::ZERO, |gamepad| {
+                left_stick(&gamepad_axes, gamepad, &stick_settings)
+            })
+        };
+        if mutators.has(Mutator::MirrorControls) && *controls != Controls::Bot {
+            move_delta.x = -move_delta.x;
+        }
 
-            if x_axis == 1 {
-                transform.translation.x += player.teleport_distance;
+        // A dash carries the player along on its own until it is over
+        if let Some(mut dashing) = dashing {
+            dashing.timer.tick(time.delta());
+            transform.translation = dashing.from.lerp(dashing.to, dashing.timer.percent());
+            velocity.0 = (dashing.to - dashing.from).truncate() / DASH_SECONDS;
+
+            if dashing.timer.finished() {
+                commands.entity(entity).remove::<Dashing>();
             }
+            continue;
+        }
+
+        // move the player
+        let delta_time = time.delta_seconds();
+        velocity.0 = move_delta * player.speed * upgrades.move_speed_multiplier();
+        transform.translation += (velocity.0 * delta_time).extend(0.0);
+
+        // A teleport plays all the way through before walking takes over
+        let walking = if velocity.0 == Vec2::ZERO {
+            AnimationState::Idle
+        } else {
+            AnimationState::Moving
+        };
+        if *animation_state != AnimationState::Teleporting && *animation_state != walking {
+            *animation_state = walking;
+        }
+
+        // Wrap the player once they are fully off screen
+        let margin = wrap_margin.map_or(0.0, |margin| margin.0);
+        wrap_position(&mut transform.translation, sprite.size / 2.0, margin);
+
+        // teleport the player if they press space or the bottom face button
+        let teleport_pressed = keyboard_teleport
+            || gamepad.map_or(false, |gamepad| {
+                gamepad_buttons.just_pressed(GamepadButton(gamepad, GamepadButtonType::South))
+            });
+        let can_teleport = !mutators.has(Mutator::NoDash) && player.teleport_cooldown.finished();
+        if teleport_pressed && can_teleport && move_delta != Vec2::ZERO {
+            let from = transform.translation;
+            let teleport_distance = player.teleport_distance * upgrades.teleport_multiplier();
 
-            if x_axis == -1 {
-                transform.translation.x -= player.teleport_distance;
+            let teleport_direction = if mouse_controlled {
+                // A click dashes straight at the cursor
+                move_delta / move_delta.length()
+            } else {
+                // Teleports stay on the eight directions even with a stick
+                let axis = |value: f32| {
+                    if value > 0.0 {
+                        1.0
+                    } else if value < 0.0 {
+                        -1.0
+                    } else {
+                        0.0
+                    }
+                };
+                Vec2::new(axis(move_delta.x), axis(move_delta.y))
+            };
+            let to = from + (teleport_direction * teleport_distance).extend(0.0);
+            let cooldown = TELEPORT_COOLDOWN * upgrades.dash_cooldown_multiplier();
+            player
+                .teleport_cooldown
+                .set_duration(Duration::from_secs_f32(cooldown));
+            player.teleport_cooldown.reset();
+
+            match *movement_ability {
+                MovementAbility::Teleport => transform.translation = to,
+                MovementAbility::Dash => {
+                    commands.entity(entity).insert(Dashing {
+                        from,
+                        to,
+                        timer: Timer::from_seconds(DASH_SECONDS, false),
+                    });
+                }
             }
+
+            *animation_state = AnimationState::Teleporting;
+
+            // A dash counts as a teleport for the sound and the stats
+            teleport_event.send(TeleportEvent { from, to });
         }
     }
 }
 
+// Sent whenever the player teleports
+pub struct TeleportEvent {
+    pub from: Vec3,
+    pub to: Vec3,
+}
+
+// Sent once the death sequence is over, the last player is removed,
+// and there are no lives left
 pub struct PlayerDeathEvent;
 
-// simple, player collides with block system
-fn player_collision_system(
+// Sent the moment a block hits the player and it isn't blocked
+pub struct PlayerHitEvent {
+    pub player: Entity,
+    pub position: Vec3,
+}
+
+// Which of the player's animations should be playing, see player_animation
+#[derive(Clone, Copy, PartialEq)]
+pub enum AnimationState {
+    Idle,
+    Moving,
+    Teleporting,
+    Dying,
+}
+
+// The short sub-state between getting hit and being removed
+pub struct Dying {
+    pub timer: Timer,
+}
+
+// Anything hostile touching a player starts their death sequence,
+// unless they can't be hit right now or a shield takes it
+#[allow(clippy::type_complexity)]
+fn damage_players(
+    mut commands: Commands,
+    mut collision_event: EventReader<CollisionEvent>,
+    player_query: Query<
+        (&Transform, Option<&Shield>),
+        (
+            With<Player>,
+            Without<Dying>,
+            Without<Invulnerable>,
+            Without<Dashing>,
+        ),
+    >,
+    mut player_hit_event: EventWriter<PlayerHitEvent>,
+) {
+    // A player can only be hit once a frame, however many things touch them
+    let mut hit: Vec<Entity> = Vec::new();
+
+    for event in collision_event.iter() {
+        if hit.contains(&event.target) {
+            continue;
+        }
+
+        let (player_transform, shield) = match player_query.get(event.target) {
+            Ok(player) => player,
+            Err(_) => continue,
+        };
+        hit.push(event.target);
+
+        // A shield takes the hit instead
+        if absorb_hit(&mut commands, event.target, shield) {
+            continue;
+        }
+
+        // Start the death sequence if they collide with a block
+        commands
+            .entity(event.target)
+            .insert(Dying {
+                timer: Timer::from_seconds(DEATH_SEQUENCE_SECONDS, false),
+            })
+            .insert(AnimationState::Dying);
+
+        player_hit_event.send(PlayerHitEvent {
+            player: event.target,
+            position: player_transform.translation,
+        });
+    }
+}
+
+// Slow the game down while the player breaks apart, then remove
+// the player and either wait to respawn, spectate or move on to game over
+#[allow(clippy::too_many_arguments)]
+fn dying_system(
     mut commands: Commands,
-    mut player_query: Query<(Entity, &Sprite, &Transform), With<Player>>,
-    collider_query: Query<&Transform, (With<Collidable>, Without<Player>)>,
+    mut dying_query: Query<(Entity, &Controls, &PlayerId, &Transform, &mut Dying)>,
+    alive_query: Query<&Player, Without<Dying>>,
+    mut time_scale: ResMut<TimeScale>,
+    mut lives: ResMut<Lives>,
+    respawn: Res<Respawn>,
+    mut life_lost_event: EventWriter<LifeLostEvent>,
     mut player_death_event: EventWriter<PlayerDeathEvent>,
+    mut start_spectating_event: EventWriter<StartSpectatingEvent>,
+    time: Res<GameTime>,
 ) {
-    if let Ok((player_entity, sprite, player_transform)) = player_query.single_mut() {
-        let player_size = sprite.size;
-
-        for transform in collider_query.iter() {
-            let collision = collide(
-                player_transform.translation,
-                player_size,
-                transform.translation,
-                sprite.size,
-            );
+    for (entity, controls, player_id, transform, mut dying) in dying_query.iter_mut() {
+        time_scale.0 = DEATH_TIME_SCALE;
+        dying.timer.tick(time.delta());
 
-            if let Some(_collision) = collision {
-                // Remove the player if they collide with a block
-                commands.entity(player_entity).despawn();
+        if dying.timer.finished() {
+            commands.entity(entity).despawn();
+            time_scale.0 = 1.0;
 
+            // Lives are shared unless the rules give every player their own.
+            // Without any left a player is out and watches the others, the
+            // run only ends once nobody is left playing.
+            let lives_left = lives.lose(*player_id);
+            let anyone_left = alive_query.iter().count() > 0 || !respawn.pending.is_empty();
+            if lives_left > 0 {
+                life_lost_event.send(LifeLostEvent(*controls));
+            } else if !anyone_left {
                 player_death_event.send(PlayerDeathEvent);
+            } else if *controls != Controls::Bot {
+                start_spectating_event.send(StartSpectatingEvent {
+                    controls: *controls,
+                    position: transform.translation,
+                });
             }
         }
     }
@@ -0,0 +1,283 @@
+// POWER UP CODE
+
+use bevy::prelude::*;
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+use std::time::Duration;
+
+use super::blocks::Block;
+use super::factions::Faction;
+use super::game_rng::GameRng;
+use super::game_time::GameTime;
+use super::lives::Invulnerable;
+use super::player::{Dying, Player};
+use super::reset_game::{Resettable, RunScoped};
+use super::rules::GameRules;
+use super::spatial_grid::{collide_wrapped, SpatialGrid, BUILD_SPATIAL_GRID};
+use super::spawning::SpawnInfo;
+use crate::graphics::particles::ParticleBurstEvent;
+use crate::{AppState, Collidable, WINDOWHEIGHT, WINDOWWIDTH};
+
+const POWERUP_SIZE: f32 = 30.0;
+// Keep power ups away from the edges where blocks come in
+const SPAWN_MARGIN: f32 = 100.0;
+// More than this on screen and the spawn is skipped
+const MAX_POWERUPS: usize = 2;
+const SLOW_MOTION_SECONDS: f32 = 5.0;
+const SLOW_MOTION_MULTIPLIER: f32 = 0.5;
+// A short grace period after the shield breaks so the
+// same block doesn't hit the player on the next frame
+const SHIELD_BREAK_INVULNERABLE_SECONDS: f32 = 0.5;
+// Every block a bomb clears goes up in a burst like this
+const BOMB_PARTICLES: usize = 12;
+const BOMB_PARTICLE_SPEED: f32 = 260.0;
+const BOMB_PARTICLE_SECONDS: f32 = 0.6;
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<PowerUpPickedEvent>()
+            .insert_resource(PowerUpTimer(Timer::from_seconds(15.0, true)))
+            .reset_with(|powerup_timer: &mut PowerUpTimer| powerup_timer.0.reset())
+            .init_resource::<SlowMotion>()
+            .reset_on_restart::<SlowMotion>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(spawn_powerups.system())
+                    .with_system(pickup_powerups.system().after(BUILD_SPATIAL_GRID))
+                    .with_system(slow_motion_system.system()),
+            )
+            .add_system(apply_powerups.system());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PowerUp {
+    // Absorbs the next hit
+    Shield,
+    // Halves block speed for a few seconds
+    SlowMotion,
+    // Clears every block on screen
+    Bomb,
+}
+
+impl PowerUp {
+    fn color(self) -> Color {
+        match self {
+            PowerUp::Shield => Color::rgb(0.3, 0.6, 1.0),
+            PowerUp::SlowMotion => Color::rgb(0.6, 1.0, 0.4),
+            PowerUp::Bomb => Color::rgb(1.0, 0.5, 0.2),
+        }
+    }
+}
+
+impl Distribution<PowerUp> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PowerUp {
+        match rng.gen_range(0..=2) {
+            0 => PowerUp::Shield,
+            1 => PowerUp::SlowMotion,
+            _ => PowerUp::Bomb,
+        }
+    }
+}
+
+pub struct PowerUpPickedEvent {
+    pub player: Entity,
+    pub powerup: PowerUp,
+}
+
+// On the player while they have a shield up
+pub struct Shield;
+
+// Counts down the slow motion power up, None when it isn't running
+#[derive(Default)]
+pub struct SlowMotion {
+    timer: Option<Timer>,
+}
+
+impl SlowMotion {
+    // Multiplies the block velocity
+    pub fn multiplier(&self) -> f32 {
+        if self.timer.is_some() {
+            SLOW_MOTION_MULTIPLIER
+        } else {
+            1.0
+        }
+    }
+}
+
+// Counts down to the next power up spawn
+struct PowerUpTimer(Timer);
+
+fn spawn_powerups(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut powerup_timer: ResMut<PowerUpTimer>,
+    powerup_query: Query<&PowerUp>,
+    rules: Res<GameRules>,
+    mut game_rng: ResMut<GameRng>,
+    time: Res<GameTime>,
+) {
+    powerup_timer
+        .0
+        .set_duration(Duration::from_secs_f32(rules.powerup_interval));
+    powerup_timer.0.tick(time.delta());
+
+    if !powerup_timer.0.just_finished() || powerup_query.iter().count() >= MAX_POWERUPS {
+        return;
+    }
+
+    let powerup: PowerUp = game_rng.gen();
+    let x = game_rng.gen_range(-WINDOWWIDTH / 2.0 + SPAWN_MARGIN..WINDOWWIDTH / 2.0 - SPAWN_MARGIN);
+    let y =
+        game_rng.gen_range(-WINDOWHEIGHT / 2.0 + SPAWN_MARGIN..WINDOWHEIGHT / 2.0 - SPAWN_MARGIN);
+
+    spawn_powerup(&mut commands, &mut materials, powerup, Vec2::new(x, y));
+}
+
+// Also used to drop power ups as rewards, anywhere near
+// the edge is pulled in to where the player can reach it
+pub fn spawn_powerup(
+    commands: &mut Commands,
+    materials: &mut Assets<ColorMaterial>,
+    powerup: PowerUp,
+    position: Vec2,
+) {
+    let x = position.x.clamp(
+        -WINDOWWIDTH / 2.0 + SPAWN_MARGIN,
+        WINDOWWIDTH / 2.0 - SPAWN_MARGIN,
+    );
+    let y = position.y.clamp(
+        -WINDOWHEIGHT / 2.0 + SPAWN_MARGIN,
+        WINDOWHEIGHT / 2.0 - SPAWN_MARGIN,
+    );
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(Vec2::new(POWERUP_SIZE, POWERUP_SIZE)),
+            material: materials.add(powerup.color().into()),
+            transform: Transform::from_xyz(x, y, 0.5),
+            ..Default::default()
+        })
+        .insert(powerup)
+        .insert(Faction::Neutral)
+        .insert(Collidable::pickup())
+        .insert(RunScoped);
+}
+
+// Power ups only look for players in the grid, and the first player
+// found takes it so the others can't also take it
+fn pickup_powerups(
+    mut commands: Commands,
+    grid: Res<SpatialGrid>,
+    player_query: Query<(&Transform, &Sprite), (With<Player>, Without<Dying>)>,
+    powerup_query: Query<(Entity, &PowerUp, &Collidable, &Transform, &Sprite)>,
+    mut powerup_picked_event: EventWriter<PowerUpPickedEvent>,
+) {
+    for (entity, powerup, collidable, transform, sprite) in powerup_query.iter() {
+        let nearby = grid.nearby(
+            transform.translation.truncate(),
+            sprite.size,
+            collidable.mask,
+        );
+
+        let player = nearby.into_iter().find(|player| {
+            player_query
+                .get(*player)
+                .map_or(false, |(player_transform, player_sprite)| {
+                    collide_wrapped(
+                        player_transform.translation,
+                        player_sprite.size,
+                        transform.translation,
+                        sprite.size,
+                    )
+                    .is_some()
+                })
+        });
+
+        if let Some(player) = player {
+            commands.entity(entity).despawn();
+            powerup_picked_event.send(PowerUpPickedEvent {
+                player,
+                powerup: *powerup,
+            });
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_powerups(
+    mut commands: Commands,
+    mut powerup_picked_event: EventReader<PowerUpPickedEvent>,
+    mut slow_motion: ResMut<SlowMotion>,
+    block_query: Query<(Entity, &Transform), With<Block>>,
+    shield_query: Query<Entity, With<Shield>>,
+    mut spawn_positions_query: Query<&mut SpawnInfo>,
+    rules: Res<GameRules>,
+    mut particle_burst_event: EventWriter<ParticleBurstEvent>,
+) {
+    for event in powerup_picked_event.iter() {
+        match event.powerup {
+            // The shield goes to whoever picked it up
+            PowerUp::Shield => {
+                commands.entity(event.player).insert(Shield);
+            }
+            PowerUp::SlowMotion => {
+                slow_motion.timer = Some(Timer::from_seconds(SLOW_MOTION_SECONDS, false));
+            }
+            PowerUp::Bomb => {
+                for (entity, transform) in block_query.iter() {
+                    commands.entity(entity).despawn();
+                    particle_burst_event.send(ParticleBurstEvent {
+                        position: transform.translation,
+                        color: PowerUp::Bomb.color(),
+                        count: BOMB_PARTICLES,
+                        speed: BOMB_PARTICLE_SPEED,
+                        seconds: BOMB_PARTICLE_SECONDS,
+                    });
+                }
+
+                // Every spawn position is free again
+                for mut spawn_position in spawn_positions_query.iter_mut() {
+                    spawn_position.spawned = false;
+                }
+
+                // The blast takes out everyone else's shields too
+                if rules.friendly_fire {
+                    for entity in shield_query.iter() {
+                        if entity != event.player {
+                            commands.entity(entity).remove::<Shield>();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn slow_motion_system(mut slow_motion: ResMut<SlowMotion>, time: Res<GameTime>) {
+    let finished = match &mut slow_motion.timer {
+        Some(timer) => timer.tick(time.delta()).finished(),
+        None => false,
+    };
+
+    if finished {
+        slow_motion.timer = None;
+    }
+}
+
+// Called by the player collision code, returns whether the hit was absorbed
+pub fn absorb_hit(commands: &mut Commands, player: Entity, shield: Option<&Shield>) -> bool {
+    if shield.is_none() {
+        return false;
+    }
+
+    commands
+        .entity(player)
+        .remove::<Shield>()
+        .insert(Invulnerable {
+            timer: Timer::from_seconds(SHIELD_BREAK_INVULNERABLE_SECONDS, false),
+        });
+    true
+}
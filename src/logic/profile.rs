@@ -0,0 +1,311 @@
+// PROFILE CODE
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+use super::challenges::ChallengeProgress;
+use super::daily::DailyChallenge;
+use super::game_rng::GameRng;
+use super::mutators::ActiveMutators;
+use super::player::{Player, PlayerDeathEvent};
+use super::projectiles::Shooter;
+use super::reset_game::Resettable;
+use super::rules::GameRules;
+use super::sealed_save::{load_sealed, save_sealed};
+use super::stats::RunStats;
+use super::upgrades::RunUpgrades;
+use super::versus::VersusMatch;
+use super::weekly::WeeklyChallenge;
+use crate::graphics::score::Score;
+
+// Sealed so it can't just be edited, see sealed_save
+const PROFILE_FILE: &str = "profile.sav";
+const LEGACY_PROFILE_FILE: &str = "profile.ron";
+// Score points needed for a single coin
+const POINTS_PER_COIN: u32 = 10;
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(Profile::load())
+            .init_resource::<NewBest>()
+            .reset_on_restart::<NewBest>()
+            .add_system(award_coins.system())
+            .add_system(record_personal_best.system())
+            .add_system(apply_unlocks.system());
+    }
+}
+
+// Where everything the game saves goes
+pub fn save_directory() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("bevy_block_game")
+}
+
+// Permanent upgrades bought with coins. Each one can
+// require another unlock first, which makes up the tree.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Unlock {
+    ExtraLife,
+    SecondExtraLife,
+    QuickFeet,
+    QuickerFeet,
+    LongTeleport,
+    // The weapon every run starts with
+    RapidFire,
+    HeavyRounds,
+}
+
+impl Unlock {
+    // In tree order, every unlock comes after the one it requires
+    pub const ALL: [Unlock; 7] = [
+        Unlock::ExtraLife,
+        Unlock::SecondExtraLife,
+        Unlock::QuickFeet,
+        Unlock::QuickerFeet,
+        Unlock::LongTeleport,
+        Unlock::RapidFire,
+        Unlock::HeavyRounds,
+    ];
+
+    pub fn cost(self) -> u32 {
+        match self {
+            Unlock::ExtraLife => 50,
+            Unlock::SecondExtraLife => 150,
+            Unlock::QuickFeet => 40,
+            Unlock::QuickerFeet => 120,
+            Unlock::LongTeleport => 80,
+            Unlock::RapidFire => 60,
+            Unlock::HeavyRounds => 160,
+        }
+    }
+
+    pub fn requires(self) -> Option<Unlock> {
+        match self {
+            Unlock::ExtraLife | Unlock::QuickFeet | Unlock::RapidFire => None,
+            Unlock::SecondExtraLife => Some(Unlock::ExtraLife),
+            Unlock::QuickerFeet | Unlock::LongTeleport => Some(Unlock::QuickFeet),
+            Unlock::HeavyRounds => Some(Unlock::RapidFire),
+        }
+    }
+
+    // How far down the tree the unlock is, roots are zero
+    pub fn depth(self) -> usize {
+        match self.requires() {
+            Some(parent) => parent.depth() + 1,
+            None => 0,
+        }
+    }
+}
+
+impl fmt::Display for Unlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unlock::ExtraLife => write!(f, "Extra life"),
+            Unlock::SecondExtraLife => write!(f, "Second extra life"),
+            Unlock::QuickFeet => write!(f, "Quick feet, +10% move speed"),
+            Unlock::QuickerFeet => write!(f, "Quicker feet, +10% move speed"),
+            Unlock::LongTeleport => write!(f, "Long teleport, +25% distance"),
+            Unlock::RapidFire => write!(f, "Rapid fire, -20% fire cooldown"),
+            Unlock::HeavyRounds => write!(f, "Heavy rounds, +1 bullet damage"),
+        }
+    }
+}
+
+// The best run in one mode on one difficulty, and on one seed for seeded runs.
+// Kept apart so an easy run never stands in for a hard one.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PersonalBest {
+    // Solo, co-op, versus, daily, weekly or the id of a challenge
+    pub mode: String,
+    pub difficulty: String,
+    pub seed: Option<u64>,
+    pub score: u32,
+    pub survival_seconds: f32,
+}
+
+// Which personal bests the last run beat, for the game over screen
+#[derive(Default)]
+pub struct NewBest {
+    pub score: bool,
+    pub survival_seconds: bool,
+}
+
+// Everything that carries over between runs and launches
+#[derive(Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub coins: u32,
+    pub unlocks: Vec<Unlock>,
+    // Ids of every challenge finished at least once
+    #[serde(default)]
+    pub completed_challenges: Vec<String>,
+    #[serde(default)]
+    pub personal_bests: Vec<PersonalBest>,
+}
+
+impl Profile {
+    // A missing or broken profile starts over from nothing
+    pub fn load() -> Self {
+        load_sealed(PROFILE_FILE, LEGACY_PROFILE_FILE, "profile").unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_sealed(self, PROFILE_FILE, "profile");
+    }
+
+    pub fn has(&self, unlock: Unlock) -> bool {
+        self.unlocks.contains(&unlock)
+    }
+
+    pub fn can_buy(&self, unlock: Unlock) -> bool {
+        let parent_owned = unlock.requires().map_or(true, |parent| self.has(parent));
+        !self.has(unlock) && parent_owned && self.coins >= unlock.cost()
+    }
+
+    // Returns whether the unlock was bought
+    pub fn buy(&mut self, unlock: Unlock) -> bool {
+        if !self.can_buy(unlock) {
+            return false;
+        }
+
+        self.coins -= unlock.cost();
+        self.unlocks.push(unlock);
+        true
+    }
+
+    // Keeps the better score and time separately, they don't have to come
+    // from the same run. The first run in a category sets it without beating it.
+    pub fn record_run(&mut self, run: PersonalBest) -> NewBest {
+        let best = self.personal_bests.iter_mut().find(|best| {
+            best.mode == run.mode && best.difficulty == run.difficulty && best.seed == run.seed
+        });
+
+        let best = match best {
+            Some(best) => best,
+            None => {
+                self.personal_bests.push(run);
+                return NewBest::default();
+            }
+        };
+
+        let new_best = NewBest {
+            score: run.score > best.score,
+            survival_seconds: run.survival_seconds > best.survival_seconds,
+        };
+        best.score = best.score.max(run.score);
+        best.survival_seconds = best.survival_seconds.max(run.survival_seconds);
+        new_best
+    }
+
+    pub fn bonus_lives(&self) -> u32 {
+        self.has(Unlock::ExtraLife) as u32 + self.has(Unlock::SecondExtraLife) as u32
+    }
+
+    fn speed_multiplier(&self) -> f32 {
+        let stacks = self.has(Unlock::QuickFeet) as i32 + self.has(Unlock::QuickerFeet) as i32;
+        1.1_f32.powi(stacks)
+    }
+
+    fn teleport_multiplier(&self) -> f32 {
+        if self.has(Unlock::LongTeleport) {
+            1.25
+        } else {
+            1.0
+        }
+    }
+
+    fn fire_cooldown_multiplier(&self) -> f32 {
+        if self.has(Unlock::RapidFire) {
+            0.8
+        } else {
+            1.0
+        }
+    }
+
+    fn bonus_damage(&self) -> u32 {
+        self.has(Unlock::HeavyRounds) as u32
+    }
+}
+
+// Turn the final score into coins once the run is over
+fn award_coins(
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    mut profile: ResMut<Profile>,
+    score: Res<Score>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+) {
+    for _event in player_death_event.iter() {
+        profile.coins += score.total(&mutators, &upgrades) / POINTS_PER_COIN;
+        profile.save();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_personal_best(
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    mut profile: ResMut<Profile>,
+    mut new_best: ResMut<NewBest>,
+    score: Res<Score>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<RunUpgrades>,
+    run_stats: Res<RunStats>,
+    rules: Res<GameRules>,
+    challenge_progress: Res<ChallengeProgress>,
+    daily: Res<DailyChallenge>,
+    weekly: Res<WeeklyChallenge>,
+    versus: Res<VersusMatch>,
+    game_rng: Res<GameRng>,
+) {
+    for _event in player_death_event.iter() {
+        // Each day's and week's seed gets its own entry
+        let mode = match challenge_progress.challenge() {
+            Some(challenge) => challenge.id.to_string(),
+            None if daily.is_active() => "daily".to_string(),
+            None if weekly.is_active() => "weekly".to_string(),
+            None if versus.active => "versus".to_string(),
+            None if score.players.len() > 1 => "co-op".to_string(),
+            None => "solo".to_string(),
+        };
+
+        *new_best = profile.record_run(PersonalBest {
+            mode,
+            difficulty: rules.preset.to_string(),
+            seed: game_rng.fixed_seed(),
+            score: score.total(&mutators, &upgrades),
+            survival_seconds: run_stats.survival_seconds,
+        });
+        profile.save();
+    }
+}
+
+// The weapon is armed a frame after the player spawns, so it is upgraded
+// once it shows up
+fn apply_unlocks(
+    profile: Res<Profile>,
+    daily: Res<DailyChallenge>,
+    mut player_query: Query<&mut Player, Added<Player>>,
+    mut shooter_query: Query<&mut Shooter, Added<Shooter>>,
+) {
+    if daily.is_active() {
+        return;
+    }
+
+    for mut player in player_query.iter_mut() {
+        player.speed *= profile.speed_multiplier();
+        player.teleport_distance *= profile.teleport_multiplier();
+    }
+
+    for mut shooter in shooter_query.iter_mut() {
+        let cooldown = shooter
+            .cooldown
+            .duration()
+            .mul_f32(profile.fire_cooldown_multiplier());
+        shooter.cooldown.set_duration(cooldown);
+        shooter.damage += profile.bonus_damage();
+    }
+}
@@ -0,0 +1,360 @@
+// PROJECTILE CODE
+
+use bevy::prelude::*;
+
+use super::block_kinds::{leading_side, Armored, BlockKind, BlockSize, Mirror};
+use super::blocks::{Block, BlockDestroyedEvent, SpawnedFrom};
+use super::bot::BotBrain;
+use super::factions::{CollisionEvent, Damage, Faction, DETECT_COLLISIONS};
+use super::game_time::GameTime;
+use super::health::Health;
+use super::input_map::{Action, InputMap};
+use super::player::{ControlScheme, Controls, Dying, Player};
+use super::reset_game::RunScoped;
+use super::upgrades::RunUpgrades;
+use super::versus::VersusMatch;
+use crate::graphics::camera::CameraRig;
+use crate::{AppState, Collidable, Velocity, WINDOWHEIGHT, WINDOWWIDTH};
+
+const BULLET_SPEED: f32 = 900.0;
+const BULLET_SIZE: f32 = 8.0;
+// Seconds between shots
+const FIRE_COOLDOWN: f32 = 0.25;
+// Radians between the bullets of a shot with extra projectiles
+const SPREAD_ANGLE: f32 = 0.15;
+// Health each bullet takes off a block, before unlocks and upgrades
+pub const BULLET_DAMAGE: u32 = 1;
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<BulletDeflectedEvent>()
+            .add_system(arm_players.system())
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(fire_bullets.system())
+                    .with_system(move_bullets.system())
+                    .with_system(resolve_bullet_hits.system().after(DETECT_COLLISIONS)),
+            );
+    }
+}
+
+// Lets a player shoot, facing is the last way they moved
+pub struct Shooter {
+    pub facing: Vec2,
+    pub cooldown: Timer,
+    // Whether each trigger is toggled on, for the toggle fire mode.
+    // The button is F or a gamepad's face button, the mouse is a click.
+    pub button_toggled: bool,
+    pub mouse_toggled: bool,
+    // Health each of their bullets takes off
+    pub damage: u32,
+}
+
+// Who fired it, turrets don't count as anyone
+pub struct Bullet {
+    pub owner: Option<Controls>,
+}
+
+// Player bullets hit blocks, hostile ones fly through them and hit players
+fn bullet_color(faction: Faction) -> Color {
+    match faction {
+        Faction::Player => Color::rgb(1.0, 0.9, 0.3),
+        Faction::Hostile => Color::rgb(1.0, 0.2, 0.2),
+        Faction::Neutral => Color::rgb(0.6, 0.6, 0.6),
+    }
+}
+
+pub fn spawn_bullet(
+    commands: &mut Commands,
+    materials: &mut Assets<ColorMaterial>,
+    position: Vec3,
+    velocity: Vec2,
+    owner: Option<Controls>,
+    faction: Faction,
+    damage: u32,
+) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(Vec2::new(BULLET_SIZE, BULLET_SIZE)),
+            material: materials.add(bullet_color(faction).into()),
+            transform: Transform::from_translation(position),
+            ..Default::default()
+        })
+        .insert(Bullet { owner })
+        .insert(faction)
+        .insert(Damage(damage))
+        .insert(Collidable::bullet())
+        .insert(RunScoped)
+        .insert(Velocity(velocity));
+}
+
+// Sent when a bullet bounces off an armored block
+pub struct BulletDeflectedEvent {
+    pub position: Vec3,
+}
+
+fn arm_players(mut commands: Commands, player_query: Query<Entity, Added<Player>>) {
+    for entity in player_query.iter() {
+        let mut cooldown = Timer::from_seconds(FIRE_COOLDOWN, false);
+        // Ready to fire straight away
+        cooldown.tick(cooldown.duration());
+
+        commands.entity(entity).insert(Shooter {
+            facing: Vec2::new(0.0, 1.0),
+            cooldown,
+            button_toggled: false,
+            mouse_toggled: false,
+            damage: BULLET_DAMAGE,
+        });
+    }
+}
+
+// F, O for the second keyboard player or a gamepad's left face button
+// fires the way the player faces,
+// a click fires towards the cursor. Whether that is per press, while
+// held or toggled on and off comes from the fire action mode.
+#[allow(clippy::too_many_arguments)]
+fn fire_bullets(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    windows: Res<Windows>,
+    camera_rig: Res<CameraRig>,
+    control_scheme: Res<ControlScheme>,
+    input_map: Res<InputMap>,
+    versus: Res<VersusMatch>,
+    upgrades: Res<RunUpgrades>,
+    mut player_query: Query<
+        (
+            &Controls,
+            Option<&BotBrain>,
+            &Velocity,
+            &Transform,
+            &mut Shooter,
+        ),
+        (With<Player>, Without<Dying>),
+    >,
+    time: Res<GameTime>,
+) {
+    let cursor = camera_rig.cursor_world_position(&windows);
+    // Mouse controls move with the left button, so they fire with the right
+    let fire_button = match *control_scheme {
+        ControlScheme::Keyboard => MouseButton::Left,
+        ControlScheme::Mouse => MouseButton::Right,
+    };
+
+    for (controls, bot, velocity, transform, mut shooter) in player_query.iter_mut() {
+        shooter.cooldown.tick(time.delta());
+
+        if velocity.0 != Vec2::ZERO {
+            shooter.facing = velocity.0.normalize();
+        }
+
+        let fire_mode = input_map.mode(Action::Fire);
+        let shooter = &mut *shooter;
+        // In versus the mouse belongs to whoever is launching blocks
+        let mouse_firing = *controls == Controls::Primary
+            && !versus.active
+            && fire_mode.active(
+                mouse_input.pressed(fire_button),
+                mouse_input.just_pressed(fire_button),
+                &mut shooter.mouse_toggled,
+            );
+        let button_firing = match controls {
+            Controls::Primary => {
+                input_map.active(&keyboard_input, Action::Fire, &mut shooter.button_toggled)
+            }
+            Controls::Gamepad(gamepad) => {
+                let button = GamepadButton(*gamepad, GamepadButtonType::West);
+                fire_mode.active(
+                    gamepad_buttons.pressed(button),
+                    gamepad_buttons.just_pressed(button),
+                    &mut shooter.button_toggled,
+                )
+            }
+            Controls::SecondKeyboard => fire_mode.active(
+                keyboard_input.pressed(KeyCode::O),
+                keyboard_input.just_pressed(KeyCode::O),
+                &mut shooter.button_toggled,
+            ),
+            Controls::Bot => false,
+        };
+
+        let position = transform.translation.truncate();
+        let aim = match controls {
+            // The bot just keeps firing at whatever it is aiming at
+            Controls::Bot => bot.and_then(|bot| bot.aim),
+            _ if mouse_firing => cursor
+                .map(|cursor| cursor - position)
+                .filter(|to_cursor| *to_cursor != Vec2::ZERO)
+                .map(|to_cursor| to_cursor.normalize()),
+            _ if button_firing => Some(shooter.facing),
+            _ => None,
+        };
+
+        let direction = match aim {
+            Some(direction) if shooter.cooldown.finished() => direction,
+            _ => continue,
+        };
+        shooter.cooldown.reset();
+
+        // Extra projectiles fan out evenly either side of the aim
+        let projectiles = upgrades.projectiles();
+        for index in 0..projectiles {
+            let angle = (index as f32 - (projectiles - 1) as f32 / 2.0) * SPREAD_ANGLE;
+            let direction = Quat::from_rotation_z(angle)
+                .mul_vec3(direction.extend(0.0))
+                .truncate();
+
+            spawn_bullet(
+                &mut commands,
+                &mut materials,
+                transform.translation,
+                direction * BULLET_SPEED,
+                Some(*controls),
+                Faction::Player,
+                shooter.damage + upgrades.bonus_damage(),
+            );
+        }
+    }
+}
+
+// Bullets fly straight and are gone once they leave the screen
+fn move_bullets(
+    mut commands: Commands,
+    mut bullet_query: Query<(Entity, &Velocity, &mut Transform), With<Bullet>>,
+    time: Res<GameTime>,
+) {
+    for (entity, velocity, mut transform) in bullet_query.iter_mut() {
+        transform.translation += (velocity.0 * time.delta_seconds()).extend(0.0);
+
+        let off_screen = transform.translation.x.abs() > WINDOWWIDTH / 2.0
+            || transform.translation.y.abs() > WINDOWHEIGHT / 2.0;
+        if off_screen {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Bounces the velocity off a side with this normal. Only on the way in,
+// so a bullet still overlapping next frame doesn't turn back around.
+fn bounce(velocity: &mut Vec2, normal: Vec2) -> bool {
+    if velocity.dot(normal) >= 0.0 {
+        return false;
+    }
+
+    *velocity -= 2.0 * velocity.dot(normal) * normal;
+    true
+}
+
+// What happens to a bullet that hits something. Blocks may bounce it
+// back or soak it up, anything else it hits just uses it up.
+#[allow(clippy::type_complexity)]
+fn resolve_bullet_hits(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut collision_event: EventReader<CollisionEvent>,
+    mut bullet_query: Query<
+        (
+            &Bullet,
+            &mut Faction,
+            &Transform,
+            &mut Velocity,
+            &Handle<ColorMaterial>,
+        ),
+        Without<Block>,
+    >,
+    mut block_query: Query<
+        (
+            &Transform,
+            &Velocity,
+            &BlockKind,
+            &BlockSize,
+            Option<&SpawnedFrom>,
+            Option<&mut Health>,
+            Option<&Armored>,
+            Option<&Mirror>,
+        ),
+        With<Block>,
+    >,
+    mut block_destroyed_event: EventWriter<BlockDestroyedEvent>,
+    mut bullet_deflected_event: EventWriter<BulletDeflectedEvent>,
+) {
+    // A bullet only hits one thing, and a block can only be
+    // destroyed once even if two bullets hit it
+    let mut spent: Vec<Entity> = Vec::new();
+    let mut destroyed: Vec<Entity> = Vec::new();
+
+    for event in collision_event.iter() {
+        if spent.contains(&event.attacker) || destroyed.contains(&event.target) {
+            continue;
+        }
+
+        let (bullet, mut faction, bullet_transform, mut bullet_velocity, material) =
+            match bullet_query.get_mut(event.attacker) {
+                Ok(bullet) => bullet,
+                Err(_) => continue,
+            };
+        spent.push(event.attacker);
+
+        let (block_transform, velocity, kind, size, spawned_from, health, armored, mirror) =
+            match block_query.get_mut(event.target) {
+                Ok(block) => block,
+                // Players and turrets deal with being hit themselves
+                Err(_) => {
+                    commands.entity(event.attacker).despawn();
+                    continue;
+                }
+            };
+
+        // Bullets bounce harmlessly off the armored side
+        if armored.is_some() && event.side == leading_side(velocity.0) {
+            if bounce(&mut bullet_velocity.0, event.side.vector()) {
+                bullet_deflected_event.send(BulletDeflectedEvent {
+                    position: bullet_transform.translation,
+                });
+            }
+            continue;
+        }
+
+        // A mirror sends the bullet back as a hazard
+        if mirror.is_some() {
+            if bounce(&mut bullet_velocity.0, event.side.vector()) {
+                *faction = Faction::Hostile;
+                if let Some(material) = materials.get_mut(material) {
+                    material.color = bullet_color(*faction);
+                }
+                bullet_deflected_event.send(BulletDeflectedEvent {
+                    position: bullet_transform.translation,
+                });
+            }
+            continue;
+        }
+
+        commands.entity(event.attacker).despawn();
+
+        // Blocks with health left soak up the bullet
+        if let Some(mut health) = health {
+            if !health.damage(event.damage) {
+                continue;
+            }
+        }
+
+        destroyed.push(event.target);
+        commands.entity(event.target).despawn();
+
+        block_destroyed_event.send(BlockDestroyedEvent {
+            block: event.target,
+            position: block_transform.translation,
+            velocity: velocity.0,
+            kind: *kind,
+            size: *size,
+            spawned_from: spawned_from.map(|spawned_from| spawned_from.0),
+            destroyed_by: bullet.owner,
+        });
+    }
+}
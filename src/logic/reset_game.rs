@@ -1,8 +1,11 @@
-use super::blocks::SpawnBlockEvent;
-use crate::graphics::score::Score;
-use crate::logic::blocks::Block;
-use crate::logic::player::Player;
+use crate::graphics::kill_cam::KillCamFinishedEvent;
+use crate::logic::bot::BotSettings;
+use crate::logic::player::Controls;
 use crate::logic::spawning::SpawnInfo;
+use crate::logic::time_scale::{RampTimeScaleEvent, TimeScale};
+use crate::settings::GameSettings;
+use crate::AppState;
+use bevy::app::{Events, ManualEventReader};
 use bevy::prelude::*;
 
 pub struct ResetGamePlugin;
@@ -10,72 +13,190 @@ pub struct ResetGamePlugin;
 impl Plugin for ResetGamePlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_event::<ResetGameEvent>()
-            .add_system(send_reset_game_event.system())
+            .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(start_run.system()))
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(clear_last_run.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(send_reset_game_event.system())
+                    .with_system(end_run.system()),
+            )
             .add_system(reset_game.system())
-            .add_system(reset_player.system());
+            .add_system(reset_player.system())
+            .add_system(despawn_run_scoped.system())
+            .add_system(run_resets.exclusive_system().at_end());
     }
 }
 
 pub struct ResetGameEvent;
 
+// Every resource that goes back to how it started when a run restarts.
+// Plugins register their resources as they add them, so a new one
+// can't quietly carry state over into the next run.
+#[derive(Default)]
+pub struct ResetRegistry {
+    resets: Vec<Box<dyn Fn(&mut World) + Send + Sync>>,
+    reader: ManualEventReader<ResetGameEvent>,
+}
+
+pub trait Resettable {
+    // Put the resource back to its default on every reset
+    fn reset_on_restart<R: Default + Send + Sync + 'static>(&mut self) -> &mut Self;
+
+    // Reset only part of the resource, for ones that keep something between runs
+    fn reset_with<R: Send + Sync + 'static>(
+        &mut self,
+        reset: impl Fn(&mut R) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    // For resets that reach past a single resource
+    fn reset_world(&mut self, reset: impl Fn(&mut World) + Send + Sync + 'static) -> &mut Self;
+}
+
+impl Resettable for AppBuilder {
+    fn reset_on_restart<R: Default + Send + Sync + 'static>(&mut self) -> &mut Self {
+        self.reset_with(|resource: &mut R| *resource = R::default())
+    }
+
+    fn reset_with<R: Send + Sync + 'static>(
+        &mut self,
+        reset: impl Fn(&mut R) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.reset_world(move |world: &mut World| {
+            if let Some(mut resource) = world.get_resource_mut::<R>() {
+                reset(&mut resource);
+            }
+        })
+    }
+
+    fn reset_world(&mut self, reset: impl Fn(&mut World) + Send + Sync + 'static) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(ResetRegistry::default)
+            .resets
+            .push(Box::new(reset));
+        self
+    }
+}
+
+// On anything spawned during a run, so it all goes away when the next run
+// starts without every feature writing its own cleanup
+pub struct RunScoped;
+
+// Every run starts from a reset game
+fn start_run(mut reset_game_event: EventWriter<ResetGameEvent>) {
+    reset_game_event.send(ResetGameEvent);
+}
+
+// Restart in the middle of a run
 fn send_reset_game_event(
     keyboard_input: Res<Input<KeyCode>>,
     mut reset_game_event: EventWriter<ResetGameEvent>,
 ) {
-    if keyboard_input.pressed(KeyCode::R) {
+    if keyboard_input.just_pressed(KeyCode::R) {
         reset_game_event.send(ResetGameEvent)
     }
 }
 
+// The run is over once the player is gone and the kill cam is done
+fn end_run(
+    mut kill_cam_finished_event: EventReader<KillCamFinishedEvent>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if kill_cam_finished_event.iter().count() > 0 {
+        let _ = app_state.set(AppState::GameOver);
+    }
+}
+
 fn reset_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    mut player_query: Query<&mut Transform, With<Player>>,
     mut reset_game_event: EventReader<ResetGameEvent>,
+    mut time_scale: ResMut<TimeScale>,
+    bot_settings: Res<BotSettings>,
 ) {
     for _event in reset_game_event.iter() {
-        // Reset player position to 0,0
-        if let Ok(mut transform) = player_query.single_mut() {
-            transform.translation.x = 0.0;
-            transform.translation.y = 0.0;
-        } else {
-            // If the player is dead, spawn a new one
-            super::player::spawn_player(&mut commands, &asset_server, &mut materials)
+        time_scale.0 = 1.0;
+
+        // Every run starts with just the primary player at 0,0, the old
+        // players are run scoped and co-op players drop back in when they are ready
+        super::player::spawn_player(
+            &mut commands,
+            &asset_server,
+            &mut materials,
+            &settings,
+            Controls::Primary,
+        );
+
+        // The bot joins every run alongside them and shares the lives
+        if bot_settings.enabled {
+            super::player::spawn_player(
+                &mut commands,
+                &asset_server,
+                &mut materials,
+                &settings,
+                Controls::Bot,
+            );
         }
     }
 }
 
 fn reset_game(
     mut reset_game_event: EventReader<ResetGameEvent>,
-    block_query: Query<Entity, With<Block>>,
-    mut commands: Commands,
-    mut score_query: Query<&mut Score>,
-    mut spawn_positions_query: Query<(Entity, &mut SpawnInfo)>,
-    mut spawn_event: EventWriter<SpawnBlockEvent>,
+    mut spawn_positions_query: Query<&mut SpawnInfo>,
+    mut ramp_event: EventWriter<RampTimeScaleEvent>,
 ) {
     for _event in reset_game_event.iter() {
-        // Despawn all of the blocks
-        for entity in block_query.iter() {
-            commands.entity(entity).despawn();
+        // Ease into the new run instead of starting at full speed
+        ramp_event.send(RampTimeScaleEvent);
+
+        // Reset the spawn positions, the first wave spawns the blocks
+        for mut spawn_positions in spawn_positions_query.iter_mut() {
+            spawn_positions.spawned = false;
         }
+    }
+}
 
-        // Reset the score
-        if let Ok(mut score) = score_query.single_mut() {
-            // NOTE It adds one automatically in score system
-            score.active = true;
-            score.value = -1;
+// Players, blocks, bullets and effects from the last run. Anything spawned
+// in reaction to the same reset isn't in the world yet, so it stays.
+fn despawn_run_scoped(
+    mut commands: Commands,
+    mut reset_game_event: EventReader<ResetGameEvent>,
+    run_scoped_query: Query<Entity, With<RunScoped>>,
+) {
+    for _event in reset_game_event.iter() {
+        for entity in run_scoped_query.iter() {
+            commands.entity(entity).despawn_recursive();
         }
+    }
+}
 
-        // Reset the spawn positions
-        let mut counter = 0;
-        for (entity, mut spawn_positions) in spawn_positions_query.iter_mut() {
-            spawn_positions.spawned = false;
+// Going back to the menu from the game over screen leaves nothing of
+// the last run behind it. Resources are still only reset when a run starts.
+fn clear_last_run(mut commands: Commands, run_scoped_query: Query<Entity, With<RunScoped>>) {
+    for entity in run_scoped_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
 
-            if counter < 6 {
-                spawn_event.send(SpawnBlockEvent(entity));
-                counter += 1;
-            }
+// Runs at the end of the frame, after everything that sent a reset
+fn run_resets(world: &mut World) {
+    let mut registry = match world.remove_resource::<ResetRegistry>() {
+        Some(registry) => registry,
+        None => return,
+    };
+
+    let resetting = world
+        .get_resource::<Events<ResetGameEvent>>()
+        .map_or(false, |events| registry.reader.iter(events).count() > 0);
+
+    if resetting {
+        for reset in registry.resets.iter() {
+            reset(world);
         }
     }
+
+    world.insert_resource(registry);
 }
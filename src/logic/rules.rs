@@ -0,0 +1,180 @@
+// RULES CODE
+
+use bevy::prelude::*;
+use std::fmt;
+
+use super::mutators::{ActiveMutators, Mutator};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<GameRules>()
+            .init_resource::<PlayerSetup>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(restore_player_setup.system()),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DifficultyPreset {
+    Easy,
+    Normal,
+    Hard,
+    Nightmare,
+    // Any of the knobs were changed by hand
+    Custom,
+}
+
+impl DifficultyPreset {
+    // The presets in order from easiest to hardest
+    pub const ALL: [DifficultyPreset; 4] = [
+        DifficultyPreset::Easy,
+        DifficultyPreset::Normal,
+        DifficultyPreset::Hard,
+        DifficultyPreset::Nightmare,
+    ];
+}
+
+impl fmt::Display for DifficultyPreset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DifficultyPreset::Easy => write!(f, "Easy"),
+            DifficultyPreset::Normal => write!(f, "Normal"),
+            DifficultyPreset::Hard => write!(f, "Hard"),
+            DifficultyPreset::Nightmare => write!(f, "Nightmare"),
+            DifficultyPreset::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+// How points are counted when more than one person is playing
+#[derive(Clone, Copy, PartialEq)]
+pub enum CoopScoring {
+    // Everyone adds to the same score
+    Shared,
+    // Every player has their own score and they are compared at the end
+    Split,
+}
+
+impl fmt::Display for CoopScoring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoopScoring::Shared => write!(f, "Shared"),
+            CoopScoring::Split => write!(f, "Split"),
+        }
+    }
+}
+
+// Whether co-op players and the bot draw on one pool of lives
+#[derive(Clone, Copy, PartialEq)]
+pub enum CoopLives {
+    // Every death takes from the same lives, whoever it was
+    Shared,
+    // Every player has the full lives, a player that joins part way
+    // through included
+    PerPlayer,
+}
+
+impl fmt::Display for CoopLives {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoopLives::Shared => write!(f, "Shared"),
+            CoopLives::PerPlayer => write!(f, "Per player"),
+        }
+    }
+}
+
+// The knobs that decide how hard a run is
+#[derive(Clone, Copy)]
+pub struct GameRules {
+    pub preset: DifficultyPreset,
+    // Seconds between runtime block spawns
+    pub spawn_interval: f32,
+    // Multiplies the base block velocity
+    pub speed_multiplier: f32,
+    pub lives: u32,
+    // Seconds between power up spawns
+    pub powerup_interval: f32,
+    // Let the game ease off or tighten up the spawn rate based on how
+    // the player is doing. Competitive players will want this off.
+    pub adaptive: bool,
+    pub coop_scoring: CoopScoring,
+    pub coop_lives: CoopLives,
+    // Co-op players push each other apart instead of overlapping
+    pub player_collision: bool,
+    // One player's bomb also breaks the other players' shields
+    pub friendly_fire: bool,
+    // Blocks never spawn closer than this to a player, in pixels
+    pub safe_spawn_radius: f32,
+}
+
+// Runs start out on normal
+impl FromWorld for GameRules {
+    fn from_world(world: &mut World) -> Self {
+        let settings = world
+            .get_resource::<GameSettings>()
+            .expect("The settings are inserted before the rules.");
+        GameRules::from_preset(DifficultyPreset::Normal, settings)
+    }
+}
+
+impl GameRules {
+    pub fn from_preset(preset: DifficultyPreset, settings: &GameSettings) -> Self {
+        // The spawn interval scales off of the one in the settings
+        let (spawn_scale, speed_multiplier, lives, powerup_interval) = match preset {
+            DifficultyPreset::Easy => (1.5, 0.75, 5, 10.0),
+            DifficultyPreset::Normal | DifficultyPreset::Custom => (1.0, 1.0, 3, 15.0),
+            DifficultyPreset::Hard => (0.75, 1.25, 2, 20.0),
+            DifficultyPreset::Nightmare => (0.5, 1.5, 1, 30.0),
+        };
+
+        GameRules {
+            preset,
+            spawn_interval: settings.spawn_interval * spawn_scale,
+            speed_multiplier,
+            lives,
+            powerup_interval,
+            adaptive: false,
+            coop_scoring: CoopScoring::Shared,
+            coop_lives: CoopLives::Shared,
+            player_collision: false,
+            friendly_fire: false,
+            safe_spawn_radius: 200.0,
+        }
+    }
+
+    // How far adaptive difficulty can move the spawn interval
+    pub fn spawn_interval_bounds(&self) -> (f32, f32) {
+        (self.spawn_interval * 0.75, self.spawn_interval * 1.5)
+    }
+}
+
+// The rules and mutators picked in the menus, put aside while a challenge,
+// the daily or the weekly plays with its own. They come back at the main menu.
+#[derive(Default)]
+pub struct PlayerSetup(Option<(GameRules, Vec<Mutator>)>);
+
+impl PlayerSetup {
+    // Only the first one counts, going from one challenge straight to
+    // another shouldn't put the first challenge's rules aside
+    pub fn set_aside(&mut self, rules: &GameRules, mutators: &ActiveMutators) {
+        if self.0.is_none() {
+            self.0 = Some((*rules, mutators.0.clone()));
+        }
+    }
+}
+
+fn restore_player_setup(
+    mut setup: ResMut<PlayerSetup>,
+    mut rules: ResMut<GameRules>,
+    mut mutators: ResMut<ActiveMutators>,
+) {
+    if let Some((player_rules, player_mutators)) = setup.0.take() {
+        *rules = player_rules;
+        mutators.0 = player_mutators;
+    }
+}
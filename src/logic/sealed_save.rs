@@ -0,0 +1,200 @@
+// SEALED SAVE CODE
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+
+use super::profile::save_directory;
+
+// NOTE
+// This only stops casual editing, anyone with the source can reseal a file.
+// The checksum is taken with the key mixed in so changing a number and
+// fixing up a plain checksum isn't enough.
+const SEAL_KEY: &[u8] = b"bevy_block_game/seal";
+
+// Reads a sealed save. A file that fails its checksum is moved aside as
+// '.invalid' so it isn't lost and the game starts that save over.
+// Saves from before sealing are read once from their old file and sealed.
+// Only ever once, a marker is left next to every sealed save so deleting
+// it and writing a new old style file doesn't get around the seal.
+pub fn load_sealed<T: Serialize + DeserializeOwned>(
+    file: &str,
+    legacy_file: &str,
+    name: &str,
+) -> Option<T> {
+    let directory = save_directory();
+    let path = directory.join(file);
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => {
+            let value = unseal(&contents).and_then(|contents| ron::de::from_str(&contents).ok());
+            if value.is_none() {
+                let invalid_path = directory.join(format!("{}.invalid", file));
+                println!(
+                    "The {} has been edited or is damaged, starting it over. The old file was kept at {}",
+                    name,
+                    invalid_path.display()
+                );
+                let _ = fs::rename(&path, invalid_path);
+            }
+            value
+        }
+        Err(_) => {
+            let sealed_before = directory.join(migrated_marker(file)).exists()
+                || directory.join(format!("{}.invalid", file)).exists();
+            if sealed_before {
+                return None;
+            }
+
+            let legacy_path = directory.join(legacy_file);
+            let value: Option<T> = fs::read_to_string(&legacy_path)
+                .ok()
+                .and_then(|contents| ron::de::from_str(&contents).ok());
+
+            if let Some(value) = &value {
+                save_sealed(value, file, name);
+                let _ = fs::remove_file(legacy_path);
+            }
+            value
+        }
+    }
+}
+
+pub fn save_sealed<T: Serialize>(value: &T, file: &str, name: &str) {
+    let directory = save_directory();
+    let contents = match ron::ser::to_string(value) {
+        Ok(contents) => contents,
+        Err(error) => {
+            println!("Could not serialize the {}: {}", name, error);
+            return;
+        }
+    };
+
+    if let Err(error) = fs::create_dir_all(&directory)
+        .and_then(|_| fs::write(directory.join(file), seal(&contents)))
+        .and_then(|_| fs::write(directory.join(migrated_marker(file)), ""))
+    {
+        println!("Could not save the {}: {}", name, error);
+    }
+}
+
+// Left behind once a save has been sealed, old style files are ignored after
+fn migrated_marker(file: &str) -> String {
+    format!("{}.migrated", file)
+}
+
+// The checksum on the first line, then the scrambled contents as hex
+fn seal(contents: &str) -> String {
+    let scrambled: String = scramble(contents.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    format!("{:016x}\n{}\n", checksum(contents.as_bytes()), scrambled)
+}
+
+fn unseal(sealed: &str) -> Option<String> {
+    let mut lines = sealed.lines();
+    let expected = u64::from_str_radix(lines.next()?, 16).ok()?;
+    let hex = lines.next()?;
+
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    let contents = scramble(&bytes);
+    if checksum(&contents) != expected {
+        return None;
+    }
+    String::from_utf8(contents).ok()
+}
+
+// XOR with the key, running it twice gives back what went in
+fn scramble(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(index, byte)| byte ^ SEAL_KEY[index % SEAL_KEY.len()] ^ (index as u8))
+        .collect()
+}
+
+// FNV-1a over the key and then the contents
+fn checksum(bytes: &[u8]) -> u64 {
+    SEAL_KEY
+        .iter()
+        .chain(bytes.iter())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENTS: &str = "(coins:120,unlocks:[ExtraLife],personal_bests:[])";
+
+    #[test]
+    fn sealed_contents_roundtrip() {
+        assert_eq!(unseal(&seal(CONTENTS)).as_deref(), Some(CONTENTS));
+        assert_eq!(unseal(&seal("")).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn sealed_contents_are_not_readable() {
+        assert!(!seal(CONTENTS).contains("coins"));
+    }
+
+    #[test]
+    fn any_flipped_byte_is_rejected() {
+        let sealed = seal(CONTENTS);
+        let checksum_length = sealed.find('\n').unwrap();
+
+        for index in 0..sealed.len() {
+            let original = sealed.as_bytes()[index];
+            if original == b'\n' {
+                continue;
+            }
+
+            // Another hex digit, so it still parses and only the seal can catch it
+            let flipped = if original == b'0' { '1' } else { '0' };
+            let mut tampered = sealed.clone();
+            tampered.replace_range(index..index + 1, &flipped.to_string());
+            assert_eq!(
+                unseal(&tampered),
+                None,
+                "byte {} of {}",
+                index,
+                checksum_length
+            );
+        }
+    }
+
+    #[test]
+    fn damaged_files_are_rejected() {
+        let sealed = seal(CONTENTS);
+        assert_eq!(unseal(""), None);
+        assert_eq!(unseal(&sealed[..sealed.len() - 2]), None);
+        assert_eq!(unseal(&sealed.replacen('\n', "", 1)), None);
+        assert_eq!(unseal(&format!("{}\n", CONTENTS)), None);
+    }
+
+    #[test]
+    fn resealing_edited_contents_needs_the_key() {
+        // A plain FNV-1a over the edited contents doesn't pass
+        let edited = CONTENTS.replace("120", "999");
+        let plain_checksum = edited
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+        let sealed = seal(CONTENTS);
+        let body = sealed.lines().nth(1).unwrap();
+        let forged = format!("{:016x}\n{}\n", plain_checksum, body);
+        assert_eq!(unseal(&forged), None);
+    }
+}
@@ -0,0 +1,218 @@
+// SEED CODES CODE
+
+use std::fmt;
+
+// Crockford's base32, no I, L, O or U so codes are hard to misread
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+// 13 characters hold the 64 bit seed, then one check character
+const SEED_LENGTH: usize = 13;
+const CODE_LENGTH: usize = SEED_LENGTH + 1;
+// Split up with dashes so it is easier to read out to someone
+const GROUP_SIZE: usize = 5;
+
+#[derive(Debug, PartialEq)]
+pub enum SeedCodeError {
+    Empty,
+    WrongLength(usize),
+    InvalidCharacter(char),
+    // The first character only holds four bits of the seed
+    TooLarge,
+    // The check character doesn't match, there is a typo somewhere
+    Mistyped,
+}
+
+impl fmt::Display for SeedCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeedCodeError::Empty => write!(f, "Type a seed code first"),
+            SeedCodeError::WrongLength(length) => write!(
+                f,
+                "Seed codes are {} characters, that one is {}",
+                CODE_LENGTH, length
+            ),
+            SeedCodeError::InvalidCharacter(character) => {
+                write!(f, "{} can't be in a seed code", character)
+            }
+            SeedCodeError::TooLarge | SeedCodeError::Mistyped => {
+                write!(f, "That seed code has a typo in it")
+            }
+        }
+    }
+}
+
+// Every position weighs differently, so most swapped characters are caught
+// too. The weights are odd so that any single wrong character is caught,
+// an even weight can cancel out against the 32.
+fn check_character(values: &[u8]) -> u8 {
+    let sum: usize = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| *value as usize * (index * 2 + 1))
+        .sum();
+    (sum % ALPHABET.len()) as u8
+}
+
+// A seed as something like 0F3KD-8ZQ1M-7RTX
+pub fn encode(seed: u64) -> String {
+    let mut values: Vec<u8> = (0..SEED_LENGTH)
+        .rev()
+        .map(|index| ((seed >> (index * 5)) & 0x1f) as u8)
+        .collect();
+    values.push(check_character(&values));
+
+    let characters: Vec<char> = values
+        .iter()
+        .map(|value| ALPHABET[*value as usize] as char)
+        .collect();
+    characters
+        .chunks(GROUP_SIZE)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+// Forgiving about case, dashes and spaces, and about the
+// letters that look like numbers
+pub fn decode(code: &str) -> Result<u64, SeedCodeError> {
+    let values = code
+        .chars()
+        .filter(|character| *character != '-' && !character.is_whitespace())
+        .map(|character| {
+            let character = match character.to_ascii_uppercase() {
+                'I' | 'L' => '1',
+                'O' => '0',
+                character => character,
+            };
+            ALPHABET
+                .iter()
+                .position(|letter| *letter as char == character)
+                .map(|value| value as u8)
+                .ok_or(SeedCodeError::InvalidCharacter(character))
+        })
+        .collect::<Result<Vec<u8>, SeedCodeError>>()?;
+
+    if values.is_empty() {
+        return Err(SeedCodeError::Empty);
+    }
+    if values.len() != CODE_LENGTH {
+        return Err(SeedCodeError::WrongLength(values.len()));
+    }
+
+    let (seed_values, check) = values.split_at(SEED_LENGTH);
+    if check[0] != check_character(seed_values) {
+        return Err(SeedCodeError::Mistyped);
+    }
+    if seed_values[0] > 0xf {
+        return Err(SeedCodeError::TooLarge);
+    }
+
+    Ok(seed_values
+        .iter()
+        .fold(0, |seed, value| (seed << 5) | *value as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u64; 6] = [0, 1, 31, 0xdead_beef, 1 << 63, u64::MAX];
+
+    #[test]
+    fn seeds_roundtrip() {
+        for seed in SEEDS.iter() {
+            assert_eq!(decode(&encode(*seed)), Ok(*seed));
+        }
+    }
+
+    #[test]
+    fn codes_are_grouped() {
+        let code = encode(u64::MAX);
+        assert_eq!(code.len(), CODE_LENGTH + 2);
+        assert_eq!(code.matches('-').count(), 2);
+    }
+
+    #[test]
+    fn decode_forgives_case_spacing_and_lookalikes() {
+        let code = encode(0xdead_beef);
+        let sloppy = code.to_lowercase().replace('-', " ");
+        assert_eq!(decode(&sloppy), Ok(0xdead_beef));
+
+        // Zeros and ones read as the letters they look like
+        let lookalikes = encode(0).replace('0', "O");
+        assert_eq!(decode(&lookalikes), Ok(0));
+        let lookalikes = encode(1).replacen('0', "o", 3);
+        assert_eq!(decode(&lookalikes), Ok(1));
+    }
+
+    #[test]
+    fn empty_codes_are_rejected() {
+        assert_eq!(decode(""), Err(SeedCodeError::Empty));
+        assert_eq!(decode(" - "), Err(SeedCodeError::Empty));
+    }
+
+    #[test]
+    fn wrong_lengths_are_rejected() {
+        let code = encode(42).replace('-', "");
+        assert_eq!(
+            decode(&code[1..]),
+            Err(SeedCodeError::WrongLength(CODE_LENGTH - 1))
+        );
+        assert_eq!(
+            decode(&format!("{}0", code)),
+            Err(SeedCodeError::WrongLength(CODE_LENGTH + 1))
+        );
+    }
+
+    #[test]
+    fn invalid_characters_are_rejected() {
+        let code = encode(42).replacen('0', "U", 1);
+        assert_eq!(decode(&code), Err(SeedCodeError::InvalidCharacter('U')));
+        assert_eq!(
+            decode("0F3KD-8ZQ1M-7R!X"),
+            Err(SeedCodeError::InvalidCharacter('!'))
+        );
+    }
+
+    #[test]
+    fn typos_are_caught() {
+        let code: Vec<char> = encode(0xdead_beef).replace('-', "").chars().collect();
+
+        for index in 0..code.len() {
+            let mut typo = code.clone();
+            typo[index] = if typo[index] == '7' { '8' } else { '7' };
+            let typo: String = typo.into_iter().collect();
+            assert_eq!(decode(&typo), Err(SeedCodeError::Mistyped), "{}", typo);
+        }
+
+        // Neighbours that are 16 apart cancel out, these aren't
+        let value = |character: char| {
+            ALPHABET
+                .iter()
+                .position(|letter| *letter as char == character)
+        };
+        let mut swapped = code.clone();
+        let (first, second) = (0..code.len() - 1)
+            .map(|index| (index, index + 1))
+            .find(|(first, second)| {
+                let difference =
+                    value(code[*first]).unwrap() as i32 - value(code[*second]).unwrap() as i32;
+                difference % 16 != 0
+            })
+            .unwrap();
+        swapped.swap(first, second);
+        let swapped: String = swapped.into_iter().collect();
+        assert_eq!(decode(&swapped), Err(SeedCodeError::Mistyped));
+    }
+
+    #[test]
+    fn seeds_past_64_bits_are_rejected() {
+        let mut values = vec![0x10];
+        values.extend(std::iter::repeat(0).take(SEED_LENGTH - 1));
+        values.push(check_character(&values));
+        let code: String = values
+            .iter()
+            .map(|value| ALPHABET[*value as usize] as char)
+            .collect();
+        assert_eq!(decode(&code), Err(SeedCodeError::TooLarge));
+    }
+}
@@ -0,0 +1,68 @@
+// SNAPSHOT CODE
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use super::blocks::Block;
+use super::game_time::GameTime;
+use super::player::{Dying, Player};
+use crate::AppState;
+
+// How much history is kept around, in seconds
+const SNAPSHOT_SECONDS: f64 = 2.0;
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Snapshots>().add_system_set(
+            SystemSet::on_update(AppState::InGame).with_system(record_snapshot.system()),
+        );
+    }
+}
+
+// Where the player and blocks were at a point in time.
+// Positions are paired with the sprite size.
+pub struct Snapshot {
+    pub time: f64,
+    pub player: (Vec3, Vec2),
+    pub blocks: Vec<(Vec3, Vec2)>,
+}
+
+// A ring buffer of the last couple seconds of play, oldest first
+#[derive(Default)]
+pub struct Snapshots(pub VecDeque<Snapshot>);
+
+// NOTE
+// Only records while a player is alive, so once the last player
+// is hit the buffer ends on the moment of impact. With co-op the
+// first player found is the one recorded.
+fn record_snapshot(
+    mut snapshots: ResMut<Snapshots>,
+    player_query: Query<(&Transform, &Sprite), (With<Player>, Without<Dying>)>,
+    block_query: Query<(&Transform, &Sprite), With<Block>>,
+    time: Res<GameTime>,
+) {
+    if let Some((player_transform, player_sprite)) = player_query.iter().next() {
+        let now = time.seconds_since_startup();
+
+        snapshots.0.push_back(Snapshot {
+            time: now,
+            player: (player_transform.translation, player_sprite.size),
+            blocks: block_query
+                .iter()
+                .map(|(transform, sprite)| (transform.translation, sprite.size))
+                .collect(),
+        });
+
+        // Drop anything older than the history we want to keep
+        while let Some(oldest) = snapshots.0.front() {
+            if oldest.time >= now - SNAPSHOT_SECONDS {
+                break;
+            }
+
+            snapshots.0.pop_front();
+        }
+    }
+}
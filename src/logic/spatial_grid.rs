@@ -0,0 +1,246 @@
+// SPATIAL GRID CODE
+
+use bevy::prelude::*;
+use bevy::sprite::collide_aabb::{collide, Collision};
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{AppState, Collidable, BLOCKSIZEX, WINDOWHEIGHT, WINDOWWIDTH};
+
+// About a block across, so most blocks sit in one to four cells
+const CELL_SIZE: f32 = BLOCKSIZEX;
+
+// Label for the system that fills the grid,
+// collision systems that use it go after it
+pub const BUILD_SPATIAL_GRID: &str = "build_spatial_grid";
+
+pub struct SpatialGridPlugin;
+
+impl Plugin for SpatialGridPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<SpatialGrid>().add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(build_spatial_grid.system().label(BUILD_SPATIAL_GRID)),
+        );
+    }
+}
+
+// Every collidable thing by the cells it covers, rebuilt each frame.
+// Collision systems ask it what is near instead of checking everything.
+#[derive(Default)]
+pub struct SpatialGrid {
+    // Each entity with the layer it is on
+    cells: HashMap<(i32, i32), Vec<(Entity, u32)>>,
+}
+
+impl SpatialGrid {
+    fn cell(position: Vec2) -> (i32, i32) {
+        (
+            (position.x / CELL_SIZE).floor() as i32,
+            (position.y / CELL_SIZE).floor() as i32,
+        )
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    // Something hanging off an edge is also put in on the other side
+    pub fn insert(&mut self, entity: Entity, position: Vec2, size: Vec2, layer: u32) {
+        for offset in wrapped_offsets(position, size) {
+            let min = SpatialGrid::cell(position + offset - size / 2.0);
+            let max = SpatialGrid::cell(position + offset + size / 2.0);
+
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    self.cells.entry((x, y)).or_default().push((entity, layer));
+                }
+            }
+        }
+    }
+
+    // Everything on the masked layers that could be touching the box, on either
+    // side of the edges. One extra ring of cells is searched since things may
+    // have moved since the grid was built.
+    pub fn nearby(&self, position: Vec2, size: Vec2, mask: u32) -> Vec<Entity> {
+        let mut found = Vec::new();
+
+        for offset in wrapped_offsets(position, size) {
+            let min = SpatialGrid::cell(position + offset - size / 2.0);
+            let max = SpatialGrid::cell(position + offset + size / 2.0);
+
+            for x in min.0 - 1..=max.0 + 1 {
+                for y in min.1 - 1..=max.1 + 1 {
+                    if let Some(entities) = self.cells.get(&(x, y)) {
+                        for (entity, layer) in entities {
+                            if layer & mask != 0 && !found.contains(entity) {
+                                found.push(*entity);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        found
+    }
+}
+
+// Everything wraps around the screen, so a box hanging off one edge is also
+// on the other side. These move the box to each copy of it, itself first.
+pub fn wrapped_offsets(position: Vec2, size: Vec2) -> Vec<Vec2> {
+    let axis = |center: f32, half_size: f32, extent: f32| {
+        let mut offsets = vec![0.0];
+        if center + half_size > extent / 2.0 {
+            offsets.push(-extent);
+        }
+        if center - half_size < -extent / 2.0 {
+            offsets.push(extent);
+        }
+        offsets
+    };
+
+    let xs = axis(position.x, size.x / 2.0, WINDOWWIDTH);
+    let ys = axis(position.y, size.y / 2.0, WINDOWHEIGHT);
+
+    xs.iter()
+        .flat_map(|x| ys.iter().map(move |y| Vec2::new(*x, *y)))
+        .collect()
+}
+
+// collide() that knows about wrapping, so something half off the right edge
+// hits something half off the left edge and edges aren't a safe spot.
+// Either box hanging off an edge can reach around to the other one.
+pub fn collide_wrapped(
+    a_position: Vec3,
+    a_size: Vec2,
+    b_position: Vec3,
+    b_size: Vec2,
+) -> Option<Collision> {
+    let axis = |a: f32, a_half: f32, b: f32, b_half: f32, extent: f32| {
+        let mut shifts = vec![0.0];
+        if a + a_half > extent / 2.0 || b - b_half < -extent / 2.0 {
+            shifts.push(-extent);
+        }
+        if a - a_half < -extent / 2.0 || b + b_half > extent / 2.0 {
+            shifts.push(extent);
+        }
+        shifts
+    };
+
+    let xs = axis(
+        a_position.x,
+        a_size.x / 2.0,
+        b_position.x,
+        b_size.x / 2.0,
+        WINDOWWIDTH,
+    );
+    let ys = axis(
+        a_position.y,
+        a_size.y / 2.0,
+        b_position.y,
+        b_size.y / 2.0,
+        WINDOWHEIGHT,
+    );
+
+    xs.iter()
+        .flat_map(|x| ys.iter().map(move |y| Vec3::new(*x, *y, 0.0)))
+        .find_map(|shift| collide(a_position + shift, a_size, b_position, b_size))
+}
+
+fn build_spatial_grid(
+    mut grid: ResMut<SpatialGrid>,
+    collidable_query: Query<(Entity, &Transform, &Sprite, &Collidable)>,
+) {
+    grid.clear();
+
+    for (entity, transform, sprite, collidable) in collidable_query.iter() {
+        grid.insert(
+            entity,
+            transform.translation.truncate(),
+            sprite.size,
+            collidable.layer,
+        );
+    }
+}
+
+// Run with --bench-collisions. Times a player and a screen full of bullets
+// against a growing number of blocks, checking every block against
+// checking only the ones the grid says are nearby.
+pub fn benchmark() {
+    const FRAMES: u32 = 100;
+    const BULLETS: usize = 50;
+
+    let mut rng = thread_rng();
+    let mut random_position = || {
+        Vec2::new(
+            rng.gen_range(-WINDOWWIDTH / 2.0..WINDOWWIDTH / 2.0),
+            rng.gen_range(-WINDOWHEIGHT / 2.0..WINDOWHEIGHT / 2.0),
+        )
+    };
+    let block_size = Vec2::new(BLOCKSIZEX, BLOCKSIZEX);
+    let bullet_size = Vec2::new(8.0, 8.0);
+
+    for block_count in [100, 500, 1000, 2000].iter() {
+        let blocks: Vec<Vec2> = (0..*block_count).map(|_| random_position()).collect();
+        // The player is checked the same way as a bullet, just bigger
+        let checkers: Vec<(Vec2, Vec2)> = (0..BULLETS)
+            .map(|_| (random_position(), bullet_size))
+            .chain(std::iter::once((Vec2::ZERO, block_size)))
+            .collect();
+
+        let mut naive_hits = 0;
+        let naive_start = Instant::now();
+        for _ in 0..FRAMES {
+            for (position, size) in checkers.iter() {
+                naive_hits += blocks
+                    .iter()
+                    .filter(|block| {
+                        collide(position.extend(0.0), *size, block.extend(0.0), block_size)
+                            .is_some()
+                    })
+                    .count();
+            }
+        }
+        let naive = naive_start.elapsed();
+
+        let mut grid = SpatialGrid::default();
+        let mut grid_hits = 0;
+        let grid_start = Instant::now();
+        for _ in 0..FRAMES {
+            grid.clear();
+            for (index, block) in blocks.iter().enumerate() {
+                grid.insert(
+                    Entity::new(index as u32),
+                    *block,
+                    block_size,
+                    Collidable::BLOCK,
+                );
+            }
+
+            for (position, size) in checkers.iter() {
+                grid_hits += grid
+                    .nearby(*position, *size, Collidable::BLOCK)
+                    .iter()
+                    .filter(|entity| {
+                        let block = blocks[entity.id() as usize];
+                        collide(position.extend(0.0), *size, block.extend(0.0), block_size)
+                            .is_some()
+                    })
+                    .count();
+            }
+        }
+        let grid_time = grid_start.elapsed();
+
+        let per_frame = |total: Duration| total.as_secs_f64() * 1000.0 / FRAMES as f64;
+        println!(
+            "{} blocks: every block {:.3}ms/frame, spatial grid {:.3}ms/frame ({} and {} hits)",
+            block_count,
+            per_frame(naive),
+            per_frame(grid_time),
+            naive_hits,
+            grid_hits
+        );
+    }
+}
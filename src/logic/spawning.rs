@@ -2,6 +2,8 @@ use crate::{Direction, BLOCKSIZEX, BLOCKSIZEY, WINDOWHEIGHT, WINDOWWIDTH};
 
 use bevy::prelude::*;
 use rand::Rng;
+
+use super::game_rng::GameRng;
 use std::fmt;
 
 pub struct SpawningPlugin;
@@ -64,6 +66,13 @@ fn get_edge_of_screen(window_size: f32) -> i16 {
     window_size as i16 / 2
 }
 
+fn random_direction<R: Rng>(orientation: BlockDirection, rng: &mut R) -> Direction {
+    match orientation {
+        BlockDirection::Horizontal => get_direction(rng.gen_range(1..=2)),
+        BlockDirection::Vertical => get_direction(rng.gen_range(3..=4)),
+    }
+}
+
 fn create_random_blocks<R: Rng>(
     block_positions: Vec<i16>,
     orientation: BlockDirection,
@@ -72,10 +81,7 @@ fn create_random_blocks<R: Rng>(
     return block_positions
         .iter()
         .map(|block_position| SpawnInfo {
-            spawn_direction: match orientation {
-                BlockDirection::Horizontal => get_direction(rng.gen_range(1..=2)),
-                BlockDirection::Vertical => get_direction(rng.gen_range(3..=4)),
-            },
+            spawn_direction: random_direction(orientation, rng),
             spawn_location: match orientation {
                 // If you want the blocks to go horizontally (left/right) the y position needs to change
                 BlockDirection::Horizontal => (get_edge_of_screen(WINDOWWIDTH), *block_position),
@@ -90,7 +96,7 @@ fn create_random_blocks<R: Rng>(
 
 // Blocks should spawn in set locations depending on
 // the size of the screen.
-fn create_spawn_locations() -> Vec<SpawnInfo> {
+fn create_spawn_locations(rng: &mut GameRng) -> Vec<SpawnInfo> {
     // Calculate the number of blocks per side
     // ScreenLength / (BlockGap + BlockLength) = BlockNumber
     // Plus 1 because I want one block to spawn at the opposite edge
@@ -111,17 +117,12 @@ fn create_spawn_locations() -> Vec<SpawnInfo> {
         .map(|y| y - get_edge_of_screen(WINDOWHEIGHT))
         .collect();
 
-    let mut rng = rand::thread_rng();
-
     // Create the horizontal and vertical blocks
     let random_horizontal_blocks: Vec<SpawnInfo> =
-        create_random_blocks(vertical_block_positions, BlockDirection::Vertical, &mut rng);
+        create_random_blocks(vertical_block_positions, BlockDirection::Vertical, rng);
 
-    let random_vertical_blocks: Vec<SpawnInfo> = create_random_blocks(
-        horizontal_block_positions,
-        BlockDirection::Horizontal,
-        &mut rng,
-    );
+    let random_vertical_blocks: Vec<SpawnInfo> =
+        create_random_blocks(horizontal_block_positions, BlockDirection::Horizontal, rng);
 
     // Combine the blocks together
     [random_horizontal_blocks, random_vertical_blocks].concat()
@@ -137,8 +138,17 @@ fn create_spawn_locations() -> Vec<SpawnInfo> {
 //
 // TODO
 // In Bevy 0.6 this can be rewritten using IteratorCommands
-fn spawn_block_positions(mut commands: Commands) {
-    for spawn_location in create_spawn_locations() {
+fn spawn_block_positions(mut commands: Commands, mut game_rng: ResMut<GameRng>) {
+    for spawn_location in create_spawn_locations(&mut game_rng) {
         commands.spawn().insert(spawn_location);
     }
 }
+
+// Every run rolls its lanes from its own seed, in the order they were
+// spawned in so the same seed always gives the same lanes
+pub fn reroll_spawn_directions(world: &mut World, game_rng: &mut GameRng) {
+    let mut spawn_query = world.query::<&mut SpawnInfo>();
+    for mut spawn_info in spawn_query.iter_mut(world) {
+        spawn_info.spawn_direction = random_direction(spawn_info.direction, game_rng);
+    }
+}
@@ -0,0 +1,255 @@
+// SPECTATOR CODE
+
+use bevy::prelude::*;
+
+use super::blocks::Block;
+use super::game_time::GameTime;
+use super::gamepad::{left_stick, ConnectedGamepads, StickSettings};
+use super::input_map::{Action, InputMap};
+use super::player::{ControlScheme, Controls, Player};
+use super::reset_game::RunScoped;
+use crate::graphics::camera::CameraRig;
+use crate::{AppState, WINDOWHEIGHT, WINDOWWIDTH};
+
+// How fast the reticle pans in 'pixels/second'
+const SPECTATOR_SPEED: f32 = 450.0;
+const RETICLE_SIZE: f32 = 16.0;
+// A block this close to the reticle can be tagged without being right on it
+const TAG_REACH: f32 = 30.0;
+// How far the marker sits past each side of a tagged block
+const MARKER_BORDER: f32 = 6.0;
+
+const MOVE_SPECTATORS: &str = "move_spectators";
+
+pub struct SpectatorPlugin;
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<StartSpectatingEvent>().add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(start_spectating.system())
+                .with_system(move_spectators.system().label(MOVE_SPECTATORS))
+                .with_system(tag_blocks.system().after(MOVE_SPECTATORS))
+                .with_system(follow_tagged_blocks.system()),
+        );
+    }
+}
+
+// A co-op player is out of lives but someone else is still playing
+pub struct StartSpectatingEvent {
+    pub controls: Controls,
+    pub position: Vec3,
+}
+
+// The reticle an out player pans around with, on their own controls
+pub struct Spectator {
+    pub controls: Controls,
+    // Set for the frame the tag button is pressed
+    tag: bool,
+}
+
+// A spectator pointed this block out
+pub struct Tagged;
+
+// Drawn behind a tagged block so the players still in can see it
+struct TagMarker(Entity);
+
+fn start_spectating(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut start_spectating_event: EventReader<StartSpectatingEvent>,
+) {
+    for event in start_spectating_event.iter() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite::new(Vec2::new(RETICLE_SIZE, RETICLE_SIZE)),
+                material: materials.add(Color::rgba(1.0, 1.0, 1.0, 0.6).into()),
+                transform: Transform::from_xyz(event.position.x, event.position.y, 3.0),
+                ..Default::default()
+            })
+            .insert(Spectator {
+                controls: event.controls,
+                tag: false,
+            })
+            .insert(RunScoped);
+    }
+}
+
+// The same controls the player had, the teleport button tags instead.
+// The reticle stays on the playfield rather than wrapping.
+#[allow(clippy::too_many_arguments)]
+fn move_spectators(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mouse_input: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<ConnectedGamepads>,
+    stick_settings: Res<StickSettings>,
+    control_scheme: Res<ControlScheme>,
+    windows: Res<Windows>,
+    camera_rig: Res<CameraRig>,
+    player_query: Query<&Controls, With<Player>>,
+    mut spectator_query: Query<(&mut Spectator, &mut Transform)>,
+    time: Res<GameTime>,
+) {
+    // Same as for the players, gamepads that are someone else's are left alone
+    let claimed: Vec<Controls> = player_query
+        .iter()
+        .copied()
+        .chain(
+            spectator_query
+                .iter_mut()
+                .map(|(spectator, _)| spectator.controls),
+        )
+        .collect();
+    let primary_gamepad = gamepads
+        .0
+        .iter()
+        .copied()
+        .find(|gamepad| !claimed.contains(&Controls::Gamepad(*gamepad)));
+
+    let axis = |up: bool, down: bool, left: bool, right: bool| {
+        Vec2::new(
+            (-(left as i8) + right as i8) as f32,
+            (-(down as i8) + up as i8) as f32,
+        )
+    };
+
+    for (mut spectator, mut transform) in spectator_query.iter_mut() {
+        let (move_delta, tag) = match spectator.controls {
+            Controls::Primary if *control_scheme == ControlScheme::Mouse => {
+                if let Some(cursor) = camera_rig.cursor_world_position(&windows) {
+                    transform.translation.x = cursor.x;
+                    transform.translation.y = cursor.y;
+                }
+                (Vec2::ZERO, mouse_input.just_pressed(MouseButton::Left))
+            }
+            Controls::Primary => {
+                let keyboard_delta = axis(
+                    input_map.pressed(&keyboard_input, Action::MoveUp),
+                    input_map.pressed(&keyboard_input, Action::MoveDown),
+                    input_map.pressed(&keyboard_input, Action::MoveLeft),
+                    input_map.pressed(&keyboard_input, Action::MoveRight),
+                );
+                let gamepad_delta = primary_gamepad.map_or(Vec2::ZERO, |gamepad| {
+                    left_stick(&gamepad_axes, gamepad, &stick_settings)
+                });
+                let gamepad_tag = primary_gamepad.map_or(false, |gamepad| {
+                    gamepad_buttons.just_pressed(GamepadButton(gamepad, GamepadButtonType::South))
+                });
+                (
+                    if keyboard_delta != Vec2::ZERO {
+                        keyboard_delta
+                    } else {
+                        gamepad_delta
+                    },
+                    input_map.just_pressed(&keyboard_input, Action::Teleport) || gamepad_tag,
+                )
+            }
+            Controls::Gamepad(gamepad) => (
+                left_stick(&gamepad_axes, gamepad, &stick_settings),
+                gamepad_buttons.just_pressed(GamepadButton(gamepad, GamepadButtonType::South)),
+            ),
+            Controls::SecondKeyboard => (
+                axis(
+                    keyboard_input.pressed(KeyCode::I),
+                    keyboard_input.pressed(KeyCode::K),
+                    keyboard_input.pressed(KeyCode::J),
+                    keyboard_input.pressed(KeyCode::L),
+                ),
+                keyboard_input.just_pressed(KeyCode::U),
+            ),
+            Controls::Bot => (Vec2::ZERO, false),
+        };
+
+        transform.translation += (move_delta * SPECTATOR_SPEED * time.delta_seconds()).extend(0.0);
+        transform.translation.x = transform
+            .translation
+            .x
+            .clamp(-WINDOWWIDTH / 2.0, WINDOWWIDTH / 2.0);
+        transform.translation.y = transform
+            .translation
+            .y
+            .clamp(-WINDOWHEIGHT / 2.0, WINDOWHEIGHT / 2.0);
+        spectator.tag = tag;
+    }
+}
+
+// Tags the block nearest the reticle, or takes the tag off again
+fn tag_blocks(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    spectator_query: Query<(&Spectator, &Transform)>,
+    block_query: Query<(Entity, &Transform, &Sprite, Option<&Tagged>), With<Block>>,
+    marker_query: Query<(Entity, &TagMarker)>,
+) {
+    for (spectator, reticle) in spectator_query.iter() {
+        if !spectator.tag {
+            continue;
+        }
+
+        let reticle_position = reticle.translation.truncate();
+        let nearest = block_query
+            .iter()
+            .map(|(entity, transform, sprite, tagged)| {
+                // Distance to the block's edge, zero anywhere inside it
+                let offset =
+                    (transform.translation.truncate() - reticle_position).abs() - sprite.size / 2.0;
+                (
+                    entity,
+                    transform,
+                    sprite,
+                    tagged,
+                    offset.max(Vec2::ZERO).length(),
+                )
+            })
+            .filter(|(.., distance)| *distance <= TAG_REACH)
+            .min_by(|a, b| a.4.total_cmp(&b.4));
+
+        let (block, transform, sprite, tagged, _) = match nearest {
+            Some(nearest) => nearest,
+            None => continue,
+        };
+
+        if tagged.is_some() {
+            commands.entity(block).remove::<Tagged>();
+            for (marker, TagMarker(tagged_block)) in marker_query.iter() {
+                if *tagged_block == block {
+                    commands.entity(marker).despawn();
+                }
+            }
+            continue;
+        }
+
+        commands.entity(block).insert(Tagged);
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite::new(sprite.size + Vec2::splat(MARKER_BORDER * 2.0)),
+                material: materials.add(Color::rgba(1.0, 0.85, 0.1, 0.5).into()),
+                transform: Transform::from_translation(
+                    transform.translation - Vec3::new(0.0, 0.0, 0.1),
+                ),
+                ..Default::default()
+            })
+            .insert(TagMarker(block))
+            .insert(RunScoped);
+    }
+}
+
+// Markers aren't children so they can outlive their block for a frame,
+// and go once it has been destroyed
+fn follow_tagged_blocks(
+    mut commands: Commands,
+    block_query: Query<&Transform, (With<Tagged>, Without<TagMarker>)>,
+    mut marker_query: Query<(Entity, &TagMarker, &mut Transform)>,
+) {
+    for (marker, TagMarker(block), mut transform) in marker_query.iter_mut() {
+        match block_query.get(*block) {
+            Ok(block_transform) => {
+                transform.translation = block_transform.translation - Vec3::new(0.0, 0.0, 0.1)
+            }
+            Err(_) => commands.entity(marker).despawn(),
+        }
+    }
+}
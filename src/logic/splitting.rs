@@ -0,0 +1,80 @@
+// SPLITTING CODE
+
+use bevy::prelude::*;
+use bevy::sprite::SpriteResizeMode;
+use rand::Rng;
+
+use super::block_kinds::{BlockKind, BlockSize};
+use super::blocks::{rotate, Block, BlockDestroyedEvent, BLOCK_WRAP_MARGIN};
+use super::factions::{Damage, Faction};
+use super::game_rng::GameRng;
+use super::health::Health;
+use super::reset_game::RunScoped;
+use crate::graphics::sprites::block_texture;
+use crate::graphics::tween::ScaleTween;
+use crate::{Collidable, Velocity, WrapMargin, BLOCKSIZEX, BLOCKSIZEY};
+
+// The pieces are this much faster than the block they came from
+const SPLIT_SPEED_MULTIPLIER: f32 = 1.3;
+// How far apart the pieces fly, in radians
+const SPLIT_SPREAD: f32 = 0.8;
+
+pub struct SplittingPlugin;
+
+impl Plugin for SplittingPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(split_blocks.system());
+    }
+}
+
+// Asteroids style, a destroyed block breaks into two or three
+// smaller, faster blocks that fan out from where it was
+fn split_blocks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut block_destroyed_event: EventReader<BlockDestroyedEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for event in block_destroyed_event.iter() {
+        let piece_size = match event.size.smaller() {
+            Some(piece_size) => piece_size,
+            None => continue,
+        };
+
+        let piece_count = rng.gen_range(2..=3);
+        for piece in 0..piece_count {
+            // Spread the pieces evenly across the fan, centered on the old heading
+            let offset = piece as f32 / (piece_count - 1) as f32 - 0.5;
+            let velocity =
+                rotate(event.velocity, offset * SPLIT_SPREAD * 2.0) * SPLIT_SPEED_MULTIPLIER;
+
+            let pop = ScaleTween::pop();
+            let mut transform = Transform::from_translation(event.position);
+            transform.scale = pop.start;
+
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        size: Vec2::new(BLOCKSIZEX, BLOCKSIZEY) * piece_size.scale(),
+                        resize_mode: SpriteResizeMode::Manual,
+                        ..Default::default()
+                    },
+                    material: materials.add(block_texture(&asset_server, BlockKind::Normal).into()),
+                    transform,
+                    ..Default::default()
+                })
+                .insert(Block)
+                .insert(BlockKind::Normal)
+                .insert(piece_size)
+                .insert(Health::new(piece_size.hit_points()))
+                .insert(Faction::Hostile)
+                .insert(Damage(1))
+                .insert(Velocity(velocity))
+                .insert(Collidable::block())
+                .insert(WrapMargin(BLOCK_WRAP_MARGIN))
+                .insert(RunScoped)
+                .insert(pop);
+        }
+    }
+}
@@ -0,0 +1,353 @@
+// STATS CODE
+
+use bevy::prelude::*;
+
+use super::blocks::{Block, BlockDestroyedEvent};
+use super::factions::{CollisionEvent, DETECT_COLLISIONS};
+use super::game_time::GameTime;
+use super::mutators::{ActiveMutators, Mutator};
+use super::player::{Controls, Dying, Player, PlayerHitEvent, TeleportEvent, MOVE_PLAYER};
+use super::powerups::PowerUpPickedEvent;
+use super::reset_game::{Resettable, RunScoped};
+use super::triggers::{spawn_sensor, Sensor, Trigger, TriggerEvent, DETECT_TRIGGERS};
+use super::waves::WaveClearedEvent;
+use crate::graphics::score::ScoreEvent;
+use crate::{AppState, Collidable, WINDOWHEIGHT, WINDOWWIDTH};
+
+// How close to two edges of the screen counts as being in a corner
+const CORNER_MARGIN: f32 = 150.0;
+// How close a block has to pass, edge to edge, to count as a near miss.
+// Every player carries a sensor this much bigger than them on each side.
+const NEAR_MISS_DISTANCE: f32 = 20.0;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<NearMissEvent>()
+            .init_resource::<RunStats>()
+            .reset_world(reset_run_stats)
+            .init_resource::<DeathHistory>()
+            .add_startup_system(spawn_corner_sensors.system())
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(track_player_stats.system().after(DETECT_TRIGGERS))
+                    .with_system(spawn_near_miss_sensors.system())
+                    .with_system(
+                        follow_players
+                            .system()
+                            .after(MOVE_PLAYER)
+                            .before(DETECT_TRIGGERS),
+                    )
+                    .with_system(
+                        count_near_misses
+                            .system()
+                            .after(DETECT_TRIGGERS)
+                            .after(DETECT_COLLISIONS),
+                    ),
+            )
+            .add_system(count_teleports.system())
+            .add_system(count_dodged_blocks.system())
+            .add_system(count_destroyed_blocks.system())
+            .add_system(count_powerups.system())
+            .add_system(count_score_events.system())
+            .add_system(record_death_zone.system());
+    }
+}
+
+// What the player did during the current run
+#[derive(Default)]
+pub struct RunStats {
+    pub survival_seconds: f32,
+    pub teleports: u32,
+    pub corner_seconds: f32,
+    // The mutators the run was played with
+    pub mutators: Vec<Mutator>,
+    // Points earned on top of surviving, and how many times
+    pub score_events: u32,
+    pub bonus_points: u32,
+    // Blocks still going when their wave ended
+    pub blocks_dodged: u32,
+    pub waves_finished: u32,
+    // Only the ones a player destroyed
+    pub blocks_destroyed: u32,
+    pub near_misses: u32,
+    pub powerups_collected: u32,
+}
+
+// A block came close to a player and got away without a hit
+pub struct NearMissEvent {
+    pub block: Entity,
+    pub position: Vec3,
+    pub player: Controls,
+}
+
+// The screen split into a 3x3 grid, -1 to 1 on each axis
+#[derive(Clone, Copy, PartialEq)]
+pub struct DeathZone(pub i8, pub i8);
+
+impl DeathZone {
+    fn from_position(position: Vec3) -> Self {
+        let third = |value: f32, size: f32| {
+            if value < -size / 6.0 {
+                -1
+            } else if value > size / 6.0 {
+                1
+            } else {
+                0
+            }
+        };
+
+        DeathZone(
+            third(position.x, WINDOWWIDTH),
+            third(position.y, WINDOWHEIGHT),
+        )
+    }
+}
+
+// Where the player died over every run this session, oldest first
+#[derive(Default)]
+pub struct DeathHistory {
+    pub zones: Vec<DeathZone>,
+}
+
+// One in each corner of the screen, looking for players
+struct CornerSensor;
+
+fn spawn_corner_sensors(mut commands: Commands) {
+    let size = Vec2::new(CORNER_MARGIN, CORNER_MARGIN);
+    let corner = Vec2::new(WINDOWWIDTH, WINDOWHEIGHT) / 2.0 - size / 2.0;
+
+    for (x, y) in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)].iter() {
+        let sensor = spawn_sensor(
+            &mut commands,
+            corner * Vec2::new(*x, *y),
+            size,
+            Collidable::PLAYER,
+        );
+        commands.entity(sensor).insert(CornerSensor);
+    }
+}
+
+fn track_player_stats(
+    mut run_stats: ResMut<RunStats>,
+    mut trigger_event: EventReader<TriggerEvent>,
+    // Players in a corner right now
+    mut in_corners: Local<Vec<Entity>>,
+    corner_query: Query<&CornerSensor>,
+    player_query: Query<Entity, (With<Player>, Without<Dying>)>,
+    time: Res<GameTime>,
+) {
+    for event in trigger_event.iter() {
+        if corner_query.get(event.sensor).is_err() {
+            continue;
+        }
+
+        match event.trigger {
+            Trigger::Entered => in_corners.push(event.other),
+            Trigger::Exited => {
+                if let Some(index) = in_corners.iter().position(|other| *other == event.other) {
+                    in_corners.remove(index);
+                }
+            }
+        }
+    }
+
+    // The run counts as survived while anyone is still playing
+    if player_query.iter().count() == 0 {
+        return;
+    }
+
+    let delta_time = time.delta_seconds();
+    run_stats.survival_seconds += delta_time;
+
+    let in_corner = in_corners
+        .iter()
+        .any(|player| player_query.get(*player).is_ok());
+    if in_corner {
+        run_stats.corner_seconds += delta_time;
+    }
+}
+
+fn count_teleports(
+    mut teleport_event: EventReader<TeleportEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for _event in teleport_event.iter() {
+        run_stats.teleports += 1;
+    }
+}
+
+// Follows a player around, looking for blocks
+struct NearMissSensor {
+    player: Entity,
+}
+
+// On a block that has already been a near miss, or got someone,
+// so it never counts again
+struct NearMissed;
+
+fn spawn_near_miss_sensors(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform, &Sprite), Added<Player>>,
+) {
+    for (player, transform, sprite) in player_query.iter() {
+        let sensor = spawn_sensor(
+            &mut commands,
+            transform.translation.truncate(),
+            sprite.size + Vec2::splat(NEAR_MISS_DISTANCE * 2.0),
+            Collidable::BLOCK,
+        );
+        commands
+            .entity(sensor)
+            .insert(NearMissSensor { player })
+            .insert(RunScoped);
+    }
+}
+
+// A player that is hit or gone loses their sensor, whatever was close
+// to them then doesn't get to leave as a near miss
+fn follow_players(
+    mut commands: Commands,
+    mut sensor_query: Query<(Entity, &NearMissSensor, &mut Transform, &mut Sprite)>,
+    player_query: Query<(&Transform, &Sprite), (With<Player>, Without<Dying>, Without<Sensor>)>,
+) {
+    for (entity, sensor, mut transform, mut sprite) in sensor_query.iter_mut() {
+        match player_query.get(sensor.player) {
+            Ok((player_transform, player_sprite)) => {
+                transform.translation = player_transform.translation;
+                sprite.size = player_sprite.size + Vec2::splat(NEAR_MISS_DISTANCE * 2.0);
+            }
+            Err(_) => commands.entity(entity).despawn(),
+        }
+    }
+}
+
+// A block counts once it has gone into a player's sensor and come back
+// out again without touching them. A shield or a dash saves the player,
+// but it still wasn't a miss.
+#[allow(clippy::too_many_arguments)]
+fn count_near_misses(
+    mut commands: Commands,
+    mut run_stats: ResMut<RunStats>,
+    mut trigger_event: EventReader<TriggerEvent>,
+    mut collision_event: EventReader<CollisionEvent>,
+    mut near_miss_event: EventWriter<NearMissEvent>,
+    // Blocks inside a player's sensor right now
+    mut close_blocks: Local<Vec<(Entity, Entity)>>,
+    sensor_query: Query<&NearMissSensor>,
+    player_query: Query<&Controls, (With<Player>, Without<Dying>)>,
+    block_query: Query<&Transform, (With<Block>, Without<NearMissed>)>,
+) {
+    // Players that were hit took their sensor with them
+    close_blocks.retain(|(player, _)| player_query.get(*player).is_ok());
+
+    for event in collision_event.iter() {
+        if player_query.get(event.target).is_ok() {
+            commands.entity(event.attacker).insert(NearMissed);
+            close_blocks.retain(|(_, block)| *block != event.attacker);
+        }
+    }
+
+    for event in trigger_event.iter() {
+        let sensor = match sensor_query.get(event.sensor) {
+            Ok(sensor) => sensor,
+            Err(_) => continue,
+        };
+
+        match event.trigger {
+            Trigger::Entered => close_blocks.push((sensor.player, event.other)),
+            Trigger::Exited => {
+                let index = close_blocks
+                    .iter()
+                    .position(|close| *close == (sensor.player, event.other));
+                let index = match index {
+                    Some(index) => index,
+                    None => continue,
+                };
+                close_blocks.remove(index);
+
+                // Blocks cleared away with the wave weren't misses
+                let (block_transform, controls) = match (
+                    block_query.get(event.other),
+                    player_query.get(sensor.player),
+                ) {
+                    (Ok(block_transform), Ok(controls)) => (block_transform, controls),
+                    _ => continue,
+                };
+
+                commands.entity(event.other).insert(NearMissed);
+                close_blocks.retain(|(_, block)| *block != event.other);
+                run_stats.near_misses += 1;
+                near_miss_event.send(NearMissEvent {
+                    block: event.other,
+                    position: block_transform.translation,
+                    player: *controls,
+                });
+            }
+        }
+    }
+}
+
+fn count_dodged_blocks(
+    mut wave_cleared_event: EventReader<WaveClearedEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for event in wave_cleared_event.iter() {
+        run_stats.blocks_dodged += event.blocks;
+        run_stats.waves_finished += 1;
+    }
+}
+
+fn count_destroyed_blocks(
+    mut block_destroyed_event: EventReader<BlockDestroyedEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for event in block_destroyed_event.iter() {
+        if event.destroyed_by.is_some() {
+            run_stats.blocks_destroyed += 1;
+        }
+    }
+}
+
+fn count_powerups(
+    mut powerup_picked_event: EventReader<PowerUpPickedEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for _event in powerup_picked_event.iter() {
+        run_stats.powerups_collected += 1;
+    }
+}
+
+fn count_score_events(mut score_event: EventReader<ScoreEvent>, mut run_stats: ResMut<RunStats>) {
+    for event in score_event.iter() {
+        run_stats.score_events += 1;
+        run_stats.bonus_points += event.points;
+    }
+}
+
+fn record_death_zone(
+    mut player_hit_event: EventReader<PlayerHitEvent>,
+    mut death_history: ResMut<DeathHistory>,
+) {
+    for event in player_hit_event.iter() {
+        death_history
+            .zones
+            .push(DeathZone::from_position(event.position));
+    }
+}
+
+// The new run keeps a note of the mutators it is played with
+fn reset_run_stats(world: &mut World) {
+    let mutators = world
+        .get_resource::<ActiveMutators>()
+        .map(|mutators| mutators.0.clone())
+        .unwrap_or_default();
+
+    if let Some(mut run_stats) = world.get_resource_mut::<RunStats>() {
+        *run_stats = RunStats {
+            mutators,
+            ..Default::default()
+        };
+    }
+}
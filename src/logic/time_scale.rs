@@ -0,0 +1,71 @@
+// TIME SCALE CODE
+
+use bevy::prelude::*;
+
+use super::game_time::GameTime;
+
+// Where the time scale starts when ramping back up to full speed
+const RAMP_START: f32 = 0.3;
+const RAMP_SECONDS: f32 = 0.5;
+
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<RampTimeScaleEvent>()
+            .init_resource::<TimeScale>()
+            .init_resource::<TimeScaleRamp>()
+            .add_system(start_time_scale_ramp.system())
+            .add_system(time_scale_ramp_system.system());
+    }
+}
+
+// Multiplier applied to gameplay movement so slow motion
+// can be done in one place. 1.0 is normal speed.
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        TimeScale(1.0)
+    }
+}
+
+// Send this when play resumes so the game eases back up to full speed,
+// that way a block that was a pixel away doesn't instantly hit the player
+pub struct RampTimeScaleEvent;
+
+struct TimeScaleRamp(Timer);
+
+impl Default for TimeScaleRamp {
+    fn default() -> Self {
+        // Start out finished so nothing ramps until asked to
+        let mut timer = Timer::from_seconds(RAMP_SECONDS, false);
+        timer.tick(timer.duration());
+
+        TimeScaleRamp(timer)
+    }
+}
+
+fn start_time_scale_ramp(
+    mut ramp_event: EventReader<RampTimeScaleEvent>,
+    mut ramp: ResMut<TimeScaleRamp>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    for _event in ramp_event.iter() {
+        ramp.0.reset();
+        time_scale.0 = RAMP_START;
+    }
+}
+
+fn time_scale_ramp_system(
+    mut ramp: ResMut<TimeScaleRamp>,
+    mut time_scale: ResMut<TimeScale>,
+    time: Res<GameTime>,
+) {
+    if ramp.0.finished() {
+        return;
+    }
+
+    ramp.0.tick(time.delta());
+    time_scale.0 = RAMP_START + (1.0 - RAMP_START) * ramp.0.percent();
+}
@@ -0,0 +1,117 @@
+// TRIGGER CODE
+
+use bevy::prelude::*;
+
+use super::player::MOVE_PLAYER;
+use super::spatial_grid::{collide_wrapped, SpatialGrid, BUILD_SPATIAL_GRID};
+use crate::{AppState, Collidable};
+
+// Label for the system that finds what went in and out of sensors,
+// anything reacting to a TriggerEvent the same frame goes after it
+pub const DETECT_TRIGGERS: &str = "detect_triggers";
+
+pub struct TriggerPlugin;
+
+impl Plugin for TriggerPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<TriggerEvent>().add_system_set(
+            SystemSet::on_update(AppState::InGame).with_system(
+                detect_triggers
+                    .system()
+                    .label(DETECT_TRIGGERS)
+                    .after(MOVE_PLAYER)
+                    .after(BUILD_SPATIAL_GRID),
+            ),
+        );
+    }
+}
+
+// An area that notices things going in and out of it but never
+// stops or hurts anything. What it notices is its Collidable mask.
+#[derive(Default)]
+pub struct Sensor {
+    inside: Vec<Entity>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Trigger {
+    Entered,
+    Exited,
+}
+
+pub struct TriggerEvent {
+    pub sensor: Entity,
+    pub other: Entity,
+    pub trigger: Trigger,
+}
+
+// A sensor the size of the sprite, the sprite is never drawn
+pub fn spawn_sensor(commands: &mut Commands, position: Vec2, size: Vec2, mask: u32) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(size),
+            transform: Transform::from_xyz(position.x, position.y, 0.0),
+            visible: Visible {
+                is_visible: false,
+                is_transparent: false,
+            },
+            ..Default::default()
+        })
+        .insert(Sensor::default())
+        .insert(Collidable::sensor(mask))
+        .id()
+}
+
+// Something that despawns while inside a sensor counts as leaving it
+fn detect_triggers(
+    grid: Res<SpatialGrid>,
+    mut sensor_query: Query<(Entity, &mut Sensor, &Collidable, &Transform, &Sprite)>,
+    other_query: Query<(&Transform, &Sprite), Without<Sensor>>,
+    mut trigger_event: EventWriter<TriggerEvent>,
+) {
+    for (sensor_entity, mut sensor, collidable, transform, sprite) in sensor_query.iter_mut() {
+        let touching: Vec<Entity> = grid
+            .nearby(
+                transform.translation.truncate(),
+                sprite.size,
+                collidable.mask,
+            )
+            .into_iter()
+            .filter(|other| {
+                other_query
+                    .get(*other)
+                    .map_or(false, |(other_transform, other_sprite)| {
+                        collide_wrapped(
+                            transform.translation,
+                            sprite.size,
+                            other_transform.translation,
+                            other_sprite.size,
+                        )
+                        .is_some()
+                    })
+            })
+            .collect();
+
+        for other in sensor.inside.iter() {
+            if !touching.contains(other) {
+                trigger_event.send(TriggerEvent {
+                    sensor: sensor_entity,
+                    other: *other,
+                    trigger: Trigger::Exited,
+                });
+            }
+        }
+
+        for other in touching.iter() {
+            if !sensor.inside.contains(other) {
+                trigger_event.send(TriggerEvent {
+                    sensor: sensor_entity,
+                    other: *other,
+                    trigger: Trigger::Entered,
+                });
+            }
+        }
+
+        sensor.inside = touching;
+    }
+}
@@ -0,0 +1,190 @@
+// TURRET CODE
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::combo::Combo;
+use super::factions::{CollisionEvent, Damage, Faction, DETECT_COLLISIONS};
+use super::game_rng::GameRng;
+use super::game_time::GameTime;
+use super::player::{Dying, Player};
+use super::projectiles::{spawn_bullet, Bullet, BULLET_DAMAGE};
+use super::reset_game::{Resettable, RunScoped};
+use super::rules::GameRules;
+use super::waves::WaveManager;
+use crate::graphics::score::{Score, ScoreEvent, AWARD_POINTS};
+use crate::{AppState, Collidable, WINDOWHEIGHT, WINDOWWIDTH};
+
+// Seconds between turrets showing up
+const TURRET_INTERVAL: f32 = 25.0;
+// Seconds between a turret's shots
+const TURRET_COOLDOWN: f32 = 2.0;
+const MAX_TURRETS: usize = 2;
+const TURRET_SIZE: f32 = 36.0;
+// Slow enough to dodge in 'pixels/second'
+const TURRET_BULLET_SPEED: f32 = 250.0;
+const TURRET_POINTS: u32 = 50;
+// How far from the edge of the screen turrets are placed
+const SPAWN_MARGIN: f32 = 100.0;
+
+pub struct TurretPlugin;
+
+impl Plugin for TurretPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(TurretTimer(Timer::from_seconds(TURRET_INTERVAL, true)))
+            .reset_with(|turret_timer: &mut TurretTimer| turret_timer.0.reset())
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(spawn_turrets.system())
+                    .with_system(aim_and_fire.system())
+                    .with_system(
+                        shoot_turrets
+                            .system()
+                            .label(AWARD_POINTS)
+                            .after(DETECT_COLLISIONS),
+                    ),
+            );
+    }
+}
+
+struct TurretTimer(Timer);
+
+// Sits still, turns to face the closest player and fires on a cooldown
+pub struct Turret {
+    cooldown: Timer,
+}
+
+// Only during a wave, never too close to a player and never too many
+#[allow(clippy::too_many_arguments)]
+fn spawn_turrets(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut turret_timer: ResMut<TurretTimer>,
+    waves: Res<WaveManager>,
+    rules: Res<GameRules>,
+    mut game_rng: ResMut<GameRng>,
+    time: Res<GameTime>,
+    turret_query: Query<&Turret>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    if !turret_timer.0.tick(time.delta()).just_finished() || !waves.is_active() {
+        return;
+    }
+
+    if turret_query.iter().count() >= MAX_TURRETS {
+        return;
+    }
+
+    let half_width = WINDOWWIDTH / 2.0 - SPAWN_MARGIN;
+    let half_height = WINDOWHEIGHT / 2.0 - SPAWN_MARGIN;
+
+    // A handful of tries, if they are all too close it waits for the next one
+    let position = (0..10)
+        .map(|_| {
+            Vec2::new(
+                game_rng.gen_range(-half_width..half_width),
+                game_rng.gen_range(-half_height..half_height),
+            )
+        })
+        .find(|position| {
+            player_query.iter().all(|transform| {
+                transform.translation.truncate().distance(*position) >= rules.safe_spawn_radius
+            })
+        });
+
+    let position = match position {
+        Some(position) => position,
+        None => return,
+    };
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(Vec2::new(TURRET_SIZE, TURRET_SIZE)),
+            material: materials.add(Color::rgb(0.8, 0.25, 0.25).into()),
+            transform: Transform::from_xyz(position.x, position.y, 1.0),
+            ..Default::default()
+        })
+        // The first shot waits a full cooldown so the player can notice it
+        .insert(Turret {
+            cooldown: Timer::from_seconds(TURRET_COOLDOWN, true),
+        })
+        .insert(Faction::Hostile)
+        .insert(Damage(1))
+        .insert(Collidable::turret())
+        .insert(RunScoped);
+}
+
+fn aim_and_fire(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<GameTime>,
+    mut turret_query: Query<(&mut Turret, &mut Transform), Without<Player>>,
+    player_query: Query<&Transform, (With<Player>, Without<Dying>)>,
+) {
+    for (mut turret, mut transform) in turret_query.iter_mut() {
+        let position = transform.translation.truncate();
+        let closest = player_query
+            .iter()
+            .map(|player_transform| player_transform.translation.truncate() - position)
+            .filter(|to_player| *to_player != Vec2::ZERO)
+            .min_by(|a, b| a.length().total_cmp(&b.length()));
+
+        let to_player = match closest {
+            Some(to_player) => to_player.normalize(),
+            None => continue,
+        };
+
+        transform.rotation = Quat::from_rotation_z(to_player.y.atan2(to_player.x));
+
+        if turret.cooldown.tick(time.delta()).just_finished() {
+            spawn_bullet(
+                &mut commands,
+                &mut materials,
+                transform.translation,
+                to_player * TURRET_BULLET_SPEED,
+                None,
+                Faction::Hostile,
+                BULLET_DAMAGE,
+            );
+        }
+    }
+}
+
+// A single player bullet takes a turret out,
+// the bullet itself is cleaned up with the other bullet hits
+fn shoot_turrets(
+    mut commands: Commands,
+    score: Res<Score>,
+    combo: Res<Combo>,
+    mut collision_event: EventReader<CollisionEvent>,
+    bullet_query: Query<&Bullet>,
+    turret_query: Query<&Transform, With<Turret>>,
+    mut score_event: EventWriter<ScoreEvent>,
+) {
+    let mut destroyed: Vec<Entity> = Vec::new();
+
+    for event in collision_event.iter() {
+        if destroyed.contains(&event.target) {
+            continue;
+        }
+
+        let (bullet, turret_transform) = match (
+            bullet_query.get(event.attacker),
+            turret_query.get(event.target),
+        ) {
+            (Ok(bullet), Ok(turret_transform)) => (bullet, turret_transform),
+            _ => continue,
+        };
+
+        destroyed.push(event.target);
+        commands.entity(event.target).despawn();
+
+        if score.active {
+            score_event.send(ScoreEvent {
+                points: TURRET_POINTS * combo.multiplier(),
+                position: turret_transform.translation,
+                player: bullet.owner,
+            });
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// TWITCH CODE
+
+use bevy::prelude::*;
+use rand::seq::IteratorRandom;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+use std::thread;
+
+use super::blocks::SpawnBlockEvent;
+use super::game_time::GameTime;
+use super::mutators::{ActiveMutators, Mutator};
+use super::player::Player;
+use super::powerups::Shield;
+use super::spawning::SpawnInfo;
+use crate::AppState;
+
+const TWITCH_IRC_ADDRESS: &str = "irc.chat.twitch.tv:6667";
+// Read only chat access doesn't need an account
+const ANONYMOUS_NICK: &str = "justinfan31415";
+// Seconds chat has to vote before the winner happens
+const VOTE_SECONDS: f32 = 30.0;
+const FLIP_SECONDS: f32 = 5.0;
+// How many blocks chat's wave spawns at once
+const CHAT_WAVE_SIZE: usize = 5;
+
+pub struct TwitchPlugin;
+
+impl Plugin for TwitchPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(ChatConnection::connect())
+            .init_resource::<ChatVotes>()
+            .add_system(read_chat.system())
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(finish_vote.system())
+                    .with_system(unflip_controls.system()),
+            );
+    }
+}
+
+// The things chat can vote for, typed in chat as the command
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChatEvent {
+    Wave,
+    Shield,
+    Flip,
+}
+
+impl ChatEvent {
+    pub const ALL: [ChatEvent; 3] = [ChatEvent::Wave, ChatEvent::Shield, ChatEvent::Flip];
+
+    pub fn command(self) -> &'static str {
+        match self {
+            ChatEvent::Wave => "!wave",
+            ChatEvent::Shield => "!shield",
+            ChatEvent::Flip => "!flip",
+        }
+    }
+}
+
+// Chat messages come in on a background thread
+struct ChatConnection {
+    messages: Option<Mutex<Receiver<(String, String)>>>,
+}
+
+impl ChatConnection {
+    // The channel to join comes from TWITCH_CHANNEL
+    fn connect() -> Self {
+        let channel_name = match std::env::var("TWITCH_CHANNEL") {
+            Ok(channel_name) => channel_name.to_lowercase(),
+            Err(_) => {
+                println!("Set TWITCH_CHANNEL to let chat vote");
+                return ChatConnection { messages: None };
+            }
+        };
+
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            if let Err(error) = run_chat(&channel_name, |user, message| {
+                // The game is gone once nobody is listening
+                sender.send((user, message)).is_ok()
+            }) {
+                println!("Lost the Twitch chat connection: {}", error);
+            }
+        });
+
+        ChatConnection {
+            messages: Some(Mutex::new(receiver)),
+        }
+    }
+}
+
+// Reads chat until the connection drops or `on_message` returns false
+fn run_chat(
+    channel_name: &str,
+    mut on_message: impl FnMut(String, String) -> bool,
+) -> std::io::Result<()> {
+    let mut stream = TcpStream::connect(TWITCH_IRC_ADDRESS)?;
+    write!(
+        stream,
+        "NICK {}\r\nJOIN #{}\r\n",
+        ANONYMOUS_NICK, channel_name
+    )?;
+
+    let reader = BufReader::new(stream.try_clone()?);
+    for line in reader.lines() {
+        let line = line?;
+
+        // Twitch drops the connection if pings go unanswered
+        if line.starts_with("PING") {
+            write!(stream, "{}\r\n", line.replacen("PING", "PONG", 1))?;
+            continue;
+        }
+
+        // :user!user@user.tmi.twitch.tv PRIVMSG #channel :message
+        if let Some((prefix, message)) = line.split_once(" PRIVMSG ") {
+            let user = prefix
+                .trim_start_matches(':')
+                .split('!')
+                .next()
+                .unwrap_or("");
+            let text = message.splitn(2, " :").nth(1).unwrap_or("");
+
+            if !on_message(user.to_string(), text.trim().to_string()) {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// The running tally, everyone gets one vote per round
+pub struct ChatVotes {
+    pub tally: [u32; 3],
+    voters: Vec<String>,
+    pub timer: Timer,
+    flip_timer: Option<Timer>,
+}
+
+impl Default for ChatVotes {
+    fn default() -> Self {
+        ChatVotes {
+            tally: [0; 3],
+            voters: Vec::new(),
+            timer: Timer::from_seconds(VOTE_SECONDS, true),
+            flip_timer: None,
+        }
+    }
+}
+
+// Messages are always drained, but only count during a run
+fn read_chat(
+    connection: Res<ChatConnection>,
+    app_state: Res<State<AppState>>,
+    mut votes: ResMut<ChatVotes>,
+) {
+    let messages = match &connection.messages {
+        Some(messages) => messages
+            .lock()
+            .expect("The chat receiver lock is poisoned."),
+        None => return,
+    };
+
+    for (user, message) in messages.try_iter() {
+        if *app_state.current() != AppState::InGame || votes.voters.contains(&user) {
+            continue;
+        }
+
+        if let Some(index) = ChatEvent::ALL
+            .iter()
+            .position(|event| message.eq_ignore_ascii_case(event.command()))
+        {
+            votes.tally[index] += 1;
+            votes.voters.push(user);
+        }
+    }
+}
+
+fn finish_vote(
+    mut commands: Commands,
+    mut votes: ResMut<ChatVotes>,
+    mut mutators: ResMut<ActiveMutators>,
+    mut spawn_event: EventWriter<SpawnBlockEvent>,
+    spawn_positions_query: Query<(Entity, &SpawnInfo)>,
+    player_query: Query<Entity, With<Player>>,
+    time: Res<GameTime>,
+) {
+    if !votes.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let winner = (0..ChatEvent::ALL.len())
+        .filter(|index| votes.tally[*index] > 0)
+        .max_by_key(|index| votes.tally[*index]);
+
+    votes.tally = [0; 3];
+    votes.voters.clear();
+
+    match winner.map(|index| ChatEvent::ALL[index]) {
+        Some(ChatEvent::Wave) => {
+            let free_positions = spawn_positions_query
+                .iter()
+                .filter(|(_entity, spawn_position)| !spawn_position.spawned)
+                .map(|(entity, _spawn_position)| entity)
+                .choose_multiple(&mut rand::thread_rng(), CHAT_WAVE_SIZE);
+
+            for entity in free_positions {
+                spawn_event.send(SpawnBlockEvent(entity));
+            }
+        }
+        Some(ChatEvent::Shield) => {
+            for entity in player_query.iter() {
+                commands.entity(entity).insert(Shield);
+            }
+        }
+        Some(ChatEvent::Flip) => {
+            // Only undo the flip later if chat was the one that turned it on
+            if !mutators.has(Mutator::MirrorControls) {
+                mutators.toggle(Mutator::MirrorControls);
+                votes.flip_timer = Some(Timer::from_seconds(FLIP_SECONDS, false));
+            }
+        }
+        None => (),
+    }
+}
+
+fn unflip_controls(
+    mut votes: ResMut<ChatVotes>,
+    mut mutators: ResMut<ActiveMutators>,
+    time: Res<GameTime>,
+) {
+    let finished = match &mut votes.flip_timer {
+        Some(timer) => timer.tick(time.delta()).finished(),
+        None => false,
+    };
+
+    if finished {
+        votes.flip_timer = None;
+        if mutators.has(Mutator::MirrorControls) {
+            mutators.toggle(Mutator::MirrorControls);
+        }
+    }
+}
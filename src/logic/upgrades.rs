@@ -0,0 +1,167 @@
+// UPGRADE CODE
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use std::fmt;
+
+use super::daily::DailyChallenge;
+use super::game_rng::GameRng;
+use super::reset_game::Resettable;
+use super::stats::RunStats;
+use super::time_scale::RampTimeScaleEvent;
+use crate::AppState;
+
+// An upgrade choice is offered every this many seconds survived
+const UPGRADE_INTERVAL_SECONDS: f32 = 30.0;
+const CARDS_PER_OFFER: usize = 3;
+
+pub struct UpgradePlugin;
+
+impl Plugin for UpgradePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<RunUpgrades>()
+            .reset_on_restart::<RunUpgrades>()
+            .init_resource::<UpgradeOffer>()
+            .add_event::<PickUpgradeEvent>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(offer_upgrades.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::ChoosingUpgrade).with_system(pick_upgrade.system()),
+            );
+    }
+}
+
+// Every upgrade that can show up on a card
+#[derive(Clone, Copy, PartialEq)]
+pub enum Upgrade {
+    MoveSpeed,
+    TeleportDistance,
+    SlowBlocks,
+    ScoreBonus,
+    DashCooldown,
+    BulletDamage,
+    ExtraProjectile,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 7] = [
+        Upgrade::MoveSpeed,
+        Upgrade::TeleportDistance,
+        Upgrade::SlowBlocks,
+        Upgrade::ScoreBonus,
+        Upgrade::DashCooldown,
+        Upgrade::BulletDamage,
+        Upgrade::ExtraProjectile,
+    ];
+}
+
+impl fmt::Display for Upgrade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Upgrade::MoveSpeed => write!(f, "+10% move speed"),
+            Upgrade::TeleportDistance => write!(f, "+20% teleport distance"),
+            Upgrade::SlowBlocks => write!(f, "-5% block speed"),
+            Upgrade::ScoreBonus => write!(f, "+10% score"),
+            Upgrade::DashCooldown => write!(f, "-20% dash cooldown"),
+            Upgrade::BulletDamage => write!(f, "+1 bullet damage"),
+            Upgrade::ExtraProjectile => write!(f, "+1 projectile"),
+        }
+    }
+}
+
+// The upgrades picked so far this run, they all stack
+#[derive(Default)]
+pub struct RunUpgrades {
+    pub picks: Vec<Upgrade>,
+    offers_made: u32,
+}
+
+impl RunUpgrades {
+    fn stacks(&self, upgrade: Upgrade) -> i32 {
+        self.picks.iter().filter(|pick| **pick == upgrade).count() as i32
+    }
+
+    pub fn move_speed_multiplier(&self) -> f32 {
+        1.1_f32.powi(self.stacks(Upgrade::MoveSpeed))
+    }
+
+    pub fn teleport_multiplier(&self) -> f32 {
+        1.2_f32.powi(self.stacks(Upgrade::TeleportDistance))
+    }
+
+    pub fn block_speed_multiplier(&self) -> f32 {
+        0.95_f32.powi(self.stacks(Upgrade::SlowBlocks))
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        1.1_f32.powi(self.stacks(Upgrade::ScoreBonus))
+    }
+
+    // Shared by teleporting and dashing
+    pub fn dash_cooldown_multiplier(&self) -> f32 {
+        0.8_f32.powi(self.stacks(Upgrade::DashCooldown))
+    }
+
+    pub fn bonus_damage(&self) -> u32 {
+        self.stacks(Upgrade::BulletDamage) as u32
+    }
+
+    // Bullets fired with every shot
+    pub fn projectiles(&self) -> u32 {
+        1 + self.stacks(Upgrade::ExtraProjectile) as u32
+    }
+}
+
+// The cards on offer right now
+#[derive(Default)]
+pub struct UpgradeOffer(pub Vec<Upgrade>);
+
+// Sent by the card screen with the index of the picked card
+pub struct PickUpgradeEvent(pub usize);
+
+// Pause the run and deal out some cards every so often.
+// None in the daily, picks would make everyone's run different.
+fn offer_upgrades(
+    daily: Res<DailyChallenge>,
+    run_stats: Res<RunStats>,
+    mut run_upgrades: ResMut<RunUpgrades>,
+    mut upgrade_offer: ResMut<UpgradeOffer>,
+    mut game_rng: ResMut<GameRng>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if daily.is_active() {
+        return;
+    }
+
+    let offers_due = (run_stats.survival_seconds / UPGRADE_INTERVAL_SECONDS) as u32;
+    if offers_due <= run_upgrades.offers_made {
+        return;
+    }
+
+    run_upgrades.offers_made = offers_due;
+    upgrade_offer.0 = Upgrade::ALL
+        .choose_multiple(&mut *game_rng, CARDS_PER_OFFER)
+        .cloned()
+        .collect();
+
+    // Pushing keeps the run around underneath the card screen
+    let _ = app_state.push(AppState::ChoosingUpgrade);
+}
+
+fn pick_upgrade(
+    mut pick_upgrade_event: EventReader<PickUpgradeEvent>,
+    upgrade_offer: Res<UpgradeOffer>,
+    mut run_upgrades: ResMut<RunUpgrades>,
+    mut app_state: ResMut<State<AppState>>,
+    mut ramp_event: EventWriter<RampTimeScaleEvent>,
+) {
+    if let Some(event) = pick_upgrade_event.iter().next() {
+        if let Some(upgrade) = upgrade_offer.0.get(event.0) {
+            run_upgrades.picks.push(*upgrade);
+
+            let _ = app_state.pop();
+            ramp_event.send(RampTimeScaleEvent);
+        }
+    }
+}
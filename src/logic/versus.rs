@@ -0,0 +1,174 @@
+// VERSUS CODE
+
+use bevy::prelude::*;
+
+use super::blocks::{SpawnWarning, WarnSpawnEvent};
+use super::game_time::GameTime;
+use super::player::{ControlScheme, PlayerDeathEvent};
+use super::reset_game::Resettable;
+use super::spawning::SpawnInfo;
+use super::stats::RunStats;
+use crate::graphics::camera::CameraRig;
+use crate::AppState;
+
+// What launching a block costs, out of a budget that refills over time
+const BLOCK_COST: f32 = 3.0;
+const MAX_BUDGET: f32 = 10.0;
+const BUDGET_PER_SECOND: f32 = 1.0;
+// Seconds between launches however much budget there is
+const LAUNCH_COOLDOWN: f32 = 0.75;
+
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<VersusMatch>()
+            .reset_with(|versus: &mut VersusMatch| versus.start_round())
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(clear_versus.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(launch_blocks.system()),
+            )
+            .add_system(record_round.system());
+    }
+}
+
+// Two players on one computer. One dodges on the keyboard while the other
+// clicks spawn positions to launch blocks at them, then they swap and
+// whoever survived longer wins. Nothing spawns on its own in versus.
+pub struct VersusMatch {
+    pub active: bool,
+    // 0 while player one dodges, 1 while player two does
+    pub round: usize,
+    // How long each player survived while dodging
+    pub times: [Option<f32>; 2],
+    pub budget: f32,
+    cooldown: Timer,
+    // The dodger has to be on the keyboard, whatever the player had picked
+    // is put back once the match is left
+    previous_scheme: Option<ControlScheme>,
+}
+
+impl Default for VersusMatch {
+    fn default() -> Self {
+        VersusMatch {
+            active: false,
+            round: 0,
+            times: [None; 2],
+            budget: MAX_BUDGET,
+            cooldown: Timer::from_seconds(LAUNCH_COOLDOWN, false),
+            previous_scheme: None,
+        }
+    }
+}
+
+impl VersusMatch {
+    pub fn start(&mut self, control_scheme: &mut ControlScheme) {
+        *self = VersusMatch {
+            active: true,
+            previous_scheme: Some(*control_scheme),
+            ..Default::default()
+        };
+        *control_scheme = ControlScheme::Keyboard;
+    }
+
+    // The next round of the match, or a new match once both have dodged
+    fn start_round(&mut self) {
+        if self.times.iter().all(|time| time.is_some()) {
+            self.times = [None; 2];
+        }
+
+        self.round = self.times.iter().filter(|time| time.is_some()).count();
+        self.budget = MAX_BUDGET;
+        self.cooldown.reset();
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.times.iter().all(|time| time.is_some())
+    }
+
+    // None until both have played, or for a tie
+    pub fn winner(&self) -> Option<usize> {
+        match self.times {
+            [Some(first), Some(second)] if first > second => Some(0),
+            [Some(first), Some(second)] if second > first => Some(1),
+            _ => None,
+        }
+    }
+}
+
+fn clear_versus(mut versus: ResMut<VersusMatch>, mut control_scheme: ResMut<ControlScheme>) {
+    if let Some(previous_scheme) = versus.previous_scheme {
+        *control_scheme = previous_scheme;
+    }
+    *versus = VersusMatch::default();
+}
+
+// A click launches a block from the free spawn position closest to the
+// cursor, with the usual warning first so the dodger can react
+fn launch_blocks(
+    mut versus: ResMut<VersusMatch>,
+    mouse_input: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    time: Res<GameTime>,
+    camera_rig: Res<CameraRig>,
+    spawn_positions_query: Query<(Entity, &SpawnInfo)>,
+    warning_query: Query<&SpawnWarning>,
+    mut warn_event: EventWriter<WarnSpawnEvent>,
+) {
+    if !versus.active {
+        return;
+    }
+
+    versus.cooldown.tick(time.delta());
+    versus.budget = (versus.budget + BUDGET_PER_SECOND * time.delta_seconds()).min(MAX_BUDGET);
+
+    if !mouse_input.just_pressed(MouseButton::Left)
+        || !versus.cooldown.finished()
+        || versus.budget < BLOCK_COST
+    {
+        return;
+    }
+
+    let cursor = match camera_rig.cursor_world_position(&windows) {
+        Some(cursor) => cursor,
+        None => return,
+    };
+
+    let closest = spawn_positions_query
+        .iter()
+        .filter(|(entity, spawn_position)| {
+            !spawn_position.spawned
+                && !warning_query
+                    .iter()
+                    .any(|warning| warning.spawn_position == *entity)
+        })
+        .map(|(entity, spawn_position)| {
+            let location = Vec2::new(
+                spawn_position.spawn_location.0 as f32,
+                spawn_position.spawn_location.1 as f32,
+            );
+            (entity, location.distance(cursor))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    if let Some((entity, _distance)) = closest {
+        warn_event.send(WarnSpawnEvent(entity));
+        versus.budget -= BLOCK_COST;
+        versus.cooldown.reset();
+    }
+}
+
+fn record_round(
+    mut player_death_event: EventReader<PlayerDeathEvent>,
+    mut versus: ResMut<VersusMatch>,
+    run_stats: Res<RunStats>,
+) {
+    for _event in player_death_event.iter() {
+        if versus.active {
+            let round = versus.round;
+            versus.times[round] = Some(run_stats.survival_seconds);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// WAVES CODE
+
+use bevy::prelude::*;
+use rand::seq::IteratorRandom;
+
+use super::blocks::{Block, SpawnBlockEvent, SpawnWarning};
+use super::game_rng::GameRng;
+use super::game_time::GameTime;
+use super::reset_game::Resettable;
+use super::spawning::SpawnInfo;
+use super::versus::VersusMatch;
+use crate::AppState;
+
+// How long the blocks of a wave are in play before the screen clears
+const WAVE_SECONDS: f32 = 20.0;
+// The quiet time between waves where the banner shows
+const INTERMISSION_SECONDS: f32 = 3.0;
+// The first wave matches the old starting six blocks
+const FIRST_WAVE_COUNT: usize = 6;
+const EXTRA_BLOCKS_PER_WAVE: usize = 2;
+const SPEED_PER_WAVE: f32 = 0.1;
+const MAX_WAVE_SPEED: f32 = 2.0;
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<WaveClearedEvent>()
+            .init_resource::<WaveManager>()
+            .reset_on_restart::<WaveManager>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame).with_system(update_waves.system()),
+            );
+    }
+}
+
+// Sent when a wave's time is up and its blocks are cleared away,
+// every one of them was dodged
+pub struct WaveClearedEvent {
+    pub blocks: u32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WavePhase {
+    // The screen is clear and the next wave is about to come in
+    Intermission,
+    Active,
+}
+
+pub struct WaveManager {
+    // The wave that is running, or coming up during an intermission
+    pub wave: u32,
+    pub phase: WavePhase,
+    timer: Timer,
+}
+
+impl Default for WaveManager {
+    fn default() -> Self {
+        WaveManager {
+            wave: 1,
+            phase: WavePhase::Intermission,
+            timer: Timer::from_seconds(INTERMISSION_SECONDS, false),
+        }
+    }
+}
+
+impl WaveManager {
+    pub fn is_active(&self) -> bool {
+        self.phase == WavePhase::Active
+    }
+
+    // How many blocks come in together at the start of the wave
+    pub fn block_count(&self) -> usize {
+        FIRST_WAVE_COUNT + EXTRA_BLOCKS_PER_WAVE * (self.wave as usize - 1)
+    }
+
+    // Multiplies the speed of every block spawned during the wave
+    pub fn speed_multiplier(&self) -> f32 {
+        (1.0 + SPEED_PER_WAVE * (self.wave - 1) as f32).min(MAX_WAVE_SPEED)
+    }
+}
+
+// Alternates between spawning a wave and clearing the screen for the next one.
+// Versus has no waves, the blocks come from the other player.
+#[allow(clippy::too_many_arguments)]
+fn update_waves(
+    mut commands: Commands,
+    mut waves: ResMut<WaveManager>,
+    time: Res<GameTime>,
+    block_query: Query<(Entity, Option<&Block>), Or<(With<Block>, With<SpawnWarning>)>>,
+    mut spawn_positions_query: Query<(Entity, &mut SpawnInfo)>,
+    mut spawn_event: EventWriter<SpawnBlockEvent>,
+    mut wave_cleared_event: EventWriter<WaveClearedEvent>,
+    mut game_rng: ResMut<GameRng>,
+    versus: Res<VersusMatch>,
+) {
+    if versus.active || !waves.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    match waves.phase {
+        WavePhase::Intermission => {
+            waves.phase = WavePhase::Active;
+            waves.timer = Timer::from_seconds(WAVE_SECONDS, false);
+
+            // The whole wave spawns at once from different spawn positions
+            let free_positions = spawn_positions_query
+                .iter_mut()
+                .filter(|(_entity, spawn_position)| !spawn_position.spawned)
+                .map(|(entity, _spawn_position)| entity)
+                .choose_multiple(&mut *game_rng, waves.block_count());
+
+            for entity in free_positions {
+                spawn_event.send(SpawnBlockEvent(entity));
+            }
+        }
+        WavePhase::Active => {
+            // Blocks that were about to come in go too, but weren't dodged
+            let mut blocks = 0;
+            for (entity, block) in block_query.iter() {
+                blocks += block.is_some() as u32;
+                commands.entity(entity).despawn();
+            }
+            wave_cleared_event.send(WaveClearedEvent { blocks });
+
+            waves.wave += 1;
+            waves.phase = WavePhase::Intermission;
+            waves.timer = Timer::from_seconds(INTERMISSION_SECONDS, false);
+
+            for (_entity, mut spawn_position) in spawn_positions_query.iter_mut() {
+                spawn_position.spawned = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// WEEKLY CHALLENGE CODE
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::daily::today;
+use super::game_rng::GameRng;
+use super::mutators::{ActiveMutators, Mutator};
+use super::rules::{DifficultyPreset, GameRules, PlayerSetup};
+use crate::settings::GameSettings;
+use crate::AppState;
+
+// Built into the game so it works offline
+//
+// TODO
+// Fetch the schedule from a server when online so new weeks can be added
+// without a release. There is no HTTP client in the game yet.
+const SCHEDULE: &str = include_str!("../../assets/weekly_challenges.ron");
+// 1970-01-01 was a Thursday, this moves the start of the week to Monday
+const DAYS_FROM_MONDAY: u64 = 3;
+
+pub struct WeeklyPlugin;
+
+impl Plugin for WeeklyPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(WeeklySchedule::load())
+            .init_resource::<WeeklyChallenge>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(clear_weekly.system()),
+            );
+    }
+}
+
+// A fixed seed played with fixed mutators for a whole week
+#[derive(Clone, Deserialize)]
+pub struct WeeklyEntry {
+    pub seed: u64,
+    pub mutators: Vec<Mutator>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct WeeklySchedule {
+    pub weeks: Vec<WeeklyEntry>,
+}
+
+impl WeeklySchedule {
+    // A broken schedule means there is no weekly challenge
+    fn load() -> Self {
+        ron::de::from_str(SCHEDULE).unwrap_or_else(|error| {
+            println!("Could not read the weekly challenges: {}", error);
+            WeeklySchedule::default()
+        })
+    }
+
+    // The schedule loops once every week in it has been played
+    pub fn entry(&self, week: u64) -> Option<&WeeklyEntry> {
+        if self.weeks.is_empty() {
+            return None;
+        }
+
+        self.weeks.get((week % self.weeks.len() as u64) as usize)
+    }
+}
+
+// The weekly challenge being played, restarting replays the same week
+#[derive(Default)]
+pub struct WeeklyChallenge {
+    // Weeks since the first Monday before 1970-01-01 in UTC
+    pub week: Option<u64>,
+    pub entry: Option<WeeklyEntry>,
+}
+
+impl WeeklyChallenge {
+    pub fn is_active(&self) -> bool {
+        self.entry.is_some()
+    }
+
+    // Normal difficulty with the week's mutators, the player's own are put
+    // aside until the main menu. Returns false when the schedule is empty
+    // and there is nothing to play.
+    pub fn start(
+        &mut self,
+        schedule: &WeeklySchedule,
+        game_rng: &mut GameRng,
+        rules: &mut GameRules,
+        mutators: &mut ActiveMutators,
+        setup: &mut PlayerSetup,
+        settings: &GameSettings,
+    ) -> bool {
+        let week = this_week();
+        let entry = match schedule.entry(week) {
+            Some(entry) => entry.clone(),
+            None => return false,
+        };
+
+        setup.set_aside(rules, mutators);
+        game_rng.set_fixed_seed(Some(entry.seed));
+        *rules = GameRules::from_preset(DifficultyPreset::Normal, settings);
+        mutators.0 = entry.mutators.clone();
+        self.week = Some(week);
+        self.entry = Some(entry);
+        true
+    }
+}
+
+pub fn this_week() -> u64 {
+    (today() + DAYS_FROM_MONDAY) / 7
+}
+
+// Going back to the main menu means normal runs again, the rules and
+// mutators the weekly put aside come back with the player setup
+fn clear_weekly(mut weekly: ResMut<WeeklyChallenge>) {
+    *weekly = WeeklyChallenge::default();
+}
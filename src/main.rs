@@ -1,84 +1,72 @@
-use bevy::app::AppExit;
-use bevy::input::system::exit_on_esc_system;
 use bevy::prelude::*;
-use bevy_kira_audio::{Audio, AudioPlugin};
+use bevy_kira_audio::AudioPlugin;
 
-mod graphics;
-mod logic;
-
-const WINDOWHEIGHT: f32 = 1200.0;
-const WINDOWWIDTH: f32 = 1500.0;
-const BLOCKSIZEX: f32 = 40.0;
-const BLOCKSIZEY: f32 = 40.0;
+use bevy_block_game::headless;
+#[cfg(debug_assertions)]
+use bevy_block_game::logic::custom_start::CustomStart;
+use bevy_block_game::logic::spatial_grid;
+use bevy_block_game::settings::GameSettings;
+use bevy_block_game::GamePlugin;
 
 fn main() {
-    App::build()
-        .insert_resource(WindowDescriptor {
-            title: "Asteroids Clone".to_string(),
-            width: WINDOWWIDTH,
-            height: WINDOWHEIGHT,
-            vsync: true,
-            ..Default::default()
-        })
-        .add_plugins(DefaultPlugins)
-        .add_plugin(AudioPlugin)
-        .add_startup_system(setup.system())
-        .add_startup_system(render_background.system())
-        .add_startup_system(start_background_audio.system())
-        // .add_startup_system(play_music.system())
-        // Turn on to see framerate, also import line above
-        // .add_plugin(FrameTimeDiagnosticsPlugin::default())
-        .add_plugin(logic::spawning::SpawningPlugin)
-        .add_plugin(logic::player::PlayerPlugin)
-        .add_plugin(logic::blocks::BlocksPlugin)
-        .add_plugin(logic::reset_game::ResetGamePlugin)
-        .add_plugin(graphics::score::ScorePlugin)
-        .add_plugin(graphics::text::TextPlugin)
-        .add_system(exit_on_esc_system.system())
-        .run();
-}
+    // Times collision checks with and without the spatial grid, no window needed
+    if std::env::args().any(|arg| arg == "--bench-collisions") {
+        spatial_grid::benchmark();
+        return;
+    }
 
-fn setup(mut commands: Commands) {
-    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
-    commands.spawn_bundle(UiCameraBundle::default());
-}
+    let args: Vec<String> = std::env::args().collect();
+    let mut seed: Option<u64> = None;
+    if let Some(index) = args.iter().position(|arg| arg == "--seed") {
+        seed = args.get(index + 1).and_then(|seed| seed.parse().ok());
+        if seed.is_none() {
+            println!("--seed needs a number, playing unseeded");
+        }
+    }
 
-fn render_background(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-) {
-    let background_image: Handle<Texture> = asset_server.load("textures/bg.png");
+    // Plays a run without a window and prints what happened,
+    // --headless 30 for 30 seconds
+    if let Some(index) = args.iter().position(|arg| arg == "--headless") {
+        let seconds = args
+            .get(index + 1)
+            .and_then(|seconds| seconds.parse().ok())
+            .unwrap_or(60.0);
+        println!("{:?}", headless::simulate(seconds, seed));
+        return;
+    }
 
-    // Width of standard image in pixels is 272
-    let background_width = WINDOWWIDTH / 272.0;
-    // Width of standard image in pixels is 160
-    let background_height = WINDOWHEIGHT / 160.0;
+    let mut settings = GameSettings::load();
+    if seed.is_some() {
+        settings.seed = seed;
+    }
 
-    commands.spawn_bundle(SpriteBundle {
-        material: materials.add(background_image.into()),
-        transform: Transform::from_scale(Vec3::new(background_width, background_height, 0.0)),
+    // NOTE
+    // Vsync queues up frames and adds input latency. Bevy only offers
+    // vsync on or off (immediate present), there is no mailbox mode yet.
+    let vsync = settings.vsync && !std::env::args().any(|arg| arg == "--no-vsync");
 
+    let mut app = App::build();
+    app.insert_resource(WindowDescriptor {
+        title: "Asteroids Clone".to_string(),
+        width: settings.window_width,
+        height: settings.window_height,
+        vsync,
         ..Default::default()
-    });
-}
+    })
+    // The game reads these instead of loading its own
+    .insert_resource(settings)
+    .add_plugins(DefaultPlugins)
+    .add_plugin(AudioPlugin)
+    .add_plugin(GamePlugin);
 
-// This is called by the system
-#[allow(dead_code)]
-fn exit_system(mut exit: EventWriter<AppExit>) {
-    exit.send(AppExit);
-}
-
-fn start_background_audio(asset_server: Res<AssetServer>, audio: Res<Audio>) {
-    audio.play_looped(asset_server.load("sounds/bg_music.mp3"));
-}
+    // Debug builds can start a run partway in,
+    // --start-wave 5 --difficulty hard --powerup shield --blocks 10
+    #[cfg(debug_assertions)]
+    {
+        if let Some(custom_start) = CustomStart::from_args(&args) {
+            app.insert_resource(custom_start);
+        }
+    }
 
-#[derive(Clone, Copy)]
-pub enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
+    app.run();
 }
-
-struct Collidable;
@@ -0,0 +1,123 @@
+// SETTINGS CODE
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::logic::input_map::Action;
+use crate::logic::player::ActivationMode;
+use crate::logic::profile::save_directory;
+use crate::{WINDOWHEIGHT, WINDOWWIDTH};
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+// Tuning that can be changed without a rebuild, loaded once in main.
+// Any field left out of the file falls back to its default.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    // The size of the window in pixels. The playfield is always the same
+    // size in the world, the camera zooms to fit it into the window.
+    pub window_width: f32,
+    pub window_height: f32,
+    pub vsync: bool,
+    // Player movement speed in 'pixels/second'
+    pub player_speed: f32,
+    pub teleport_distance: f32,
+    // Seconds between runtime block spawns on normal difficulty,
+    // the other difficulties scale off of it
+    pub spawn_interval: f32,
+    // The difficulty ramp, block speed and spawn frequency are multiplied
+    // by a rate that goes from the initial to the max one over this many
+    // seconds of survival
+    pub difficulty_initial_rate: f32,
+    pub difficulty_max_rate: f32,
+    pub difficulty_ramp_seconds: f32,
+    // Blocks bounce off each other instead of passing through.
+    // Off by default since it makes the field harder to read.
+    pub block_bounce: bool,
+    // Every run plays out the same spawns with a seed, --seed on the
+    // command line overrides it
+    pub seed: Option<u64>,
+    // Feedback from the menu is also posted here when set,
+    // it is always saved next to the settings either way
+    pub feedback_endpoint: Option<String>,
+    // A code from assets/locales.ron, picks the font the text uses
+    pub language: String,
+    // Draws the playfield with big square pixels, scaled up by a whole
+    // number so they all stay the same size
+    pub pixel_art: bool,
+    // The name runs go on the leaderboard under
+    pub username: String,
+    // Usernames shown by the leaderboard's friends filter
+    pub friends: Vec<String>,
+    // Tap, hold or toggle for the actions that can be held,
+    // the input map applies them
+    pub action_modes: HashMap<Action, ActivationMode>,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            window_width: WINDOWWIDTH,
+            window_height: WINDOWHEIGHT,
+            vsync: true,
+            player_speed: 300.0,
+            teleport_distance: 70.0,
+            spawn_interval: 2.0,
+            difficulty_initial_rate: 1.0,
+            difficulty_max_rate: 1.75,
+            difficulty_ramp_seconds: 180.0,
+            block_bounce: false,
+            seed: None,
+            feedback_endpoint: None,
+            language: "en".to_string(),
+            pixel_art: false,
+            username: "Player".to_string(),
+            friends: Vec::new(),
+            action_modes: HashMap::new(),
+        }
+    }
+}
+
+impl GameSettings {
+    // A missing file gets the defaults written out so there is something to edit,
+    // a broken one is left alone and the defaults are used
+    pub fn load() -> Self {
+        let path = save_directory().join(SETTINGS_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => ron::de::from_str(&contents).unwrap_or_else(|error| {
+                println!("Could not read the settings, using the defaults: {}", error);
+                GameSettings::default()
+            }),
+            Err(_) => {
+                let settings = GameSettings::default();
+                settings.save();
+                settings
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let directory = save_directory();
+        let contents = match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()) {
+            Ok(contents) => contents,
+            Err(error) => {
+                println!("Could not serialize the settings: {}", error);
+                return;
+            }
+        };
+
+        if let Err(error) = fs::create_dir_all(&directory)
+            .and_then(|_| fs::write(directory.join(SETTINGS_FILE), contents))
+        {
+            println!("Could not save the settings: {}", error);
+        }
+    }
+}
+
+// World units per window pixel, so the whole playfield fits in a window this size
+pub fn camera_scale(window_width: f32, window_height: f32) -> f32 {
+    (WINDOWWIDTH / window_width).max(WINDOWHEIGHT / window_height)
+}
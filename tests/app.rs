@@ -0,0 +1,74 @@
+use bevy::ecs::component::Component;
+use bevy::prelude::*;
+
+use bevy_block_game::graphics::score::Score;
+use bevy_block_game::headless::{advance, headless_app};
+use bevy_block_game::logic::blocks::Block;
+use bevy_block_game::logic::player::Player;
+use bevy_block_game::settings::GameSettings;
+use bevy_block_game::AppState;
+
+fn start_run(app: &mut App) {
+    advance(app);
+    if let Some(mut app_state) = app.world.get_resource_mut::<State<AppState>>() {
+        app_state.set(AppState::InGame).unwrap();
+    }
+    advance(app);
+}
+
+fn count<T: Component>(app: &mut App) -> usize {
+    app.world
+        .query_filtered::<Entity, With<T>>()
+        .iter(&app.world)
+        .count()
+}
+
+fn seeded_app() -> App {
+    headless_app(GameSettings {
+        seed: Some(1),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn builds_and_starts_loading() {
+    let mut app = seeded_app();
+    advance(&mut app);
+
+    let app_state = app.world.get_resource::<State<AppState>>().unwrap();
+    assert_eq!(*app_state.current(), AppState::Loading);
+}
+
+#[test]
+fn starting_a_run_spawns_one_player() {
+    let mut app = seeded_app();
+    start_run(&mut app);
+    advance(&mut app);
+
+    assert_eq!(count::<Player>(&mut app), 1);
+    assert_eq!(count::<Block>(&mut app), 0);
+}
+
+#[test]
+fn score_counts_up_while_the_player_is_alive() {
+    let mut app = seeded_app();
+    start_run(&mut app);
+    for _ in 0..60 {
+        advance(&mut app);
+    }
+
+    let score = app.world.get_resource::<Score>().unwrap();
+    assert!(score.survival_seconds > 0.5);
+}
+
+#[test]
+fn blocks_spawn_during_a_run() {
+    // The first spawn comes a couple of seconds in, after its warning
+    let mut app = seeded_app();
+    start_run(&mut app);
+    for _ in 0..60 * 10 {
+        advance(&mut app);
+    }
+
+    assert!(count::<Block>(&mut app) > 0);
+}
@@ -0,0 +1,21 @@
+use bevy_block_game::headless::simulate;
+
+// Seconds simulated by each test, long enough for blocks to start coming.
+// GameTime is stepped by hand so this doesn't take 20 real seconds.
+const SECONDS: f32 = 20.0;
+
+#[test]
+fn same_seed_plays_the_same_run() {
+    let first = simulate(SECONDS, Some(7));
+    let second = simulate(SECONDS, Some(7));
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn runs_at_the_fixed_timestep() {
+    let report = simulate(SECONDS, Some(7));
+
+    // One frame for startup on top of the ones simulated
+    assert_eq!(report.frames, (SECONDS * 60.0) as u32 + 1);
+}